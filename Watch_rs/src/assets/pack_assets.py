@@ -2,6 +2,7 @@
 import argparse
 import pathlib
 import re
+import struct
 import sys
 import zlib
 
@@ -16,7 +17,16 @@ def size_from_name(p: pathlib.Path):
         return None
     return int(m.group(1)), int(m.group(2))
 
-def compress_one(path: pathlib.Path, level: int, force: bool, overwrite: bool) -> bool:
+# Optional header understood by ui::decode_asset:
+#   magic "RWIM" | version u8 | reserved u8 | width u16 LE | height u16 LE | crc32 u32 LE
+ASSET_MAGIC = b"RWIM"
+ASSET_VERSION = 1
+
+def asset_header(w: int, h: int, data: bytes) -> bytes:
+    crc = zlib.crc32(data) & 0xFFFFFFFF
+    return ASSET_MAGIC + struct.pack("<BBHHI", ASSET_VERSION, 0, w, h, crc)
+
+def compress_one(path: pathlib.Path, level: int, force: bool, overwrite: bool, header: bool) -> bool:
     wh = size_from_name(path)
     if wh is None:
         print(f"skip: {path.name} (name must end with _<W>x<H>_rgb565_be.raw)")
@@ -35,6 +45,8 @@ def compress_one(path: pathlib.Path, level: int, force: bool, overwrite: bool) -
         return True
 
     comp = zlib.compress(data, level=level)
+    if header:
+        comp = asset_header(w, h, data) + comp
     out.write_bytes(comp)
     ratio = (len(comp) / len(data)) if len(data) else 1.0
     print(f"ok: {path.name} -> {out.name}  {len(data)} -> {len(comp)} bytes ({ratio:.2%})")
@@ -46,6 +58,7 @@ def main():
     ap.add_argument("-f", "--force", action="store_true", help="ignore size check (W*H*2) derived from filename")
     ap.add_argument("-o", "--overwrite", action="store_true", help="overwrite existing .zlib files")
     ap.add_argument("-r", "--recursive", action="store_true", help="recurse into subdirectories")
+    ap.add_argument("--header", action="store_true", help="prepend the RWIM header (size + crc32) checked on device")
     args = ap.parse_args()
 
     if args.level < 0 or args.level > 9:
@@ -62,7 +75,7 @@ def main():
     ok = 0
     for f in files:
         try:
-            if compress_one(f, args.level, args.force, args.overwrite):
+            if compress_one(f, args.level, args.force, args.overwrite, args.header):
                 ok += 1
         except Exception as e:
             print(f"fail: {f.name}: {e}")
@@ -0,0 +1,171 @@
+#!/usr/bin/env python3
+# Generates the status icon sprite atlas as BIG-endian RGB565 .raw (no pillow needed).
+# Grid is ATLAS_COLS x ATLAS_ROWS cells of CELL x CELL pixels; order must match ui::StatusIcon.
+# Run, then pack with: python3 pack_assets.py --header -o
+
+import pathlib
+import struct
+
+CELL = 24
+ATLAS_COLS = 4
+ATLAS_ROWS = 2
+SCALE = 2  # art below is 12x12, doubled to fill a cell
+
+PALETTE = {
+    ".": (0, 0, 0),
+    "W": (255, 255, 255),
+    "G": (139, 227, 8),  # omnitrix lime
+    "Y": (255, 200, 0),
+    "R": (255, 40, 40),
+    "B": (40, 120, 255),
+}
+
+ICONS = [
+    # battery full
+    [
+        "............",
+        "............",
+        "............",
+        "WWWWWWWWWW..",
+        "W........W..",
+        "W.GGGGGG.WW.",
+        "W.GGGGGG.WW.",
+        "W........W..",
+        "WWWWWWWWWW..",
+        "............",
+        "............",
+        "............",
+    ],
+    # battery half
+    [
+        "............",
+        "............",
+        "............",
+        "WWWWWWWWWW..",
+        "W........W..",
+        "W.YYY....WW.",
+        "W.YYY....WW.",
+        "W........W..",
+        "WWWWWWWWWW..",
+        "............",
+        "............",
+        "............",
+    ],
+    # battery low
+    [
+        "............",
+        "............",
+        "............",
+        "WWWWWWWWWW..",
+        "W........W..",
+        "W.R......WW.",
+        "W.R......WW.",
+        "W........W..",
+        "WWWWWWWWWW..",
+        "............",
+        "............",
+        "............",
+    ],
+    # bluetooth
+    [
+        ".....B......",
+        ".....BB.....",
+        ".....B.B....",
+        "..B..B..B...",
+        "...B.B.B....",
+        "....BBB.....",
+        "....BBB.....",
+        "...B.B.B....",
+        "..B..B..B...",
+        ".....B.B....",
+        ".....BB.....",
+        ".....B......",
+    ],
+    # alarm bell
+    [
+        ".....WW.....",
+        "....WWWW....",
+        "...WWWWWW...",
+        "...WWWWWW...",
+        "...WWWWWW...",
+        "...WWWWWW...",
+        "..WWWWWWWW..",
+        "..WWWWWWWW..",
+        ".WWWWWWWWWW.",
+        "............",
+        ".....WW.....",
+        "............",
+    ],
+    # clock warning (time not trusted)
+    [
+        "...YYYYYY...",
+        "..Y......Y..",
+        ".Y...Y....Y.",
+        "Y....Y.....Y",
+        "Y....Y.....Y",
+        "Y....YYY...Y",
+        "Y..........Y",
+        "Y..........Y",
+        ".Y........Y.",
+        "..Y......Y..",
+        "...YYYYYY...",
+        "............",
+    ],
+    # thermometer
+    [
+        ".....WW.....",
+        "....W..W....",
+        "....W..W....",
+        "....W..W....",
+        "....WRRW....",
+        "....WRRW....",
+        "....WRRW....",
+        "...WRRRRW...",
+        "...WRRRRW...",
+        "...WRRRRW...",
+        "....WWWW....",
+        "............",
+    ],
+    # moon (dimmed / always-on)
+    [
+        "....WWWW....",
+        "...WWW......",
+        "..WWW.......",
+        ".WWW........",
+        ".WWW........",
+        ".WWW........",
+        ".WWW........",
+        ".WWWW.......",
+        "..WWWW....W.",
+        "...WWWWWWW..",
+        "....WWWWW...",
+        "............",
+    ],
+]
+
+
+def rgb565_be(rgb):
+    r, g, b = rgb
+    return struct.pack(">H", ((r >> 3) << 11) | ((g >> 2) << 5) | (b >> 3))
+
+
+def main():
+    w = CELL * ATLAS_COLS
+    h = CELL * ATLAS_ROWS
+    px = [[(0, 0, 0)] * w for _ in range(h)]
+    for i, art in enumerate(ICONS):
+        ox = (i % ATLAS_COLS) * CELL
+        oy = (i // ATLAS_COLS) * CELL
+        for y, row in enumerate(art):
+            for x, ch in enumerate(row):
+                for sy in range(SCALE):
+                    for sx in range(SCALE):
+                        px[oy + y * SCALE + sy][ox + x * SCALE + sx] = PALETTE[ch]
+    out = b"".join(rgb565_be(c) for row in px for c in row)
+    path = pathlib.Path(__file__).parent / f"status_icons_{w}x{h}_rgb565_be.raw"
+    path.write_bytes(out)
+    print(f"ok: {path.name} ({len(out)} bytes)")
+
+
+if __name__ == "__main__":
+    main()
@@ -13,14 +13,29 @@ esp_bootloader_esp_idf::esp_app_desc!();
 use esp32s3_tests::{
     display::setup_display,
     input::{
-        handle_button_generic, handle_encoder_generic, handle_imu_int_generic, ButtonState,
-        ImuIntState, RotaryState,
+        encoder_accel_delta, handle_button_generic, handle_button_with_longpress,
+        handle_button_with_repeat, handle_encoder_switch_generic, handle_encoder_with_accel,
+        handle_imu_int_generic, ButtonState, ChordDetector, ImuIntState, RotaryState,
+        DEFAULT_DEBOUNCE_MS,
     },
-    qmi8658_imu::{Qmi8658, SmashDetector, DEFAULT_I2C_ADDR},
+    qmi8658_imu::{
+        DoubleTapDetector, FreefallDetector, OrientationDetector, Qmi8658, ShakeDetector,
+        SmashDetector, SmashKind, TapAction, TapCount, TapDetector, TapNavigator, WristEvent,
+        WristRaiseDetector, DEFAULT_I2C_ADDR,
+    },
+    sched::{monotonic_us, LoopBudget, LoopTask},
+    settings::{load_settings, save_settings},
     ui::{
-        brightness_adjust, clear_all_caches, clock_now_seconds_u32, get_clock_seconds,
-        precache_asset, set_clock_seconds, update_ui, AssetId, Dialog, MainMenuState, Page,
-        SettingsMenuState, UiState, WatchAppState,
+        always_on_enabled, auto_rotate_enabled, brightness_adjust, brightness_limits,
+        brightness_set_limits, brightness_set_pct, carousel_secs, clear_all_caches,
+        clock_now_seconds_u32, current_state, default_start_state, draw_fault_screen,
+        encoder_delta, force_full_repaint, get_clock_seconds, precache_asset, resync_from_rtc,
+        sensor_health, sensor_health_set, set_clock_reliable, set_clock_seconds,
+        set_clock_seconds_checked, set_page, set_pixel_shift, set_state, smash_cal_report,
+        status_icon_set, tap_cadence, temperature_record, temperature_reset_minmax, update_state,
+        update_ui, wake_alarm_at, wake_sources, AssetError, AssetId, Dialog, FaultCode,
+        MainMenuState, Page, SensorHealth, SettingsMenuState, StatusIcon, UiState, WakeSources,
+        WatchAppState,
     },
     wiring::{init_board_pins, BoardPins},
 };
@@ -29,8 +44,12 @@ use esp32s3_tests::rtc_pcf85063::{
     datetime_is_valid, datetime_to_unix, unix_to_datetime, Pcf85063,
 };
 
+#[cfg(feature = "esp32s3-disp143Oled")]
+use esp32s3_tests::co5300::{Orientation, TeMode};
 #[cfg(feature = "esp32s3-disp143Oled")]
 use esp32s3_tests::display::TimerDelay;
+#[cfg(feature = "esp32s3-disp143Oled")]
+use esp32s3_tests::serial::{ImuStream, SerialCommand, SerialConsole};
 
 // Core imports
 use core::cell::{Cell, RefCell};
@@ -39,12 +58,15 @@ use esp_backtrace as _;
 
 // ESP-HAL imports
 use esp_hal::{
+    analog::adc::{Adc, AdcCalCurve, AdcConfig, Attenuation},
     handler,
     i2c::master::{Config as I2cConfig, I2c},
-    main, psram, ram,
+    main,
+    peripherals::ADC1,
+    psram, ram,
     rtc_cntl::{
         reset_reason,
-        sleep::{Ext0WakeupSource, WakeupLevel},
+        sleep::{Ext0WakeupSource, Ext1WakeupSource, TimerWakeupSource, WakeSource, WakeupLevel},
         wakeup_cause, Rtc, SocResetReason,
     },
     system::Cpu,
@@ -55,7 +77,6 @@ use esp_hal::{
 
 // Embedded HAL trait for delay
 use embedded_hal::delay::DelayNs;
-use embedded_hal::i2c::I2c as _;
 
 #[cfg(feature = "esp32s3-disp143Oled")]
 // Println macro
@@ -63,7 +84,7 @@ use esp_println::println;
 
 // Allocator for PSRAM
 extern crate alloc;
-use alloc::{boxed::Box, vec};
+use alloc::{boxed::Box, vec::Vec};
 
 #[cfg(feature = "devkit-esp32s3-disp128")]
 #[ram]
@@ -71,34 +92,19 @@ static mut DISPLAY_BUF: [u8; 1024] = [0; 1024];
 
 use core::sync::atomic::{AtomicBool, Ordering};
 static BUTTON1_PRESSED: AtomicBool = AtomicBool::new(false);
+static BUTTON1_LONG_PRESSED: AtomicBool = AtomicBool::new(false); // Held SLEEP_HOLD_MS: deep sleep
 static BUTTON2_PRESSED: AtomicBool = AtomicBool::new(false);
+static BUTTON2_REPEAT_ON: AtomicBool = AtomicBool::new(false); // Hold button 2 to repeat it
 static BUTTON3_PRESSED: AtomicBool = AtomicBool::new(false);
+static ENC_SW_PRESSED: AtomicBool = AtomicBool::new(false);
 static IMU_INT_FLAG: AtomicBool = AtomicBool::new(false);
 
 // Shared resources for Button
-static BUTTON1: ButtonState<'static> = ButtonState {
-    input: Mutex::new(RefCell::new(None)),
-    // led: Mutex::new(RefCell::new(None)),
-    last_level: Mutex::new(Cell::new(true)),
-    last_interrupt: Mutex::new(Cell::new(0)),
-    name: "Button1",
-};
+static BUTTON1: ButtonState<'static> = ButtonState::new("Button1");
 
-static BUTTON2: ButtonState<'static> = ButtonState {
-    input: Mutex::new(RefCell::new(None)),
-    // led: Mutex::new(RefCell::new(None)),
-    last_level: Mutex::new(Cell::new(true)),
-    last_interrupt: Mutex::new(Cell::new(0)),
-    name: "Button2",
-};
+static BUTTON2: ButtonState<'static> = ButtonState::new("Button2");
 
-static BUTTON3: ButtonState<'static> = ButtonState {
-    input: Mutex::new(RefCell::new(None)),
-    // led: Mutex::new(RefCell::new(None)),
-    last_level: Mutex::new(Cell::new(true)),
-    last_interrupt: Mutex::new(Cell::new(0)),
-    name: "Button3",
-};
+static BUTTON3: ButtonState<'static> = ButtonState::new("Button3");
 
 // Shared resources for rotary encoder
 static ROTARY: RotaryState<'static> = RotaryState {
@@ -107,30 +113,112 @@ static ROTARY: RotaryState<'static> = RotaryState {
     position: Mutex::new(Cell::new(0)),
     last_qstate: Mutex::new(Cell::new(0)), // bits: [CLK<<1 | DT]
     last_step: Mutex::new(Cell::new(0)),   // +1 or -1 from last transition
+    sw: Mutex::new(RefCell::new(None)),
+    sw_last_level: Mutex::new(Cell::new(true)),
+    sw_last_interrupt: Mutex::new(Cell::new(0)),
+    sw_debounce_ms: DEFAULT_DEBOUNCE_MS,
+    last_step_ms: Mutex::new(Cell::new(0)),
+    step_interval_ms: Mutex::new(Cell::new(0)),
 };
 
 #[cfg(feature = "esp32s3-disp143Oled")]
-fn apply_brightness(display: &mut esp32s3_tests::display::DisplayType<'static>, pct: u8) {
-    let hw = ((pct as u16) * 255 / 100) as u8;
-    let _ = display.set_brightness(hw);
+// Returns true when the low-brightness gamma toggled, the screen then needs a full repaint
+fn apply_brightness(display: &mut esp32s3_tests::display::DisplayType<'static>, pct: u8) -> bool {
+    if esp32s3_tests::ui::brightness_perceptual() {
+        let _ = display.set_brightness_perceptual(pct);
+    } else {
+        let _ = display.set_brightness(((pct as u16) * 255 / 100) as u8);
+    }
+    display.set_gamma((pct < GAMMA_BELOW_PCT).then_some(LOW_BRIGHTNESS_GAMMA))
 }
 
-// Global UI state
-static UI_STATE: Mutex<Cell<UiState>> = Mutex::new(Cell::new(UiState {
-    page: Page::Main(MainMenuState::Home),
-    // page: Page::Omnitrix(esp32s3_tests::ui::OmnitrixState::Alien1),
-    dialog: None,
-}));
-
 // IMU interrupt input holder
 #[cfg(feature = "esp32s3-disp143Oled")]
 static IMU_INT: ImuIntState<'static> = ImuIntState {
     input: Mutex::new(RefCell::new(None)),
 };
 
-// Current debounce time (milliseconds)
-const DEBOUNCE_MS: u64 = 240;
 const SLEEP_HOLD_MS: u64 = 5000; // Hold button 1 for 5 seconds to sleep/wake
+const BUTTON2_REPEAT_DELAY_MS: u64 = 500; // Clock edit: hold button 2 this long to start repeating
+const BUTTON2_REPEAT_MS: u64 = 150; // Clock edit: then one more button 2 press this often
+const TEMP_RESET_HOLD_MS: u64 = 1500; // Hold button 2 on the temperature page to reset min/max
+const TEMP_POLL_MS: u64 = 1000; // IMU die temperature poll interval
+const IMU_PROBE_RETRIES: u8 = 4; // Extra probe rounds (with backoff) if the IMU isn't up yet
+const IMU_STREAM_PERIOD_MS: u64 = 10; // Fastest CSV line rate for `STREAM ON` (100 Hz)
+const RTC_RESYNC_MS: u64 = 120_000; // Software clock -> RTC drift correction interval
+const FAULT_SHOW_MS: u32 = 3000; // How long a recoverable boot fault stays on screen
+const ASSET_OOM_DETAIL: &str = "Not enough PSRAM for all images. Some screens show an asset error.";
+const GAMMA_BELOW_PCT: u8 = 25; // Below this brightness images are blitted through the gamma LUT
+const LOW_BRIGHTNESS_GAMMA: f32 = 0.6; // < 1 lifts dark tones so dim content stays legible
+const AOD_DIM_PCT: u8 = 8; // Always-on: brightness while the wrist is down
+const AOD_RAMP_STEP: u8 = 4; // Always-on: brightness change per ramp tick (percent)
+const AOD_RAMP_MS: u64 = 15; // Always-on: ramp tick interval
+const AOD_DIM_FRAME_MS: u64 = 1000; // Always-on: watch face redraw interval while dimmed
+const LOW_BATTERY_MAX_BRIGHTNESS: u8 = 40; // Brightness cap while the battery is low
+const BATTERY_HYSTERESIS_PCT: u8 = 3; // Headroom above warn before leaving low-battery mode
+const BATTERY_SAMPLE_MS: u64 = 5000; // Battery ADC sampling interval
+const BATTERY_DIVIDER: u16 = 3; // BAT_ADC sees the cell voltage through a 1:3 divider
+const WAKE_MOTION_THRESHOLD_MG: u8 = 80; // Wake-on-motion threshold for the motion wake source
+const SLOW_FRAME_MS: u64 = 100; // Log UI frames that take longer than this
+const LOOP_BUDGET_US: u32 = 20_000; // Target upper bound for one main loop iteration
+const LOOP_MAX_DEFER: u8 = 4; // Iterations a task may be put off before it runs anyway
+const PANEL_IDLE_MS: u64 = 8000; // No input this long on a watch face: panel idle mode (8 colors)
+const PIXEL_SHIFT_IDLE_MS: u64 = 60_000; // Idle time before the burn-in pixel shift starts
+const PIXEL_SHIFT_PERIOD_MS: u64 = 60_000; // How often the content moves while idle
+
+// Offsets walked while idle (first entry is the normal position), all within ui::PIXEL_SHIFT_MAX
+const PIXEL_SHIFT_PATTERN: [(i32, i32); 9] = [
+    (0, 0),
+    (2, 0),
+    (2, 2),
+    (0, 2),
+    (-2, 2),
+    (-2, 0),
+    (-2, -2),
+    (0, -2),
+    (2, -2),
+];
+
+// Low-battery state machine levels (thresholds come from the Low Battery setting)
+#[cfg(feature = "esp32s3-disp143Oled")]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum BatteryLevel {
+    Ok,
+    Low,
+    Critical,
+}
+
+// Button 1 press / hold-to-sleep flags. Runs from the interrupt (edges) and the main loop
+// (the hold crossing SLEEP_HOLD_MS raises no interrupt).
+#[ram]
+fn poll_button1(now_ms: u64) {
+    handle_button_with_longpress(
+        &BUTTON1,
+        now_ms,
+        SLEEP_HOLD_MS,
+        || BUTTON1_PRESSED.store(true, Ordering::Relaxed),
+        || BUTTON1_LONG_PRESSED.store(true, Ordering::Relaxed),
+    );
+}
+
+// Button 2 press flag, repeated while held when BUTTON2_REPEAT_ON is set. Runs from the
+// interrupt (edges) and, while repeating is wanted, the main loop (a hold raises no
+// interrupts). With repeating off the initial delay never ends, so a hold is one press.
+#[ram]
+fn poll_button2(now_ms: u64) {
+    let initial_delay_ms = if BUTTON2_REPEAT_ON.load(Ordering::Relaxed) {
+        BUTTON2_REPEAT_DELAY_MS
+    } else {
+        u64::MAX
+    };
+    handle_button_with_repeat(
+        &BUTTON2,
+        now_ms,
+        initial_delay_ms,
+        BUTTON2_REPEAT_MS,
+        || BUTTON2_PRESSED.store(true, Ordering::Relaxed),
+    );
+}
 
 // Interrupt handler
 #[handler]
@@ -141,23 +229,24 @@ fn handler() {
         t.saturating_mul(1000) / SystemTimer::ticks_per_second()
     };
 
-    // Button 1: JUST SET THE FLAG
-    handle_button_generic(&BUTTON1, now_ms, DEBOUNCE_MS, || {
-        BUTTON1_PRESSED.store(true, Ordering::Relaxed);
-    });
+    // Button 1: JUST SET THE FLAGS
+    poll_button1(now_ms);
 
     // Button 2: JUST SET THEFlag
-    handle_button_generic(&BUTTON2, now_ms, DEBOUNCE_MS, || {
-        BUTTON2_PRESSED.store(true, Ordering::Relaxed);
-    });
+    poll_button2(now_ms);
 
     // Button 3: JUST SET THE FLAG
-    handle_button_generic(&BUTTON3, now_ms, DEBOUNCE_MS, || {
+    handle_button_generic(&BUTTON3, now_ms, || {
         BUTTON3_PRESSED.store(true, Ordering::Relaxed);
     });
 
+    // Encoder push switch: JUST SET THE FLAG
+    handle_encoder_switch_generic(&ROTARY, now_ms, || {
+        ENC_SW_PRESSED.store(true, Ordering::Relaxed);
+    });
+
     // Encoder logic is fine, it's just math
-    handle_encoder_generic(&ROTARY);
+    handle_encoder_with_accel(&ROTARY, now_ms);
 
     #[cfg(feature = "esp32s3-disp143Oled")]
     {
@@ -168,10 +257,8 @@ fn handler() {
 #[main]
 fn main() -> ! {
     // initial UI state
-    let mut last_ui_state = UiState {
-        page: Page::Main(MainMenuState::Home),
-        dialog: None,
-    };
+    set_state(default_start_state());
+    let mut last_ui_state = current_state();
 
     let mut needs_redraw = true;
     // // for debug, start on Alien page
@@ -194,13 +281,33 @@ fn main() -> ! {
         enc_dt,
         #[cfg(feature = "esp32s3-disp143Oled")]
         imu_int,
+        #[cfg(feature = "esp32s3-disp143Oled")]
+        enc_sw,
         display_pins,
         #[cfg(feature = "esp32s3-disp143Oled")]
         imu_i2c,
         #[cfg(feature = "esp32s3-disp143Oled")]
         lpwr,
+        #[cfg(feature = "esp32s3-disp143Oled")]
+        bat_adc,
+        #[cfg(feature = "esp32s3-disp143Oled")]
+        adc1,
+        #[cfg(feature = "esp32s3-disp143Oled")]
+        usb_device,
     } = pins;
 
+    // Battery ADC, calibrated so reads come back in mV at the pin
+    #[cfg(feature = "esp32s3-disp143Oled")]
+    let (mut battery_adc, mut battery_pin) = {
+        let mut config = AdcConfig::new();
+        let pin = config.enable_pin_with_cal::<_, AdcCalCurve<ADC1>>(bat_adc, Attenuation::_11dB);
+        (Adc::new(adc1, config), pin)
+    };
+
+    // Serial command console (SET <unix_seconds> / GET) on USB-Serial-JTAG
+    #[cfg(feature = "esp32s3-disp143Oled")]
+    let mut serial_console = SerialConsole::new(usb_device);
+
     // -------------------- RTC and Deep Sleep Wake Detection --------------------
     #[cfg(feature = "esp32s3-disp143Oled")]
     let mut rtc = Rtc::new(lpwr);
@@ -234,11 +341,41 @@ fn main() -> ! {
         from_sleep
     };
 
+    // Preferences saved before deep sleep (kept defaults after a power-on)
+    if load_settings() {
+        println!("settings restored");
+    }
+
     // rotary encoder detent tracking
     const DETENT_STEPS: i32 = 4; // set to 4 if your encoder is 4 steps per detent
     let mut last_detent: Option<i32> = None;
-    let mut sleep_hold_start: Option<u64> = None; // Track button 1 hold for deep sleep
+    let mut service_chord = ChordDetector::new([&BUTTON1, &BUTTON3]); // Hold 1, press 3
+    let mut service_next_ms: u64 = 0; // Next once-a-second refresh of the service page
+    let mut temp_reset_hold_start: Option<u64> = None; // Track button 2 hold for min/max reset
+    let mut temp_reset_done = false;
     let mut last_watch_edit_active = false;
+    let mut last_input_ms: u64 = 0; // Last button/encoder activity, pauses the Omnitrix carousel
+    let mut carousel_last_ms: u64 = 0; // Last carousel advance (or carousel (re)arm)
+    let mut pixel_shift_last_ms: u64 = 0; // Last burn-in pixel shift step
+    let mut pixel_shift_step: usize = 0; // Index into PIXEL_SHIFT_PATTERN
+    let mut aod_dim = false; // Always-on: wrist is down, face dimmed
+    let mut aod_last_frame_ms: u64 = 0; // Always-on: last watch redraw while dimmed
+    #[cfg(feature = "esp32s3-disp143Oled")]
+    let mut aod_shown_pct = esp32s3_tests::ui::brightness_pct(); // Brightness currently on the panel
+    #[cfg(feature = "esp32s3-disp143Oled")]
+    let mut aod_last_ramp_ms: u64 = 0;
+    #[cfg(feature = "esp32s3-disp143Oled")]
+    let mut rtc_resync_next_ms: u64 = RTC_RESYNC_MS; // Next periodic RTC drift correction
+    #[cfg(feature = "esp32s3-disp143Oled")]
+    let mut rtc_resync_on_watch = false; // Was on a watch face last loop (resync on entry)
+    #[cfg(feature = "esp32s3-disp143Oled")]
+    let mut screen_off = false; // Screen timeout: panel asleep, CPU and clock still running
+    #[cfg(feature = "esp32s3-disp143Oled")]
+    let mut battery_next_ms: u64 = 0; // Next battery ADC sample
+    #[cfg(feature = "esp32s3-disp143Oled")]
+    let mut battery_level = BatteryLevel::Ok;
+    #[cfg(feature = "esp32s3-disp143Oled")]
+    let mut battery_saved: Option<(u8, (u8, u8))> = None; // User brightness and limits before the low-battery cap
 
     // Read encoder pin states BEFORE moving them
     let clk_initial = enc_clk.is_high() as u8;
@@ -264,6 +401,11 @@ fn main() -> ! {
 
         #[cfg(feature = "esp32s3-disp143Oled")]
         IMU_INT.input.borrow_ref_mut(cs).replace(imu_int);
+        #[cfg(feature = "esp32s3-disp143Oled")]
+        {
+            ROTARY.sw.borrow_ref_mut(cs).replace(enc_sw);
+            ROTARY.sw_last_level.borrow(cs).set(true);
+        }
     });
 
     // If we woke from deep sleep, wait for the wake button (Button 2) to be released
@@ -298,6 +440,14 @@ fn main() -> ! {
 
     io.set_interrupt_handler(handler);
 
+    // The TE line (if wired) stays with main to pace watch face flushes
+    #[cfg(feature = "esp32s3-disp143Oled")]
+    let (display_pins, mut te_pin) = {
+        let mut pins = display_pins;
+        let te = pins.te.take();
+        (pins, te)
+    };
+
     let mut my_display = {
         #[cfg(feature = "devkit-esp32s3-disp128")]
         {
@@ -308,17 +458,53 @@ fn main() -> ! {
         #[cfg(feature = "esp32s3-disp143Oled")]
         {
             const W: usize = 466;
-            let fb: &'static mut [u16] = Box::leak(vec![0u16; W * W].into_boxed_slice());
-
-            setup_display(display_pins, fb)
+            // Reserve explicitly: without PSRAM there is no framebuffer and so no way to put
+            // a fault screen on the panel, so report it on serial and stop.
+            let mut fb_vec: Vec<u16> = Vec::new();
+            if fb_vec.try_reserve_exact(W * W).is_err() {
+                println!(
+                    "E{:02} {}: no {} bytes for the framebuffer, is PSRAM enabled?",
+                    FaultCode::Memory.code(),
+                    FaultCode::Memory.title(),
+                    W * W * 2
+                );
+                loop {
+                    TimerDelay.delay_ms(1000);
+                }
+            }
+            fb_vec.resize(W * W, 0);
+            let fb: &'static mut [u16] = Box::leak(fb_vec.into_boxed_slice());
+
+            let mut display = setup_display(display_pins, fb);
+            // Second buffer for double-buffered animation frames; optional, so only if
+            // PSRAM has room for it
+            let mut back_vec: Vec<u16> = Vec::new();
+            if back_vec.try_reserve_exact(W * W).is_ok() {
+                back_vec.resize(W * W, 0);
+                display.enable_double_buffer(Box::leak(back_vec.into_boxed_slice()));
+            } else {
+                println!("No PSRAM for a second framebuffer, single buffered");
+            }
+            display
         }
     };
 
+    #[cfg(feature = "esp32s3-disp143Oled")]
+    if te_pin.is_some() && my_display.enable_tearing(TeMode::VBlank).is_err() {
+        te_pin = None;
+    }
+
+    // Put the restored brightness on the panel before the first frame
+    #[cfg(feature = "esp32s3-disp143Oled")]
+    apply_brightness(&mut my_display, aod_shown_pct);
+
     // -------------------- IMU and RTC initialization --------------------
 
     #[cfg(feature = "esp32s3-disp143Oled")]
     let mut rtc_bus: Option<&'static core::cell::RefCell<I2c<'static, esp_hal::Blocking>>> = None;
     #[cfg(feature = "esp32s3-disp143Oled")]
+    let mut boot_fault: Option<(FaultCode, &str)> = None;
+    #[cfg(feature = "esp32s3-disp143Oled")]
     let mut imu = {
         let cfg = I2cConfig::default().with_frequency(Rate::from_khz(400));
         match I2c::new(i2c0, cfg) {
@@ -371,84 +557,93 @@ fn main() -> ! {
                 });
                 // esp_println::println!("[RTC] boot set_clock_seconds({})", boot_secs);
                 set_clock_seconds(boot_secs);
+                // VL set / invalid / unreadable RTC: the time is a guess until the user sets it
+                set_clock_reliable(rtc_secs.is_some());
                 rtc_bus = Some(bus_static);
-                let mut bus_device = embedded_hal_bus::i2c::RefCellDevice::new(bus_static);
-
-                // Small helper to probe addresses
-                let mut probe = |addr: u8| -> Option<u8> {
-                    let mut who = [0u8];
-                    match bus_device.write_read(addr, &[0x00], &mut who) {
-                        Ok(()) => {
-                            // println!("IMU probe ok addr 0x{:02X} WHO 0x{:02X}", addr, who[0]);
-                            Some(who[0])
-                        }
-                        Err(_e) => {
-                            // println!("IMU probe fail addr 0x{:02X}: {:?}", addr, e);
-                            None
-                        }
-                    }
-                };
-
-                // First attempt
-                let mut found = None;
-                for &addr in &[DEFAULT_I2C_ADDR, 0x6A] {
-                    if let Some(who) = probe(addr) {
-                        found = Some((addr, who));
-                        break;
-                    }
-                }
-
-                // If not found, wait and re-probe (handles power-up race)
-                if found.is_none() {
-                    for _ in 0..50 {
-                        core::hint::spin_loop();
-                    }
-                    for &addr in &[DEFAULT_I2C_ADDR, 0x6A] {
-                        if let Some(who) = probe(addr) {
-                            found = Some((addr, who));
-                            break;
-                        }
+                let bus_device = embedded_hal_bus::i2c::RefCellDevice::new(bus_static);
+
+                // Probe both possible addresses, retrying to ride out the power-up race
+                let mut delay = TimerDelay;
+                match Qmi8658::probe_and_open(
+                    bus_device,
+                    &[DEFAULT_I2C_ADDR, 0x6A],
+                    &mut delay,
+                    IMU_PROBE_RETRIES,
+                ) {
+                    Ok(mut dev) => {
+                        // Power-up check; a failed self-test still leaves the IMU in use
+                        let health = match dev.self_test() {
+                            Ok(true) => SensorHealth::Ok,
+                            Ok(false) => SensorHealth::SelfTestFailed,
+                            Err(e) => {
+                                println!("IMU self-test error: {:?}", e);
+                                SensorHealth::SelfTestFailed
+                            }
+                        };
+                        sensor_health_set(health);
+                        Some(dev)
                     }
-                }
-
-                if let Some((addr, _who)) = found {
-                    match Qmi8658::new(bus_device, addr) {
-                        Ok(dev) => {
-                            // Ok(mut dev) => {
-                            // println!("IMU WHO_AM_I (driver): 0x{:02X}", who);
-                            // match (dev.read_reg8(0x02), dev.read_reg8(0x09)) {
-                            //     (Ok(c1), Ok(c8)) => println!("IMU CTRL1=0x{:02X} CTRL8=0x{:02X}", c1, c8),
-                            //     _ => println!("IMU ctrl read failed"),
-                            // }
-                            Some(dev)
-                        }
-                        Err(_e) => {
-                            // println!("IMU init failed: {:?}", e);
-                            None
-                        }
+                    Err(e) => {
+                        println!("IMU unavailable: {:?}", e);
+                        boot_fault = Some((
+                            FaultCode::Imu,
+                            "Motion sensor did not respond. Smash gestures are off.",
+                        ));
+                        None
                     }
-                } else {
-                    // println!("IMU not found on scanned addresses");
-                    None
                 }
             }
             Err(_e) => {
                 // println!("I2C init failed: {:?}", e);
+                boot_fault = Some((
+                    FaultCode::I2c,
+                    "Sensor bus failed to start. Motion and the clock chip are off.",
+                ));
                 None
             }
         }
     };
 
+    // Recoverable bring-up failure: tell the user on screen, then carry on without it
+    #[cfg(feature = "esp32s3-disp143Oled")]
+    if let Some((code, detail)) = boot_fault {
+        draw_fault_screen(&mut my_display, code, detail);
+        let mut delay = TimerDelay;
+        delay.delay_ms(FAULT_SHOW_MS);
+        // The initial draw below repaints the whole panel (full-screen logo)
+    }
+
     #[cfg(feature = "esp32s3-disp143Oled")]
     let mut smash_detector = SmashDetector::default_rough();
+    // Always-on Dim <-> Bright. The face dims on Lowered: tilted past ~60 deg for 800 ms.
+    // It brightens on Raised: back within ~40 deg for 200 ms, or on any input. Between 40
+    // and 60 deg nothing changes, and samples taken mid-swing are ignored, so jitter
+    // can't flicker it. Brightness then ramps (AOD_RAMP_*) rather than jumps.
+    #[cfg(feature = "esp32s3-disp143Oled")]
+    let mut wrist_detector = WristRaiseDetector::default_profile();
+    #[cfg(feature = "esp32s3-disp143Oled")]
+    let mut shake_detector = ShakeDetector::default_profile();
+    #[cfg(feature = "esp32s3-disp143Oled")]
+    let mut orientation_detector = OrientationDetector::default_profile();
+    #[cfg(feature = "esp32s3-disp143Oled")]
+    let mut tap_detector = TapDetector::default_profile();
+    #[cfg(feature = "esp32s3-disp143Oled")]
+    let mut tap_nav = TapNavigator::new(400);
+    #[cfg(feature = "esp32s3-disp143Oled")]
+    let mut wake_tap = DoubleTapDetector::default_profile();
+    #[cfg(feature = "esp32s3-disp143Oled")]
+    let mut freefall_detector = FreefallDetector::default_profile();
     #[cfg(feature = "esp32s3-disp143Oled")]
     let mut last_sample: Option<esp32s3_tests::qmi8658_imu::ImuSample> = None;
     #[cfg(feature = "esp32s3-disp143Oled")]
     let mut next_poll_ms: u64 = 0;
+    #[cfg(feature = "esp32s3-disp143Oled")]
+    let mut next_temp_ms: u64 = 0;
+    #[cfg(feature = "esp32s3-disp143Oled")]
+    let mut imu_stream = ImuStream::new(IMU_STREAM_PERIOD_MS);
 
-    // count smash gestures while on Omnitrix page
     #[cfg(feature = "esp32s3-disp143Oled")]
-    let mut smash_count: u8 = 0;
+    let mut smash_cal_active = false; // On the calibration page last loop
 
     // Debug output of IMU data
     // #[cfg(feature = "esp32s3-disp143Oled")]
@@ -461,7 +656,10 @@ fn main() -> ! {
     #[cfg(feature = "esp32s3-disp143Oled")]
     {
         // Pre-cache Omnitrix logo image
-        let _ = precache_asset(AssetId::Logo);
+        if let Err(AssetError::OutOfMemory { .. }) = precache_asset(AssetId::Logo) {
+            draw_fault_screen(&mut my_display, FaultCode::Memory, ASSET_OOM_DETAIL);
+            TimerDelay.delay_ms(FAULT_SHOW_MS);
+        }
     }
 
     // Initial UI draw (timed)
@@ -479,7 +677,11 @@ fn main() -> ! {
         // Pre-cache all Omnitrix images
 
         use esp32s3_tests::ui::precache_all;
-        let _n = precache_all();
+        if let Err(AssetError::OutOfMemory { .. }) = precache_all() {
+            draw_fault_screen(&mut my_display, FaultCode::Memory, ASSET_OOM_DETAIL);
+            TimerDelay.delay_ms(FAULT_SHOW_MS);
+            needs_redraw = true;
+        }
         // esp_println::println!("Precached {} Omnitrix images", n);
     }
 
@@ -604,29 +806,42 @@ fn main() -> ! {
 
     // // -------------------- Main loop --------------------
 
+    // Keeps the expensive steps (IMU read, redraw) from piling into one iteration
+    let mut loop_budget = LoopBudget::new(LOOP_BUDGET_US, LOOP_MAX_DEFER);
+
     // Main loop: handle UI, buttons, rotary, and IMU-triggered smash input
     loop {
+        loop_budget.begin();
+        // Keep a background (DMA) flush moving; a no-op when none is in flight
+        #[cfg(feature = "esp32s3-disp143Oled")]
+        my_display.flush_poll();
         let now_ms = {
             let t = SystemTimer::unit_value(Unit::Unit0);
             t.saturating_mul(1000) / SystemTimer::ticks_per_second()
         };
+        // Set by any input handled this iteration (alongside `last_input_ms`)
+        let mut input_this_iter = false;
+        // Set by input this iteration that turns a timed-out screen back on
+        #[cfg(feature = "esp32s3-disp143Oled")]
+        let mut screen_wake = false;
 
         // Check for UI state changes
-        let ui_state = critical_section::with(|cs| UI_STATE.borrow(cs).get());
+        let ui_state = current_state();
         if ui_state != last_ui_state {
             last_ui_state = ui_state;
             needs_redraw = true;
         }
         let in_omnitrix = matches!(ui_state.page, Page::Omnitrix(_));
-        if !in_omnitrix {
-            smash_count = 0;
-        }
 
         if matches!(ui_state.page, Page::Watch(WatchAppState::Digital))
             || matches!(ui_state.page, Page::Watch(WatchAppState::Analog))
         {
-            // Keep redrawing to refresh the clock hands/digits while in watch modes.
-            needs_redraw = true;
+            // Keep redrawing to refresh the clock hands/digits while in watch modes
+            // (only about once a second while the always-on face is dimmed).
+            if !aod_dim || now_ms.saturating_sub(aod_last_frame_ms) >= AOD_DIM_FRAME_MS {
+                needs_redraw = true;
+                aod_last_frame_ms = now_ms;
+            }
         }
 
         if matches!(
@@ -634,6 +849,30 @@ fn main() -> ! {
             Page::Settings(SettingsMenuState::BrightnessAdjust)
         ) {
             if esp32s3_tests::ui::brightness_take_dirty() {
+                save_settings();
+                needs_redraw = true;
+            }
+        }
+
+        if matches!(ui_state.page, Page::Temperature) {
+            if esp32s3_tests::ui::temperature_take_dirty() {
+                needs_redraw = true;
+            }
+        }
+
+        if matches!(ui_state.page, Page::SmashCalibration) {
+            if esp32s3_tests::ui::smash_cal_take_dirty() {
+                needs_redraw = true;
+            }
+        }
+
+        if matches!(ui_state.page, Page::Service) && now_ms >= service_next_ms {
+            service_next_ms = now_ms + 1000;
+            needs_redraw = true;
+        }
+
+        if matches!(ui_state.page, Page::Main(MainMenuState::Home)) {
+            if esp32s3_tests::ui::status_take_dirty() {
                 needs_redraw = true;
             }
         }
@@ -643,11 +882,21 @@ fn main() -> ! {
             needs_redraw = true;
         }
 
-        update_ui(&mut my_display, last_ui_state, needs_redraw);
-        needs_redraw = false;
+        // Entering the calibration page starts a fresh gravity measurement
+        #[cfg(feature = "esp32s3-disp143Oled")]
+        {
+            let on_cal = matches!(ui_state.page, Page::SmashCalibration);
+            if on_cal && !smash_cal_active && imu.is_some() {
+                smash_detector.begin_calibration();
+                smash_cal_report(smash_detector.calibration_progress());
+            }
+            smash_cal_active = on_cal;
+        }
 
         // IMU smash detection
         #[cfg(feature = "esp32s3-disp143Oled")]
+        let mut tap_event = None;
+        #[cfg(feature = "esp32s3-disp143Oled")]
         if let Some(dev) = imu.as_mut() {
             // Only read when IMU INT fired, additional fall back to periodic reads if INT never comes.
             let timed = now_ms >= next_poll_ms;
@@ -659,28 +908,89 @@ fn main() -> ! {
                     .map(|p| p.is_low())
                     .unwrap_or(false)
             });
-            let should_read = IMU_INT_FLAG.swap(false, Ordering::Relaxed)
+            let should_read = IMU_INT_FLAG.load(Ordering::Relaxed)
                 || pin_level_trig
                 || last_sample.is_none()
                 || timed;
-            if should_read {
+            // A read that doesn't fit this iteration's budget keeps the INT flag for the next one
+            let read = should_read
+                .then(|| loop_budget.run(LoopTask::Imu, || dev.read_sample()))
+                .flatten();
+            if let Some(read) = read {
+                IMU_INT_FLAG.store(false, Ordering::Relaxed);
                 // Read sample
-                match dev.read_sample() {
+                match read {
                     Ok(sample) => {
+                        imu_stream.push(now_ms, &sample);
                         // Process sample for smash detection
-                        if smash_detector.update(now_ms, &sample) {
-                            // println!("IMU smash hit:");
-
-                            // the omnitrix page is the only one that uses this input
-                            if in_omnitrix {
-                                smash_count = smash_count.saturating_add(1);
-                                // 2 smashes as it will count both the pop up and the down slam
-                                if smash_count >= 1 {
-                                    // reset count after triggering
-                                    smash_count = 0;
-                                    BUTTON3_PRESSED.store(true, Ordering::Relaxed);
+                        let smash = smash_detector.update(now_ms, &sample);
+                        if smash_cal_active {
+                            smash_cal_report(smash_detector.calibration_progress());
+                        }
+                        // A down slam on the omnitrix page (the only one that uses it) is
+                        // Button 3; the pop up is reported as Up and ignored
+                        if smash == Some(SmashKind::Down) && in_omnitrix {
+                            BUTTON3_PRESSED.store(true, Ordering::Relaxed);
+                        }
+                        // Dropped: wake the screen and put up a warning
+                        if freefall_detector.update(now_ms, &sample) {
+                            println!("IMU drop detected");
+                            last_input_ms = now_ms;
+                            input_this_iter = true;
+                            screen_wake = true;
+                            update_state(UiState::drop_warning);
+                            needs_redraw = true;
+                        }
+                        // Shake closes an open dialog, same as Button 1 (not on a dark screen)
+                        if shake_detector.update(now_ms, &sample)
+                            && !screen_off
+                            && current_state().dialog.is_some()
+                        {
+                            last_input_ms = now_ms;
+                            input_this_iter = true;
+                            update_state(UiState::back);
+                            needs_redraw = true;
+                        }
+                        // Tap navigation (Settings > Tap Navigation picks the timing windows).
+                        // A smash is a much bigger knock and must not also count as a tap.
+                        if let Some((double_ms, hold_ms)) = tap_cadence().windows_ms() {
+                            tap_detector.set_hold_ms(hold_ms);
+                            tap_nav.set_double_ms(double_ms);
+                            tap_event = tap_detector
+                                .update(now_ms, &sample)
+                                .filter(|_| smash.is_none());
+                        }
+                        // Double tap wakes a timed-out screen (counts as input)
+                        let double_tap = wake_tap.update(now_ms, &sample) == Some(TapCount::Double);
+                        if double_tap && smash.is_none() && screen_off {
+                            last_input_ms = now_ms;
+                            input_this_iter = true;
+                            screen_wake = true;
+                        }
+                        // Auto-rotate: turn the panel to the edge facing up, then repaint it all
+                        orientation_detector.update(now_ms, &sample);
+                        if auto_rotate_enabled()
+                            && my_display.set_orientation(Orientation::from_quarter_turns(
+                                orientation_detector.quarter_turns(),
+                            ))
+                        {
+                            force_full_repaint();
+                            needs_redraw = true;
+                        }
+                        // Always-on: dim on wrist-down, brighten on wrist-up
+                        match wrist_detector.update(now_ms, &sample) {
+                            Some(WristEvent::Lowered) => aod_dim = true,
+                            Some(WristEvent::Raised) => {
+                                aod_dim = false;
+                                // Flicking the watch into view wakes a timed-out or idle
+                                // screen like any input
+                                if wrist_detector.raised_by_gesture() {
+                                    last_input_ms = now_ms;
+                                    input_this_iter = true;
+                                    screen_wake = true;
                                 }
                             }
+                            None => {}
                         }
                         last_sample = Some(sample);
                     }
@@ -691,102 +1001,313 @@ fn main() -> ! {
                     next_poll_ms = now_ms.saturating_add(50);
                 }
             }
-        }
 
-        // Handle button events
-        let b1_event = BUTTON1_PRESSED.swap(false, Ordering::Acquire);
-        let b2_event = BUTTON2_PRESSED.swap(false, Ordering::Acquire);
+            // Die temperature (reads warm of ambient, see `read_temperature`)
+            if now_ms >= next_temp_ms {
+                next_temp_ms = now_ms.saturating_add(TEMP_POLL_MS);
+                // Keep the sensor line current while a page showing it is up. The Service
+                // page redraws every second anyway; the Info page only on a change.
+                if matches!(ui_state.page, Page::Service | Page::EasterEgg)
+                    && sensor_health() != SensorHealth::SelfTestFailed
+                {
+                    let health = if dev.is_responsive() {
+                        SensorHealth::Ok
+                    } else {
+                        SensorHealth::NotResponding
+                    };
+                    if health != sensor_health() {
+                        sensor_health_set(health);
+                        needs_redraw = true;
+                    }
+                }
+                if let Ok(temp_c) = dev.read_temperature() {
+                    temperature_record(temp_c);
+                }
+            }
+        }
 
+        // Battery: one ADC conversion every BATTERY_SAMPLE_MS. A conversion that isn't done
+        // yet is picked up on a later iteration instead of blocking the loop.
         #[cfg(feature = "esp32s3-disp143Oled")]
-        {
-            // Track button 1 hold for deep sleep trigger
-            let btn1_down = critical_section::with(|cs| {
-                BUTTON1
-                    .input
-                    .borrow_ref(cs)
-                    .as_ref()
-                    .map(|p| p.is_low())
-                    .unwrap_or(false)
-            });
+        if now_ms >= battery_next_ms {
+            if let Ok(pin_mv) = battery_adc.read_oneshot(&mut battery_pin) {
+                battery_next_ms = now_ms.saturating_add(BATTERY_SAMPLE_MS);
+                let load_pct = if screen_off { 0 } else { aod_shown_pct };
+                esp32s3_tests::ui::battery_sample_mv(
+                    pin_mv.saturating_mul(BATTERY_DIVIDER),
+                    load_pct,
+                );
+            }
+        }
 
-            // Start tracking hold when button 1 goes down
-            if btn1_down && sleep_hold_start.is_none() {
-                sleep_hold_start = Some(now_ms);
+        // Low battery: below warn, cap the brightness and show the icon; at critical, the
+        // deep sleep path below takes over. Recovering needs a few percent of headroom.
+        #[cfg(feature = "esp32s3-disp143Oled")]
+        if let Some(pct) = esp32s3_tests::ui::battery_pct() {
+            let (warn, critical) = esp32s3_tests::ui::battery_thresholds();
+            let level = if pct <= critical {
+                BatteryLevel::Critical
+            } else if pct <= warn
+                || (battery_level != BatteryLevel::Ok
+                    && pct <= warn.saturating_add(BATTERY_HYSTERESIS_PCT))
+            {
+                BatteryLevel::Low
+            } else {
+                BatteryLevel::Ok
+            };
+            if level != battery_level {
+                if battery_level == BatteryLevel::Ok {
+                    let (min, max) = brightness_limits();
+                    battery_saved = Some((esp32s3_tests::ui::brightness_pct(), (min, max)));
+                    let cap = max.min(LOW_BATTERY_MAX_BRIGHTNESS);
+                    brightness_set_limits(min.min(cap), cap);
+                    status_icon_set(StatusIcon::BatteryLow, true);
+                } else if level == BatteryLevel::Ok {
+                    if let Some((p, (min, max))) = battery_saved.take() {
+                        brightness_set_limits(min, max);
+                        brightness_set_pct(p as i32);
+                    }
+                    status_icon_set(StatusIcon::BatteryLow, false);
+                }
+                battery_level = level;
             }
-            // Reset if button released
-            if !btn1_down {
-                sleep_hold_start = None;
+        }
+
+        // Handle button events. Holding Button 1 and pressing Button 3 is the service chord;
+        // Button 1 reports on release, so its press is known not to be part of one.
+        poll_button1(now_ms);
+        // Clock edit: holding button 2 fast-advances through the fields
+        let editing = esp32s3_tests::ui::watch_edit_active();
+        BUTTON2_REPEAT_ON.store(editing, Ordering::Relaxed);
+        if editing {
+            poll_button2(now_ms);
+        }
+        // The first input after a screen timeout only turns the panel back on: drop this
+        // iteration's presses, encoder turn and tap so they don't also act on a page the
+        // user couldn't see
+        #[cfg(feature = "esp32s3-disp143Oled")]
+        if screen_off {
+            let pressed = [
+                &BUTTON1_PRESSED,
+                &BUTTON2_PRESSED,
+                &BUTTON3_PRESSED,
+                &ENC_SW_PRESSED,
+            ]
+            .iter()
+            .fold(false, |any, flag| {
+                flag.swap(false, Ordering::Acquire) || any
+            });
+            let detent =
+                critical_section::with(|cs| ROTARY.position.borrow(cs).get()) / DETENT_STEPS;
+            let turned = last_detent.is_some_and(|d| d != detent);
+            if pressed || turned || tap_event.take().is_some() {
+                last_input_ms = now_ms;
+                input_this_iter = true;
+                screen_wake = true;
             }
+            last_detent = Some(detent);
+        }
+        let mut chord_fired = false;
+        let [b1_event, b3_event] = service_chord.update(
+            [
+                BUTTON1_PRESSED.swap(false, Ordering::Acquire),
+                BUTTON3_PRESSED.swap(false, Ordering::Acquire),
+            ],
+            || chord_fired = true,
+        );
+        if chord_fired {
+            last_input_ms = now_ms;
+            input_this_iter = true;
+            set_page(Page::Service);
+            needs_redraw = true;
+        }
 
-            // Check for 5-second hold to enter deep sleep
-            if let Some(t0) = sleep_hold_start {
-                if now_ms.saturating_sub(t0) >= SLEEP_HOLD_MS && btn1_down {
-                    // Save clock time to RTC (RTC continues during deep sleep)
-                    let current_clock_secs = get_clock_seconds();
-                    let rtc_now_us = rtc.current_time_us();
-                    let elapsed_since_boot_us = rtc_now_us.saturating_sub(rtc_boot_time_us);
-                    let clock_total_us = (current_clock_secs as u64) * 1_000_000
-                        + (elapsed_since_boot_us % 1_000_000);
-                    rtc.set_current_time_us(clock_total_us);
+        // The encoder's push switch is a second Select
+        let b2_event = BUTTON2_PRESSED.swap(false, Ordering::Acquire)
+            || ENC_SW_PRESSED.swap(false, Ordering::Acquire);
 
-                    // Disable display
-                    let mut delay = TimerDelay;
-                    let _ = my_display.disable(&mut delay);
-
-                    // Wait for button 1 release
-                    loop {
-                        let btn1_released = critical_section::with(|cs| {
-                            BUTTON1
-                                .input
-                                .borrow_ref(cs)
-                                .as_ref()
-                                .map(|b| b.is_high())
-                                .unwrap_or(true)
-                        });
-                        if btn1_released {
-                            break;
-                        }
-                        delay.delay_ms(10);
-                    }
-                    delay.delay_ms(50);
+        // Tap patterns stand in for the buttons: tap-hold = Button 1, double tap = Button 2,
+        // single tap = encoder step forward
+        #[cfg(feature = "esp32s3-disp143Oled")]
+        let (b1_event, b2_event) = match tap_cadence()
+            .windows_ms()
+            .and_then(|_| tap_nav.update(now_ms, tap_event))
+        {
+            Some(TapAction::Back) => (true, b2_event),
+            Some(TapAction::Select) => (b1_event, true),
+            Some(TapAction::Next) => {
+                last_input_ms = now_ms;
+                input_this_iter = true;
+                update_state(UiState::next_item);
+                needs_redraw = true;
+                (b1_event, b2_event)
+            }
+            None => (b1_event, b2_event),
+        };
 
-                    // Release button pins for reconfiguration
-                    critical_section::with(|cs| {
-                        let _ = BUTTON1.input.borrow_ref_mut(cs).take();
-                        let _ = BUTTON2.input.borrow_ref_mut(cs).take();
+        #[cfg(feature = "esp32s3-disp143Oled")]
+        {
+            // Deep sleep on a 5-second hold of button 1, or forced once the battery is critical
+            let hold_done = BUTTON1_LONG_PRESSED.swap(false, Ordering::Acquire);
+            let battery_empty = battery_level == BatteryLevel::Critical;
+            if hold_done || battery_empty {
+                if battery_empty {
+                    println!("battery critical, sleeping");
+                    draw_fault_screen(
+                        &mut my_display,
+                        FaultCode::Battery,
+                        "Charge the watch. Press select to wake.",
+                    );
+                    TimerDelay.delay_ms(FAULT_SHOW_MS);
+                }
+                // Keep the user's brightness and limits, not the low-battery cap, for after
+                // the wake
+                if let Some((p, (min, max))) = battery_saved.take() {
+                    brightness_set_limits(min, max);
+                    brightness_set_pct(p as i32);
+                }
+                save_settings();
+
+                // Save clock time to RTC (RTC continues during deep sleep)
+                let current_clock_secs = get_clock_seconds();
+                let rtc_now_us = rtc.current_time_us();
+                let elapsed_since_boot_us = rtc_now_us.saturating_sub(rtc_boot_time_us);
+                let clock_total_us =
+                    (current_clock_secs as u64) * 1_000_000 + (elapsed_since_boot_us % 1_000_000);
+                rtc.set_current_time_us(clock_total_us);
+
+                // Disable display
+                let mut delay = TimerDelay;
+                let _ = my_display.disable(&mut delay);
+
+                // Wait for button 1 release
+                loop {
+                    let btn1_released = critical_section::with(|cs| {
+                        BUTTON1
+                            .input
+                            .borrow_ref(cs)
+                            .as_ref()
+                            .map(|b| b.is_high())
+                            .unwrap_or(true)
+                    });
+                    if btn1_released {
+                        break;
+                    }
+                    delay.delay_ms(10);
+                }
+                delay.delay_ms(50);
+
+                // Wake sources from settings. Motion needs the IMU switched to wake-on-motion
+                // and the alarm needs a time set; if neither is usable the button is kept
+                // so the watch can always be woken.
+                let sources = wake_sources();
+                let motion = sources.contains(WakeSources::MOTION)
+                    && imu.as_mut().is_some_and(|dev| {
+                        dev.enable_wake_on_motion(WAKE_MOTION_THRESHOLD_MG).is_ok()
+                    });
+                let alarm_in_secs = wake_alarm_at()
+                    .filter(|_| sources.contains(WakeSources::ALARM))
+                    .map(|at| {
+                        let now = (get_clock_seconds() % 86_400) as u32;
+                        match (at + 86_400 - now) % 86_400 {
+                            0 => 86_400,
+                            d => d,
+                        }
                     });
+                let button =
+                    sources.contains(WakeSources::BUTTON) || (!motion && alarm_in_secs.is_none());
+                println!(
+                    "sleeping, wake on button={} motion={} alarm_in={:?}s",
+                    button, motion, alarm_in_secs
+                );
+
+                // Release button and IMU interrupt pins for reconfiguration
+                critical_section::with(|cs| {
+                    let _ = BUTTON1.input.borrow_ref_mut(cs).take();
+                    let _ = BUTTON2.input.borrow_ref_mut(cs).take();
+                    let _ = IMU_INT.input.borrow_ref_mut(cs).take();
+                });
 
-                    // Configure GPIO7 (Button 2) as wake source with RTC pull-up
-                    // uses unsafe steal since we've released the pin from earlier
-                    let gpio7 = unsafe { esp_hal::peripherals::GPIO7::steal() };
-                    use esp_hal::gpio::RtcPinWithResistors;
-                    gpio7.rtcio_pullup(true);
-                    gpio7.rtcio_pulldown(false);
-                    let ext0_wake = Ext0WakeupSource::new(gpio7, WakeupLevel::Low);
+                // Configure GPIO7 (Button 2) and GPIO8 (IMU INT1) with RTC pull-ups, both
+                // wake on low. uses unsafe steal since we've released the pins from earlier
+                let mut gpio7 = unsafe { esp_hal::peripherals::GPIO7::steal() };
+                let mut gpio8 = unsafe { esp_hal::peripherals::GPIO8::steal() };
+                use esp_hal::gpio::{RtcPin, RtcPinWithResistors};
+                gpio7.rtcio_pullup(true);
+                gpio7.rtcio_pulldown(false);
+                gpio8.rtcio_pullup(true);
+                gpio8.rtcio_pulldown(false);
+
+                let timer_wake = alarm_in_secs.map(|secs| {
+                    TimerWakeupSource::new(core::time::Duration::from_secs(secs as u64))
+                });
+                let mut wake: heapless::Vec<&dyn WakeSource, 2> = heapless::Vec::new();
+                if let Some(t) = timer_wake.as_ref() {
+                    let _ = wake.push(t);
+                }
 
-                    // Enter deep sleep (resets on wake)
-                    rtc.sleep_deep(&[&ext0_wake]);
+                // Enter deep sleep (resets on wake). Motion goes through Ext1 (any listed pin
+                // low), together with the button if that is selected too; the button alone
+                // keeps the single-pin Ext0 source.
+                if motion {
+                    let mut pins: [&mut dyn RtcPin; 2] = [&mut gpio7, &mut gpio8];
+                    let pins = if button {
+                        &mut pins[..]
+                    } else {
+                        &mut pins[1..]
+                    };
+                    let ext1_wake = Ext1WakeupSource::new(pins, WakeupLevel::Low);
+                    let _ = wake.push(&ext1_wake);
+                    rtc.sleep_deep(&wake);
+                }
+                let ext0_wake = Ext0WakeupSource::new(gpio7, WakeupLevel::Low);
+                if button {
+                    let _ = wake.push(&ext0_wake);
                 }
+                rtc.sleep_deep(&wake);
             }
         }
 
+        // Hold button 2 on the temperature page to reset min/max tracking
+        {
+            let btn2_down = critical_section::with(|cs| {
+                BUTTON2
+                    .input
+                    .borrow_ref(cs)
+                    .as_ref()
+                    .map(|p| p.is_low())
+                    .unwrap_or(false)
+            });
+            if btn2_down && matches!(ui_state.page, Page::Temperature) {
+                let t0 = *temp_reset_hold_start.get_or_insert(now_ms);
+                if !temp_reset_done && now_ms.saturating_sub(t0) >= TEMP_RESET_HOLD_MS {
+                    temperature_reset_minmax();
+                    temp_reset_done = true;
+                }
+            } else {
+                temp_reset_hold_start = None;
+                temp_reset_done = false;
+            }
+        }
+
+        if b1_event || b2_event {
+            last_input_ms = now_ms;
+            input_this_iter = true;
+        }
+
         // Button 1 = Back (go up a layer)
         if b1_event {
             if esp32s3_tests::ui::watch_edit_active() {
                 esp32s3_tests::ui::watch_edit_cancel();
             } else {
-                critical_section::with(|cs| {
-                    let state = UI_STATE.borrow(cs).get();
-                    let new_state = state.back();
-                    UI_STATE.borrow(cs).set(new_state);
-                });
+                update_state(UiState::back);
             }
             needs_redraw = true;
         }
 
         // Button 2 = Select (enter/confirm)
         if b2_event {
-            let ui_state = critical_section::with(|cs| UI_STATE.borrow(cs).get());
+            let ui_state = current_state();
             if matches!(
                 ui_state.page,
                 Page::Watch(esp32s3_tests::ui::WatchAppState::Digital)
@@ -797,22 +1318,16 @@ fn main() -> ! {
                     esp32s3_tests::ui::watch_edit_start();
                 }
             } else {
-                critical_section::with(|cs| {
-                    let state = UI_STATE.borrow(cs).get();
-                    let new_state = state.select();
-                    UI_STATE.borrow(cs).set(new_state);
-                });
+                update_state(UiState::select);
             }
             needs_redraw = true;
         }
 
         // Button 3 = Transform (IMU will actually trigger this, electrically this will be disconnected)
-        if BUTTON3_PRESSED.swap(false, Ordering::Acquire) {
-            critical_section::with(|cs| {
-                let state = UI_STATE.borrow(cs).get();
-                let new_state = state.transform(); // use Omnitrix-only dialog
-                UI_STATE.borrow(cs).set(new_state);
-            });
+        if b3_event {
+            last_input_ms = now_ms;
+            input_this_iter = true;
+            update_state(UiState::transform); // use Omnitrix-only dialog
             if in_omnitrix {
                 needs_redraw = true;
             }
@@ -825,37 +1340,188 @@ fn main() -> ! {
         // If detent changed, update UI state
         if Some(detent) != last_detent {
             if let Some(prev) = last_detent {
-                let step_delta = detent - prev;
-                let ui_state = critical_section::with(|cs| UI_STATE.borrow(cs).get());
+                last_input_ms = now_ms;
+                input_this_iter = true;
+                // Direction-corrected (per the Encoder Direction setting): positive = forward
+                let step_delta = encoder_delta(detent - prev);
+                // Value adjustments jump further on a fast spin; menus move one item per detent
+                let fast_delta = encoder_accel_delta(&ROTARY, step_delta, now_ms);
+                let ui_state = current_state();
                 if esp32s3_tests::ui::watch_edit_active() {
-                    esp32s3_tests::ui::watch_edit_adjust(-step_delta);
+                    esp32s3_tests::ui::watch_edit_adjust(fast_delta);
                 } else if matches!(
                     ui_state.page,
                     Page::Settings(SettingsMenuState::BrightnessAdjust)
                 ) {
-                    let new_pct = brightness_adjust(-step_delta);
+                    let new_pct = brightness_adjust(fast_delta);
                     #[cfg(feature = "esp32s3-disp143Oled")]
-                    apply_brightness(&mut my_display, new_pct);
+                    {
+                        if apply_brightness(&mut my_display, new_pct) {
+                            force_full_repaint();
+                            needs_redraw = true;
+                        }
+                        aod_shown_pct = new_pct;
+                    }
                 } else if step_delta > 0 {
-                    // turned clockwise: go to next state
-                    critical_section::with(|cs| {
-                        let state = UI_STATE.borrow(cs).get();
-                        let new_state = state.prev_item();
-                        UI_STATE.borrow(cs).set(new_state);
-                    });
+                    update_state(UiState::next_item);
                 } else if step_delta < 0 {
-                    // turned counter-clockwise: go to previous state (optional)
-                    critical_section::with(|cs| {
-                        let state = UI_STATE.borrow(cs).get();
-                        let new_state = state.next_item();
-                        UI_STATE.borrow(cs).set(new_state);
-                    });
+                    update_state(UiState::prev_item);
                 }
             }
             last_detent = Some(detent);
             needs_redraw = true;
         }
 
+        // Omnitrix carousel: advance to the next alien every N seconds while idle on the page.
+        // Any input restarts the wait, so touching the encoder pauses it immediately.
+        {
+            let secs = carousel_secs();
+            let ui_state = current_state();
+            if secs > 0 && matches!(ui_state.page, Page::Omnitrix(_)) && ui_state.dialog.is_none() {
+                let idle_since = last_input_ms.max(carousel_last_ms);
+                if now_ms.saturating_sub(idle_since) >= secs as u64 * 1000 {
+                    update_state(UiState::next_item);
+                    carousel_last_ms = now_ms;
+                    needs_redraw = true;
+                }
+            } else {
+                carousel_last_ms = now_ms;
+            }
+        }
+
+        // Serial commands: set/get the clock from a PC without the digit editor
+        #[cfg(feature = "esp32s3-disp143Oled")]
+        if let Some(cmd) = serial_console.poll() {
+            match cmd {
+                SerialCommand::Set(secs) => {
+                    set_clock_seconds_checked(secs, true);
+                    set_clock_reliable(true);
+                    if let Some(bus_ref) = rtc_bus {
+                        let dev = embedded_hal_bus::i2c::RefCellDevice::new(bus_ref);
+                        let mut rtc_handle = Pcf85063::new(dev);
+                        let _ = rtc_handle.set_datetime(&unix_to_datetime(secs));
+                    }
+                    println!("OK {}", secs);
+                    if matches!(ui_state.page, Page::Watch(_)) {
+                        needs_redraw = true;
+                    }
+                }
+                SerialCommand::Get => println!("TIME {}", clock_now_seconds_u32()),
+                SerialCommand::Page(page) => {
+                    set_page(page);
+                    println!("OK");
+                }
+                SerialCommand::Stream(on) => {
+                    if on && imu.is_none() {
+                        println!("ERR no IMU");
+                    } else {
+                        imu_stream.set_enabled(on);
+                    }
+                }
+            }
+        }
+
+        // Always-on dim: any input wakes the face; otherwise ramp the panel toward the
+        // dim or user brightness in small steps so the change isn't a hard jump.
+        if !always_on_enabled() || input_this_iter {
+            aod_dim = false;
+        }
+        #[cfg(feature = "esp32s3-disp143Oled")]
+        {
+            let user_pct = esp32s3_tests::ui::brightness_pct();
+            let flashlight = matches!(ui_state.page, Page::Flashlight);
+            // Torch is as bright as allowed (the low-battery cap still applies)
+            let torch_pct = esp32s3_tests::ui::brightness_limits().1;
+            if flashlight && aod_shown_pct != torch_pct {
+                // Torch turns on instantly; leaving it ramps back to the user setting below.
+                if apply_brightness(&mut my_display, torch_pct) {
+                    force_full_repaint();
+                    needs_redraw = true;
+                }
+                aod_shown_pct = torch_pct;
+            }
+            let target = if flashlight {
+                torch_pct
+            } else if aod_dim {
+                AOD_DIM_PCT.min(user_pct)
+            } else {
+                user_pct
+            };
+            if aod_shown_pct != target && now_ms.saturating_sub(aod_last_ramp_ms) >= AOD_RAMP_MS {
+                aod_shown_pct = if aod_shown_pct < target {
+                    aod_shown_pct.saturating_add(AOD_RAMP_STEP).min(target)
+                } else {
+                    aod_shown_pct.saturating_sub(AOD_RAMP_STEP).max(target)
+                };
+                if apply_brightness(&mut my_display, aod_shown_pct) {
+                    force_full_repaint();
+                    needs_redraw = true;
+                }
+                aod_last_ramp_ms = now_ms;
+            }
+        }
+
+        // Screen timeout: after the idle period turn the panel off (display_off + sleep_in)
+        // but keep running, so no RTC save/restore is needed. Any input turns it back on
+        // (`screen_wake`), and that input does nothing else.
+        // Skipped while always-on dim or the flashlight is in use.
+        #[cfg(feature = "esp32s3-disp143Oled")]
+        {
+            let timeout_ms = esp32s3_tests::ui::screen_timeout_secs() as u64 * 1000;
+            if screen_off {
+                if screen_wake {
+                    let mut delay = TimerDelay;
+                    let _ = my_display.enable(&mut delay);
+                    // enable() restores full brightness, put the user's level back
+                    if apply_brightness(&mut my_display, aod_shown_pct) {
+                        force_full_repaint();
+                        needs_redraw = true;
+                    }
+                    screen_off = false;
+                    needs_redraw = true;
+                }
+            } else if timeout_ms > 0
+                && !always_on_enabled()
+                && !matches!(ui_state.page, Page::Flashlight)
+                && now_ms.saturating_sub(last_input_ms) >= timeout_ms
+            {
+                let mut delay = TimerDelay;
+                let _ = my_display.disable(&mut delay);
+                screen_off = true;
+            }
+        }
+
+        // Panel idle mode: a watch face left alone drops to the panel's 8-color idle mode to
+        // save power while the time stays visible. Input or leaving the face restores color.
+        #[cfg(feature = "esp32s3-disp143Oled")]
+        if !screen_off {
+            let want_idle = matches!(ui_state.page, Page::Watch(_))
+                && ui_state.dialog.is_none()
+                && now_ms.saturating_sub(last_input_ms) >= PANEL_IDLE_MS;
+            if want_idle != my_display.idle() {
+                let _ = my_display.idle_mode(want_idle);
+            }
+        }
+
+        // Burn-in mitigation: once idle, nudge everything around a small square every period.
+        // Any input snaps back to the normal position.
+        if now_ms.saturating_sub(last_input_ms) >= PIXEL_SHIFT_IDLE_MS {
+            if now_ms.saturating_sub(pixel_shift_last_ms) >= PIXEL_SHIFT_PERIOD_MS {
+                pixel_shift_step = (pixel_shift_step + 1) % PIXEL_SHIFT_PATTERN.len();
+                let (dx, dy) = PIXEL_SHIFT_PATTERN[pixel_shift_step];
+                if set_pixel_shift(dx, dy) {
+                    needs_redraw = true;
+                }
+                pixel_shift_last_ms = now_ms;
+            }
+        } else if pixel_shift_step != 0 {
+            pixel_shift_step = 0;
+            pixel_shift_last_ms = now_ms;
+            if set_pixel_shift(0, 0) {
+                needs_redraw = true;
+            }
+        }
+
         // If we just exited watch edit, sync external RTC with current software clock.
         #[cfg(feature = "esp32s3-disp143Oled")]
         {
@@ -872,6 +1538,70 @@ fn main() -> ! {
             last_watch_edit_active = edit_active;
         }
 
+        // Pull the software clock toward the RTC every few minutes, and on entering a watch
+        // face, so SystemTimer drift never builds up. Small errors are slewed, not jumped.
+        #[cfg(feature = "esp32s3-disp143Oled")]
+        {
+            let on_watch = matches!(ui_state.page, Page::Watch(_));
+            let due = now_ms >= rtc_resync_next_ms || (on_watch && !rtc_resync_on_watch);
+            rtc_resync_on_watch = on_watch;
+            if due && !esp32s3_tests::ui::watch_edit_active() {
+                rtc_resync_next_ms = now_ms.saturating_add(RTC_RESYNC_MS);
+                if let Some(bus_ref) = rtc_bus {
+                    let dev = embedded_hal_bus::i2c::RefCellDevice::new(bus_ref);
+                    let mut rtc_handle = Pcf85063::new(dev);
+                    if let Ok((dt, false)) = rtc_handle.read_datetime() {
+                        if datetime_is_valid(&dt) {
+                            let step_ms = resync_from_rtc(datetime_to_unix(&dt));
+                            if step_ms != 0 {
+                                println!("rtc resync: {} ms", step_ms);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        // Nothing to draw onto a sleeping panel; keep any pending redraw for the wake.
+        #[cfg(feature = "esp32s3-disp143Oled")]
+        let panel_awake = !screen_off;
+        #[cfg(not(feature = "esp32s3-disp143Oled"))]
+        let panel_awake = true;
+
+        // Input above may have moved the UI on; draw the state it left
+        let ui_state = current_state();
+        if ui_state != last_ui_state {
+            last_ui_state = ui_state;
+            needs_redraw = true;
+        }
+
+        // The redraw goes last so the IMU read and input always get their turn first. When
+        // they used up the iteration's budget the frame waits (needs_redraw stays set) for a
+        // later one; only frames that actually drew feed the cost estimate.
+        if panel_awake && needs_redraw && loop_budget.admit(LoopTask::Redraw) {
+            // Watch faces and the transform animation redraw every frame: start them at
+            // the panel's blanking so the flush doesn't shear
+            #[cfg(feature = "esp32s3-disp143Oled")]
+            if let Some(te) = te_pin.as_mut() {
+                let animated = matches!(last_ui_state.page, Page::Watch(_))
+                    || matches!(last_ui_state.dialog, Some(Dialog::TransformPage));
+                if animated {
+                    my_display.wait_for_te(te);
+                }
+            }
+            let frame_t0 = monotonic_us();
+            let report = update_ui(&mut my_display, last_ui_state, needs_redraw);
+            needs_redraw = false;
+            if report.drew {
+                let frame_us = monotonic_us().saturating_sub(frame_t0);
+                loop_budget.record(LoopTask::Redraw, frame_us);
+                let frame_ms = frame_us / 1000;
+                if frame_ms > SLOW_FRAME_MS {
+                    println!("slow frame: {} ms, dirty {:?}", frame_ms, report.dirty);
+                }
+            }
+        }
+
         // Minimal delay to keep polling responsive
     }
 }
@@ -20,28 +20,153 @@
 use core::fmt;
 
 use embedded_graphics::{pixelcolor::Rgb565, prelude::*, primitives::Rectangle};
-use embedded_hal::digital::OutputPin;
+use embedded_hal::digital::{InputPin, OutputPin};
 
 use esp_hal::{gpio::Output, Blocking};
 
 use embedded_graphics::prelude::IntoStorage;
 
-use esp_hal::spi::master::{Address, Command, DataMode, SpiDmaBus};
+use esp_hal::dma::{DmaRxBuf, DmaTxBuf};
+use esp_hal::spi::master::{Address, Command, DataMode, SpiDmaBus, SpiDmaTransfer};
 // use embedded_hal::delay::DelayNs;
 
 extern crate alloc;
 use bytemuck::cast_slice;
 
+use crate::sched::monotonic_us;
+
 // Public constants so the rest of your code can adopt 466×466 easily.
 pub const CO5300_WIDTH: u16 = 466;
 pub const CO5300_HEIGHT: u16 = 466;
+// Visible area inside the controller's GRAM: 466 lines starting at column 6, row 0, in a
+// 480x480 GRAM. Mirroring an axis moves the visible area to the other end of it.
+const GRAM_SIZE: u16 = 480;
+const PANEL_X_OFF: u16 = 0x0006;
+const PANEL_Y_OFF: u16 = 0x0000;
+const MADCTL_MY: u8 = 0x80;
+const MADCTL_MX: u8 = 0x40;
+const MADCTL_MV: u8 = 0x20;
 const RAMWR_OPCODE: u8 = 0x2C;
+
+// Brightness percent -> 0x51 level along a 2.2 gamma, so each percent step looks like the
+// same change in light. Precomputed (round(255 * (pct/100)^2.2), at least 1 above 0%).
+const PERCEPTUAL_BRIGHTNESS: [u8; 101] = [
+    0, 1, 1, 1, 1, 1, 1, 1, 1, 1, 2, 2, 2, 3, 3, 4, 5, 5, 6, 7, 7, 8, 9, 10, 11, 12, 13, 14, 15,
+    17, 18, 19, 21, 22, 24, 25, 27, 29, 30, 32, 34, 36, 38, 40, 42, 44, 46, 48, 51, 53, 55, 58, 60,
+    63, 66, 68, 71, 74, 77, 80, 83, 86, 89, 92, 96, 99, 102, 106, 109, 113, 116, 120, 124, 128,
+    131, 135, 139, 143, 148, 152, 156, 160, 165, 169, 174, 178, 183, 188, 192, 197, 202, 207, 212,
+    217, 223, 228, 233, 238, 244, 249, 255,
+];
 const RAMWRC_OPCODE: u8 = 0x3C;
 
 // Use a small CPU staging buffer per call (HAL will copy it into DMA TX buffer)
 const STAGE_BYTES: usize = 4096; // safe on stack; adjust if needed
 const DMA_CHUNK_SIZE: usize = 32 * 1023; // max DMA chunk size for ESP32-S3 SPI
 
+// Per-channel gamma lookup over RGB565 levels (5-bit red/blue share a table, 6-bit green).
+// gamma < 1 lifts dark tones, which keeps dim content legible at low panel brightness.
+#[derive(Clone)]
+pub struct GammaLut {
+    gamma: f32,
+    rb: [u8; 32],
+    g: [u8; 64],
+}
+
+impl GammaLut {
+    pub fn new(gamma: f32) -> Self {
+        fn curve<const N: usize>(gamma: f32) -> [u8; N] {
+            let max = (N - 1) as f32;
+            let mut out = [0u8; N];
+            for (i, v) in out.iter_mut().enumerate() {
+                *v = libm::roundf(max * libm::powf(i as f32 / max, gamma)).clamp(0.0, max) as u8;
+            }
+            out
+        }
+        Self {
+            gamma,
+            rb: curve::<32>(gamma),
+            g: curve::<64>(gamma),
+        }
+    }
+
+    pub fn gamma(&self) -> f32 {
+        self.gamma
+    }
+
+    // Map one native-order RGB565 pixel
+    #[inline(always)]
+    pub fn apply(&self, c: u16) -> u16 {
+        let r = self.rb[(c >> 11) as usize] as u16;
+        let g = self.g[((c >> 5) & 0x3F) as usize] as u16;
+        let b = self.rb[(c & 0x1F) as usize] as u16;
+        (r << 11) | (g << 5) | b
+    }
+}
+
+// Tearing effect output (TEON 0x35 / TEOFF 0x34). The TE line goes high during vertical
+// blanking (and, with VHBlank, also between lines).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TeMode {
+    Off,
+    VBlank,
+    VHBlank,
+}
+
+// Longest wait for a TE edge (a frame at 60 Hz is ~16.7 ms)
+const TE_WAIT_TIMEOUT_US: u64 = 40_000;
+
+// Hardware mounting rotation, clockwise, applied through MADCTL (`set_rotation`). Meant to
+// be set once for how the panel sits in the case; see `Orientation` for runtime turns.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Rotation {
+    Deg0,
+    Deg90,
+    Deg180,
+    Deg270,
+}
+
+impl Rotation {
+    fn madctl(self) -> u8 {
+        match self {
+            Rotation::Deg0 => 0x00,
+            Rotation::Deg90 => MADCTL_MV | MADCTL_MX,
+            Rotation::Deg180 => MADCTL_MX | MADCTL_MY,
+            Rotation::Deg270 => MADCTL_MV | MADCTL_MY,
+        }
+    }
+
+    fn swaps_axes(self) -> bool {
+        matches!(self, Rotation::Deg90 | Rotation::Deg270)
+    }
+}
+
+// Panel rotation, clockwise. Drawing always uses upright (logical) coordinates and the FB
+// stays in that layout; only what is streamed to the panel is rotated, in software since
+// the CO5300 has no row/column exchange. Quarter turns need a square panel.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Orientation {
+    Deg0,
+    Deg90,
+    Deg180,
+    Deg270,
+}
+
+impl Orientation {
+    // 0..=3 quarter turns clockwise (wraps)
+    pub fn from_quarter_turns(q: u8) -> Self {
+        match q % 4 {
+            0 => Orientation::Deg0,
+            1 => Orientation::Deg90,
+            2 => Orientation::Deg180,
+            _ => Orientation::Deg270,
+        }
+    }
+
+    fn is_quarter(self) -> bool {
+        matches!(self, Orientation::Deg90 | Orientation::Deg270)
+    }
+}
+
 // Error type that wraps SPI and GPIO errors.
 #[derive(Debug)]
 pub enum Co5300Error<SpiE, GpioE> {
@@ -66,8 +191,19 @@ pub struct Co5300Display<'fb, RST> {
     h: u16,
     x_off: u16,
     y_off: u16,
-    fb: &'fb mut [u16],             // framebuffer storage
+    rotation: Rotation, // MADCTL mounting rotation; w/h are already swapped for it
+    inverted: bool,     // INVON active
+    scroll: Option<(u16, u16)>, // vertical scroll region (first line, lines), from set_scroll_area
+    dma_job: Option<DmaJob>, // FB flush being streamed by DMA, see flush_rect_even_dma
+    quad: bool,         // QPI entered (0x38) and not exited since
+    idle: bool,         // IDMON active (8-color idle mode)
+    fb: &'fb mut [u16], // framebuffer storage
+    front: Option<&'fb mut [u16]>, // double buffering: copy of what the panel shows
     stage: alloc::boxed::Box<[u8]>, // staging buffer for writes
+    touched: Option<(u16, u16, u16, u16)>, // union of panel windows written since last take
+    dirty: Option<(u16, u16, u16, u16)>, // FB region written directly, waiting for flush_dirty
+    orientation: Orientation, // rotation applied when streaming to the panel
+    gamma: Option<GammaLut>, // applied by blit_rect_be_fast* when set
 }
 
 impl<'fb, RST> Co5300Display<'fb, RST>
@@ -108,6 +244,9 @@ where
                 src_off += 2;
             }
         }
+        if w > 0 && h > 0 {
+            self.mark_dirty(x, y, x + w - 1, y + h - 1);
+        }
         Ok(())
     }
 
@@ -137,10 +276,21 @@ where
             rst,
             w: width,
             h: height,
-            x_off: 0x0006,
-            y_off: 0x0000,
+            x_off: PANEL_X_OFF,
+            y_off: PANEL_Y_OFF,
+            rotation: Rotation::Deg0,
+            inverted: false,
+            scroll: None,
+            dma_job: None,
+            quad: false,
+            idle: false,
             fb,
+            front: None,
             stage: alloc::vec![0u8; STAGE_BYTES].into_boxed_slice(),
+            touched: None,
+            dirty: None,
+            orientation: Orientation::Deg0,
+            gamma: None,
         };
 
         // Hard reset sequence
@@ -240,6 +390,48 @@ where
         (self.w, self.h)
     }
 
+    // Rotate the panel in hardware (MADCTL row/column exchange and mirroring). For 90/270
+    // the width and height swap, the FB is reinterpreted with the new row width, and the
+    // GRAM offsets follow the axes so windows still land on the visible area. FB content is
+    // not moved: redraw everything afterwards.
+    pub fn set_rotation(&mut self, rot: Rotation) -> Result<(), Co5300Error<(), RST::Error>> {
+        let (nw, nh) = if self.rotation.swaps_axes() {
+            (self.h, self.w)
+        } else {
+            (self.w, self.h)
+        };
+        let m = rot.madctl();
+        let along = |off: u16, len: u16, mirror: bool| {
+            if mirror {
+                GRAM_SIZE - off - len
+            } else {
+                off
+            }
+        };
+        // Logical columns run along GRAM rows once MV exchanges the axes
+        let ((cx_off, cx_len), (ry_off, ry_len)) = if m & MADCTL_MV != 0 {
+            ((PANEL_Y_OFF, nh), (PANEL_X_OFF, nw))
+        } else {
+            ((PANEL_X_OFF, nw), (PANEL_Y_OFF, nh))
+        };
+
+        self.qspi_exit_single();
+        let res = self.cmd(0x36, &[m]);
+        self.qspi_enter_quad();
+        res?;
+
+        self.rotation = rot;
+        self.x_off = along(cx_off, cx_len, m & MADCTL_MX != 0);
+        self.y_off = along(ry_off, ry_len, m & MADCTL_MY != 0);
+        (self.w, self.h) = if rot.swaps_axes() { (nh, nw) } else { (nw, nh) };
+        self.dirty = None;
+        Ok(())
+    }
+
+    pub fn rotation(&self) -> Rotation {
+        self.rotation
+    }
+
     // Raw window set (no even expansion, still applies panel offsets)
     fn set_window_raw(
         &mut self,
@@ -252,6 +444,7 @@ where
         if x0 > x1 || y0 > y1 || x1 >= self.w || y1 >= self.h {
             return Err(Co5300Error::OutOfBounds);
         }
+        self.mark_touched(x0, y0, x1, y1);
 
         // Apply panel offsets
         let x0p = x0 + self.x_off;
@@ -279,6 +472,90 @@ where
         Ok(())
     }
 
+    // Every pixel write opens a window first, so the union of windows is what reached the panel.
+    fn mark_touched(&mut self, x0: u16, y0: u16, x1: u16, y1: u16) {
+        self.touched = Some(match self.touched {
+            None => (x0, y0, x1, y1),
+            Some((a, b, c, d)) => (a.min(x0), b.min(y0), c.max(x1), d.max(y1)),
+        });
+    }
+
+    // Map an inclusive logical rect to where it lands on the panel
+    fn panel_rect(&self, x0: u16, y0: u16, x1: u16, y1: u16) -> (u16, u16, u16, u16) {
+        let (wm, hm) = (self.w - 1, self.h - 1);
+        match self.orientation {
+            Orientation::Deg0 => (x0, y0, x1, y1),
+            Orientation::Deg90 => (wm - y1, x0, wm - y0, x1),
+            Orientation::Deg180 => (wm - x1, hm - y1, wm - x0, hm - y0),
+            Orientation::Deg270 => (y0, hm - x1, y1, hm - x0),
+        }
+    }
+
+    // Change the panel rotation. Returns true if it changed; the panel still shows the old
+    // layout until everything is redrawn (e.g. `flush_rect_even` over the whole FB).
+    // Quarter turns are refused on a non-square panel.
+    pub fn set_orientation(&mut self, orientation: Orientation) -> bool {
+        if orientation == self.orientation || (orientation.is_quarter() && self.w != self.h) {
+            return false;
+        }
+        self.orientation = orientation;
+        true
+    }
+
+    pub fn orientation(&self) -> Orientation {
+        self.orientation
+    }
+
+    // Stream a w×h logical rect to the panel window already opened for it, in panel scan
+    // order. `pixel(col, row)` returns the native RGB565 value at that offset in the rect.
+    fn stream_rotated(
+        &mut self,
+        w: usize,
+        h: usize,
+        pixel: impl Fn(usize, usize) -> u16,
+    ) -> Result<(), Co5300Error<(), RST::Error>> {
+        let o = self.orientation;
+        let (pw, ph) = if o.is_quarter() { (h, w) } else { (w, h) };
+        let instruction = Command::_8Bit(0x32, DataMode::Quad);
+        let mut current_cmd = RAMWR_OPCODE;
+        let bus: &mut SpiDmaBus<'fb, Blocking> = self.spi.bus.ready();
+        let stage = &mut self.stage;
+        let mut filled = 0usize;
+        for pr in 0..ph {
+            for pc in 0..pw {
+                let (c, r) = match o {
+                    Orientation::Deg0 => (pc, pr),
+                    Orientation::Deg90 => (pr, h - 1 - pc),
+                    Orientation::Deg180 => (w - 1 - pc, h - 1 - pr),
+                    Orientation::Deg270 => (w - 1 - pr, pc),
+                };
+                stage[filled..filled + 2].copy_from_slice(&pixel(c, r).to_be_bytes());
+                filled += 2;
+                if filled == stage.len() || (pr == ph - 1 && pc == pw - 1) {
+                    let address = Address::_24Bit((current_cmd as u32) << 8, DataMode::Quad);
+                    let _ = self.spi.cs.set_low();
+                    let res = bus.half_duplex_write(
+                        DataMode::Quad,
+                        instruction,
+                        address,
+                        0,
+                        &stage[..filled],
+                    );
+                    let _ = self.spi.cs.set_high();
+                    res.map_err(|_| Co5300Error::Spi(()))?;
+                    current_cmd = RAMWRC_OPCODE;
+                    filled = 0;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Bounding box of everything written to the panel since the last call (inclusive), then reset.
+    pub fn take_touched_rect(&mut self) -> Option<(u16, u16, u16, u16)> {
+        self.touched.take()
+    }
+
     // QSPI variant: send CASET/RASET using quad instruction/address/data while in QPI.
     fn qspi_set_window_raw(
         &mut self,
@@ -290,6 +567,9 @@ where
         if x0 > x1 || y0 > y1 || x1 >= self.w || y1 >= self.h {
             return Err(Co5300Error::OutOfBounds);
         }
+        self.flush_wait();
+        self.mark_touched(x0, y0, x1, y1);
+        let (x0, y0, x1, y1) = self.panel_rect(x0, y0, x1, y1);
 
         let x0p = x0 + self.x_off;
         let x1p = x1 + self.x_off;
@@ -314,10 +594,13 @@ where
             let instruction = Command::_8Bit(0x02, DataMode::Quad);
             let address = Address::_24Bit((cmd as u32) << 8, DataMode::Quad);
             let _ = self.spi.cs.set_low();
-            let res = self
-                .spi
-                .bus
-                .half_duplex_write(DataMode::Quad, instruction, address, 0, data);
+            let res = self.spi.bus.ready().half_duplex_write(
+                DataMode::Quad,
+                instruction,
+                address,
+                0,
+                data,
+            );
             let _ = self.spi.cs.set_high();
             res.map_err(|_| Co5300Error::Spi(()))
         };
@@ -388,8 +671,10 @@ where
         // Re-assert format/orientation if needed
         self.qspi_exit_single();
         self.cmd(0x3A, &[0x55])?; // RGB565
-        self.cmd(0x36, &[0x00])?; // MADCTL
-                                  // Optionally restore brightness
+        self.cmd(0x36, &[self.rotation.madctl()])?; // MADCTL
+        self.cmd(if self.inverted { 0x21 } else { 0x20 }, &[])?; // INVON / INVOFF
+        self.cmd(if self.idle { 0x39 } else { 0x38 }, &[])?; // IDMON / IDMOFF
+                                                             // Optionally restore brightness
         self.set_brightness(0xFF)?;
         self.qspi_enter_quad();
 
@@ -397,6 +682,132 @@ where
         Ok(())
     }
 
+    // Panel-side color inversion (INVON 0x21 / INVOFF 0x20). The FB is untouched, so this
+    // flips the whole screen at once without a redraw.
+    pub fn set_invert(&mut self, invert: bool) -> Result<(), Co5300Error<(), RST::Error>> {
+        self.qspi_exit_single();
+        let res = self.cmd(if invert { 0x21 } else { 0x20 }, &[]);
+        self.qspi_enter_quad();
+        res?;
+        self.inverted = invert;
+        Ok(())
+    }
+
+    pub fn inverted(&self) -> bool {
+        self.inverted
+    }
+
+    // Idle mode (IDMON 0x39 / IDMOFF 0x38): the panel drops to 8 colors, each channel cut to
+    // its top bit, for lower power while the image stays up. Gradients, images and
+    // antialiasing collapse to flat primaries, so keep it to simple high-contrast faces.
+    // GRAM keeps full color, so turning it off restores the image without a redraw.
+    // (IDMOFF shares 0x38 with the bare QPI-enter instruction; here it goes inside a 0x02
+    // register write from 1-wire mode, which the panel treats as the DCS command.)
+    pub fn idle_mode(&mut self, on: bool) -> Result<(), Co5300Error<(), RST::Error>> {
+        self.qspi_exit_single();
+        let res = self.cmd(if on { 0x39 } else { 0x38 }, &[]);
+        self.qspi_enter_quad();
+        res?;
+        self.idle = on;
+        Ok(())
+    }
+
+    pub fn idle(&self) -> bool {
+        self.idle
+    }
+
+    // Define the hardware vertical scroll layout (VSCRDEF 0x33): a fixed band at the top,
+    // the scrolling band, and a fixed band at the bottom, in panel lines. They must add up
+    // to the panel height. Lines are panel rows, so this ignores `Orientation` turns.
+    pub fn set_scroll_area(
+        &mut self,
+        top_fixed: u16,
+        scroll: u16,
+        bottom_fixed: u16,
+    ) -> Result<(), Co5300Error<(), RST::Error>> {
+        if top_fixed as u32 + scroll as u32 + bottom_fixed as u32 != self.h as u32 {
+            return Err(Co5300Error::OutOfBounds);
+        }
+        let mut data = [0u8; 6];
+        data[0..2].copy_from_slice(&top_fixed.to_be_bytes());
+        data[2..4].copy_from_slice(&scroll.to_be_bytes());
+        data[4..6].copy_from_slice(&bottom_fixed.to_be_bytes());
+        self.qspi_exit_single();
+        let res = self.cmd(0x33, &data);
+        self.qspi_enter_quad();
+        res?;
+        self.scroll = Some((top_fixed, scroll));
+        Ok(())
+    }
+
+    // Set which GRAM line is shown at the top of the scroll band (VSCSAD 0x37). Content drawn
+    // once into the band wraps around as this advances, so a ticker only needs this call per
+    // frame. `line` must fall inside the band set by `set_scroll_area`.
+    pub fn set_scroll_start(&mut self, line: u16) -> Result<(), Co5300Error<(), RST::Error>> {
+        let Some((top, lines)) = self.scroll else {
+            return Err(Co5300Error::OutOfBounds);
+        };
+        if line < top || line >= top + lines {
+            return Err(Co5300Error::OutOfBounds);
+        }
+        self.qspi_exit_single();
+        let res = self.cmd(0x37, &line.to_be_bytes());
+        self.qspi_enter_quad();
+        res
+    }
+
+    // Send any command with parameters, for vendor registers the driver doesn't wrap (gamma
+    // 0xE0/0xE1, VCOM, porch timing...). Drops to 1-wire for the command if QPI is active and
+    // returns to it afterwards. Nothing is validated: an unknown command or a bad parameter
+    // can desync the panel until the next reset, and state the driver caches (MADCTL,
+    // inversion, scroll) is not updated, so use the dedicated setters for those.
+    pub fn write_command(
+        &mut self,
+        cmd: u8,
+        data: &[u8],
+    ) -> Result<(), Co5300Error<(), RST::Error>> {
+        let was_quad = self.quad;
+        if was_quad {
+            self.qspi_exit_single();
+        }
+        let res = self.cmd(cmd, data);
+        if was_quad {
+            self.qspi_enter_quad();
+        }
+        res
+    }
+
+    // Turn the TE output on (scanline mode byte for TEON) or off
+    pub fn enable_tearing(&mut self, mode: TeMode) -> Result<(), Co5300Error<(), RST::Error>> {
+        self.qspi_exit_single();
+        let res = match mode {
+            TeMode::Off => self.cmd(0x34, &[]),
+            TeMode::VBlank => self.cmd(0x35, &[0x00]),
+            TeMode::VHBlank => self.cmd(0x35, &[0x01]),
+        };
+        self.qspi_enter_quad();
+        res
+    }
+
+    // Spin until TE rises (start of blanking), so a flush issued right after writes GRAM
+    // ahead of the scan instead of through it. Needs `enable_tearing` first. Returns false
+    // if no edge came within the timeout (TE off or not wired); the caller can just draw.
+    pub fn wait_for_te(&mut self, te_pin: &mut impl InputPin) -> bool {
+        let deadline = monotonic_us() + TE_WAIT_TIMEOUT_US;
+        // Already inside blanking: wait for it to end so the whole next window is ours
+        while te_pin.is_high().unwrap_or(false) {
+            if monotonic_us() >= deadline {
+                return false;
+            }
+        }
+        while !te_pin.is_high().unwrap_or(false) {
+            if monotonic_us() >= deadline {
+                return false;
+            }
+        }
+        true
+    }
+
     // adjustable brightness (0-255)
     pub fn set_brightness(&mut self, bright: u8) -> Result<(), Co5300Error<(), RST::Error>> {
         // exit qspi if needed
@@ -406,6 +817,16 @@ where
         res
     }
 
+    // Brightness in percent (0-100, clamped) through the perceptual table instead of the
+    // linear pct*255/100, where low percents look almost off on the AMOLED and the top
+    // third barely changes.
+    pub fn set_brightness_perceptual(
+        &mut self,
+        pct: u8,
+    ) -> Result<(), Co5300Error<(), RST::Error>> {
+        self.set_brightness(PERCEPTUAL_BRIGHTNESS[pct.min(100) as usize])
+    }
+
     // Flush an FB rectangle, forcing even start/end (2x2 tiles), using raw window, important for embedded-graphics integration.
     fn flush_fb_rect_even(
         &mut self,
@@ -428,59 +849,156 @@ where
 
         // Use quad window and quad payload streaming
         self.qspi_set_window_raw(ax0, ay0, ax1, ay1)?;
+        self.sync_front(ax0, ay0, ax1, ay1);
 
-        let fbw = self.w as usize;
-        let instruction = Command::_8Bit(0x32, DataMode::Quad);
-        let mut current_cmd = RAMWR_OPCODE;
-        let address_mode = DataMode::Quad;
-        let data_mode = DataMode::Quad;
-        let bus: &mut SpiDmaBus<'fb, Blocking> = &mut self.spi.bus;
-
-        // Use staging buffer directly; no realloc per call
-        let stage = &mut self.stage;
-        let mut filled = 0usize;
+        // A flush covering the pending dirty region makes it clean
+        if let Some((dx0, dy0, dx1, dy1)) = self.dirty {
+            if dx0 >= ax0 && dy0 >= ay0 && dx1 <= ax1 && dy1 <= ay1 {
+                self.dirty = None;
+            }
+        }
 
-        for y in ay0..=ay1 {
-            // get row slice from FB
-            let row_base = (y as usize) * fbw + (ax0 as usize);
-            let row = &self.fb[row_base..row_base + ew];
-            let row_bytes = cast_slice(row);
-            let mut off = 0usize;
+        if self.orientation != Orientation::Deg0 {
+            let fb = core::mem::take(&mut self.fb);
+            let fbw = self.w as usize;
+            let (bx, by) = (ax0 as usize, ay0 as usize);
+            let eh = (ay1 - ay0 + 1) as usize;
+            let res = self.stream_rotated(ew, eh, |c, r| u16::from_be(fb[(by + r) * fbw + bx + c]));
+            self.fb = fb;
+            return res;
+        }
 
-            // stream row bytes into staging buffer and send when full
-            while off < row_bytes.len() {
-                let space = stage.len().saturating_sub(filled);
-                let take = core::cmp::min(space, row_bytes.len() - off);
-                stage[filled..filled + take].copy_from_slice(&row_bytes[off..off + take]);
-                filled += take;
-                off += take;
+        // One RAMWR per flush: CS stays asserted for the whole payload. Only the first write
+        // carries the 0x32/RAMWR header; later writes are bare quad data, which the panel
+        // treats as a continuation of the same memory write.
+        let fbw = self.w as usize;
+        let bus: &mut SpiDmaBus<'fb, Blocking> = self.spi.bus.ready();
+        let mut header = true;
+        let mut send = |bytes: &[u8]| {
+            let (instruction, address) = if header {
+                header = false;
+                (
+                    Command::_8Bit(0x32, DataMode::Quad),
+                    Address::_24Bit((RAMWR_OPCODE as u32) << 8, DataMode::Quad),
+                )
+            } else {
+                (Command::None, Address::None)
+            };
+            bus.half_duplex_write(DataMode::Quad, instruction, address, 0, bytes)
+        };
 
-                if filled == stage.len() {
-                    // send current chunk over quad
-                    let ad: u32 = (current_cmd as u32) << 8;
-                    let address = Address::_24Bit(ad, address_mode);
-                    let _ = self.spi.cs.set_low();
-                    let res =
-                        bus.half_duplex_write(data_mode, instruction, address, 0, &stage[..filled]);
-                    let _ = self.spi.cs.set_high();
-                    res.map_err(|_| Co5300Error::Spi(()))?;
-                    current_cmd = RAMWRC_OPCODE;
-                    filled = 0;
+        let _ = self.spi.cs.set_low();
+        let res = if ax0 == 0 && ew == fbw {
+            // Full-width rows are contiguous in the FB: send straight from it, no staging copy.
+            let start = (ay0 as usize) * fbw;
+            let end = (ay1 as usize + 1) * fbw;
+            let bytes: &[u8] = cast_slice(&self.fb[start..end]);
+            bytes.chunks(DMA_CHUNK_SIZE).try_for_each(&mut send)
+        } else {
+            // Gather rows into the staging buffer and send whenever it fills.
+            let stage = &mut self.stage;
+            let mut filled = 0usize;
+            let mut res = Ok(());
+            'rows: for y in ay0..=ay1 {
+                let row_base = (y as usize) * fbw + (ax0 as usize);
+                let row_bytes: &[u8] = cast_slice(&self.fb[row_base..row_base + ew]);
+                let mut off = 0usize;
+                while off < row_bytes.len() {
+                    let space = stage.len() - filled;
+                    let take = core::cmp::min(space, row_bytes.len() - off);
+                    stage[filled..filled + take].copy_from_slice(&row_bytes[off..off + take]);
+                    filled += take;
+                    off += take;
+                    if filled == stage.len() {
+                        res = send(&stage[..filled]);
+                        filled = 0;
+                        if res.is_err() {
+                            break 'rows;
+                        }
+                    }
                 }
             }
+            if res.is_ok() && filled > 0 {
+                res = send(&stage[..filled]);
+            }
+            res
+        };
+        let _ = self.spi.cs.set_high();
+        res.map_err(|_| Co5300Error::Spi(()))
+    }
+
+    // Direct framebuffer access for per-pixel effects. Row-major, `width()` pixels per row.
+    // Each entry is RGB565 stored big-endian (`color.into_storage().to_be()`), the byte order
+    // the panel expects on the wire, so read with `u16::from_be` and write with `.to_be()`.
+    // The gamma LUT is not applied to direct writes. Nothing reaches the panel until the
+    // region is passed to `mark_dirty` and flushed (`flush_dirty` or `flush_rect_even`).
+    pub fn framebuffer(&self) -> &[u16] {
+        self.fb
+    }
+
+    pub fn framebuffer_mut(&mut self) -> &mut [u16] {
+        self.fb
+    }
+
+    // Copy a rect of what is on screen into `out` as RGB565 big-endian bytes (w*h*2),
+    // for screenshots and debugging. This reads the software FB rather than GRAM: a RAMRD
+    // (0x2E) read-back needs a quad read with dummy cycles that hasn't been validated on
+    // this board, while the FB already mirrors the panel for everything except blits made
+    // with `update_fb = false`. Logical coordinates, so
+    // the capture is upright whatever `Orientation` is set; gamma is already applied.
+    pub fn snapshot_fb(
+        &self,
+        x: u16,
+        y: u16,
+        w: u16,
+        h: u16,
+        out: &mut [u8],
+    ) -> Result<(), Co5300Error<(), RST::Error>> {
+        if x as u32 + w as u32 > self.w as u32 || y as u32 + h as u32 > self.h as u32 {
+            return Err(Co5300Error::OutOfBounds);
+        }
+        let row_bytes = (w as usize) * 2;
+        if out.len() != row_bytes * (h as usize) {
+            return Err(Co5300Error::OutOfBounds);
+        }
+        if row_bytes == 0 {
+            return Ok(());
         }
+        let fbw = self.w as usize;
+        for (r, dst) in out.chunks_exact_mut(row_bytes).enumerate() {
+            let base = (y as usize + r) * fbw + x as usize;
+            dst.copy_from_slice(cast_slice(&self.fb[base..base + w as usize]));
+        }
+        Ok(())
+    }
 
-        // send any remaining data
-        if filled > 0 {
-            let ad: u32 = (current_cmd as u32) << 8;
-            let address = Address::_24Bit(ad, address_mode);
-            let _ = self.spi.cs.set_low();
-            let res = bus.half_duplex_write(data_mode, instruction, address, 0, &stage[..filled]);
-            let _ = self.spi.cs.set_high();
-            res.map_err(|_| Co5300Error::Spi(()))?;
+    // Record an inclusive FB rectangle as changed. The FB-only helpers (`write_rect_fb`,
+    // `fill_rect_fb`, `draw_line_fb*`, ...) call this themselves; direct writes through
+    // `framebuffer_mut` need it by hand. Calls accumulate into one bounding box, clipped
+    // to the panel.
+    pub fn mark_dirty(&mut self, x0: u16, y0: u16, x1: u16, y1: u16) {
+        if x0 > x1 || y0 > y1 || x0 >= self.w || y0 >= self.h {
+            return;
         }
+        let (x1, y1) = (x1.min(self.w - 1), y1.min(self.h - 1));
+        self.dirty = Some(match self.dirty {
+            None => (x0, y0, x1, y1),
+            Some((a, b, c, d)) => (a.min(x0), b.min(y0), c.max(x1), d.max(y1)),
+        });
+    }
 
-        Ok(())
+    // Take the region collected by `mark_dirty` without flushing it.
+    pub fn take_dirty(&mut self) -> Option<(u16, u16, u16, u16)> {
+        self.dirty.take()
+    }
+
+    // Flush the region collected by `mark_dirty` (if any), then reset it. Nothing dirty,
+    // nothing sent.
+    pub fn flush_dirty(&mut self) -> Result<(), Co5300Error<(), RST::Error>> {
+        match self.dirty.take() {
+            Some((x0, y0, x1, y1)) => self.flush_fb_rect_even(x0, y0, x1, y1),
+            None => Ok(()),
+        }
     }
 
     // Public wrapper to flush an FB rectangle.
@@ -494,6 +1012,222 @@ where
         self.flush_fb_rect_even(x0, y0, x1, y1)
     }
 
+    // Start flushing an FB rectangle (even-aligned like `flush_rect_even`) in the background.
+    // Rows are copied into the DMA TX buffer one chunk (up to 64 KB) at a time and the CPU is
+    // free while a chunk is on the wire; call `flush_poll` regularly to start the next chunk.
+    //
+    // Lifetime rules: the FB rows of a chunk are read when that chunk starts, so drawing
+    // into rows not yet sent shows up in this flush (draw into rows already sent, or
+    // `flush_wait` first). Any other panel access (draws, commands, the sync flush) first
+    // waits for the whole flush to finish, so nothing interleaves with it on the bus.
+    // With double buffering the rect is copied to the front buffer up front and streamed
+    // from there, so drawing can go on right away.
+    // Rotated panels (`set_orientation`) fall back to the synchronous flush.
+    pub fn flush_rect_even_dma(
+        &mut self,
+        x0: u16,
+        y0: u16,
+        x1: u16,
+        y1: u16,
+    ) -> Result<FlushToken, Co5300Error<(), RST::Error>> {
+        if x0 > x1 || y0 > y1 || x0 >= self.w || y0 >= self.h {
+            return Err(Co5300Error::OutOfBounds);
+        }
+        let ax0 = x0 & !1;
+        let ay0 = y0 & !1;
+        let ax1 = (x1 | 1).min(self.w - 1);
+        let ay1 = (y1 | 1).min(self.h - 1);
+        let token = FlushToken {
+            rect: (ax0, ay0, ax1, ay1),
+        };
+        if self.orientation != Orientation::Deg0 {
+            self.flush_fb_rect_even(ax0, ay0, ax1, ay1)?;
+            return Ok(token);
+        }
+
+        self.qspi_set_window_raw(ax0, ay0, ax1, ay1)?;
+        self.sync_front(ax0, ay0, ax1, ay1);
+        self.dma_flush_start(ax0, ay0, ax1, ay1)?;
+        Ok(token)
+    }
+
+    // Window already set: start streaming an even-aligned rect (from the front buffer when
+    // double buffering, else the FB) and clear the dirty region it covers
+    fn dma_flush_start(
+        &mut self,
+        ax0: u16,
+        ay0: u16,
+        ax1: u16,
+        ay1: u16,
+    ) -> Result<(), Co5300Error<(), RST::Error>> {
+        if let Some((dx0, dy0, dx1, dy1)) = self.dirty {
+            if dx0 >= ax0 && dy0 >= ay0 && dx1 <= ax1 && dy1 <= ay1 {
+                self.dirty = None;
+            }
+        }
+        self.dma_job = Some(DmaJob {
+            x0: ax0,
+            x1: ax1,
+            y1: ay1,
+            next_row: ay0,
+        });
+        let _ = self.spi.cs.set_low();
+        self.dma_next_chunk(true)
+    }
+
+    // Double buffering: `second` (same size as the FB) becomes the front buffer, a copy of
+    // what the panel shows, and the FB becomes the back buffer that every drawing helper
+    // keeps writing to. `swap_buffers` then publishes a finished frame: DMA streams it from
+    // the front while the next frame is drawn into the back, so a slow flush never sends a
+    // half-drawn frame. Costs a second full-size buffer. Returns false on a size mismatch.
+    pub fn enable_double_buffer(&mut self, second: &'fb mut [u16]) -> bool {
+        if second.len() != self.fb.len() {
+            return false;
+        }
+        self.flush_wait();
+        second.copy_from_slice(self.fb);
+        self.front = Some(second);
+        true
+    }
+
+    // Back to a single FB; hands the second buffer back
+    pub fn disable_double_buffer(&mut self) -> Option<&'fb mut [u16]> {
+        self.flush_wait();
+        self.front.take()
+    }
+
+    pub fn double_buffered(&self) -> bool {
+        self.front.is_some()
+    }
+
+    // Publish the finished back buffer: the region drawn since the last swap (`mark_dirty`)
+    // starts going out by DMA, the buffers trade places and the new back gets that region
+    // copied in, so drawing continues from the frame just shown. Keep `flush_poll` running
+    // as with `flush_rect_even_dma`; the next swap waits for this flush. Without double
+    // buffering this is `flush_dirty`.
+    pub fn swap_buffers(&mut self) -> Result<(), Co5300Error<(), RST::Error>> {
+        if self.front.is_none() {
+            return self.flush_dirty();
+        }
+        let Some((x0, y0, x1, y1)) = self.dirty else {
+            return Ok(());
+        };
+        let ax0 = x0 & !1;
+        let ay0 = y0 & !1;
+        let ax1 = (x1 | 1).min(self.w - 1);
+        let ay1 = (y1 | 1).min(self.h - 1);
+
+        // The front may still be streaming the previous frame
+        self.flush_wait();
+        if let Some(front) = self.front.as_mut() {
+            core::mem::swap(&mut self.fb, front);
+        }
+        self.copy_rect(ax0, ay0, ax1, ay1, false);
+
+        if self.orientation != Orientation::Deg0 {
+            return self.flush_fb_rect_even(ax0, ay0, ax1, ay1);
+        }
+        self.qspi_set_window_raw(ax0, ay0, ax1, ay1)?;
+        self.dma_flush_start(ax0, ay0, ax1, ay1)
+    }
+
+    // Double buffering: the front keeps mirroring the panel when FB rects are sent directly
+    fn sync_front(&mut self, x0: u16, y0: u16, x1: u16, y1: u16) {
+        self.copy_rect(x0, y0, x1, y1, true);
+    }
+
+    // Copy an inclusive rect between the FB (back) and the front buffer, if there is one
+    fn copy_rect(&mut self, x0: u16, y0: u16, x1: u16, y1: u16, to_front: bool) {
+        let Some(front) = self.front.as_deref_mut() else {
+            return;
+        };
+        let fbw = self.w as usize;
+        for y in y0 as usize..=y1 as usize {
+            let row = y * fbw + x0 as usize..=y * fbw + x1 as usize;
+            if to_front {
+                front[row.clone()].copy_from_slice(&self.fb[row]);
+            } else {
+                self.fb[row.clone()].copy_from_slice(&front[row]);
+            }
+        }
+    }
+
+    // Keep a DMA flush going: starts the next chunk once the current one is done. Returns
+    // true when no DMA flush is in flight anymore.
+    pub fn flush_poll(&mut self) -> bool {
+        if self.dma_job.is_none() {
+            return true;
+        }
+        if self.spi.bus.is_busy() {
+            return false;
+        }
+        self.spi.bus.ready();
+        let _ = self.dma_next_chunk(false);
+        self.dma_job.is_none()
+    }
+
+    // Block until the DMA flush in flight (if any) has been fully sent
+    pub fn flush_wait(&mut self) {
+        while !self.flush_poll() {}
+    }
+
+    // Copy the next rows of the DMA job into the TX buffer and start sending them; ends the
+    // job (CS up) when all rows are out or the transfer can't start.
+    fn dma_next_chunk(&mut self, first: bool) -> Result<(), Co5300Error<(), RST::Error>> {
+        let Some(mut job) = self.dma_job else {
+            return Ok(());
+        };
+        if job.next_row > job.y1 {
+            self.dma_job = None;
+            let _ = self.spi.cs.set_high();
+            return Ok(());
+        }
+        let BusSlot::Idle(bus) = core::mem::replace(&mut self.spi.bus, BusSlot::Empty) else {
+            self.dma_job = None;
+            let _ = self.spi.cs.set_high();
+            return Err(Co5300Error::Spi(()));
+        };
+        let (spi, rx, mut tx) = bus.split();
+
+        // Whole rows only, as many as fit
+        let fbw = self.w as usize;
+        let ew = (job.x1 - job.x0 + 1) as usize;
+        let src: &[u16] = self.front.as_deref().unwrap_or(&*self.fb);
+        let buf = tx.as_mut_slice();
+        let rows = (buf.len() / (ew * 2)).min((job.y1 - job.next_row + 1) as usize);
+        for r in 0..rows {
+            let base = (job.next_row as usize + r) * fbw + job.x0 as usize;
+            let row: &[u8] = cast_slice(&src[base..base + ew]);
+            buf[r * ew * 2..(r + 1) * ew * 2].copy_from_slice(row);
+        }
+        let len = rows * ew * 2;
+        tx.set_length(len);
+        job.next_row += rows as u16;
+        self.dma_job = Some(job);
+
+        // Only the first chunk carries the RAMWR header, like the synchronous flush
+        let (instruction, address) = if first {
+            (
+                Command::_8Bit(0x32, DataMode::Quad),
+                Address::_24Bit((RAMWR_OPCODE as u32) << 8, DataMode::Quad),
+            )
+        } else {
+            (Command::None, Address::None)
+        };
+        match spi.half_duplex_write(DataMode::Quad, instruction, address, 0, len, tx) {
+            Ok(transfer) => {
+                self.spi.bus = BusSlot::Busy(transfer, rx);
+                Ok(())
+            }
+            Err((_, spi, tx)) => {
+                self.spi.bus = BusSlot::Idle(spi.with_buffers(rx, tx));
+                self.dma_job = None;
+                let _ = self.spi.cs.set_high();
+                Err(Co5300Error::Spi(()))
+            }
+        }
+    }
+
     // Draw a line directly into the framebuffer (no flush). Returns the drawn bounding box. Used for certain specific graphics.
     pub fn draw_line_fb(
         &mut self,
@@ -565,6 +1299,233 @@ where
         if minx == i32::MAX {
             None
         } else {
+            self.mark_dirty(minx as u16, miny as u16, maxx as u16, maxy as u16);
+            Some((minx as u16, miny as u16, maxx as u16, maxy as u16))
+        }
+    }
+
+    // Draw a circle of radius `r` into the framebuffer (no flush): a solid disc when `filled`,
+    // else a 1px midpoint outline. Clipped to the panel. Returns the drawn bounding box.
+    pub fn draw_circle_fb(
+        &mut self,
+        cx: i32,
+        cy: i32,
+        r: i32,
+        color: Rgb565,
+        filled: bool,
+    ) -> Option<(u16, u16, u16, u16)> {
+        if r < 0 {
+            return None;
+        }
+        let cbe = color.into_storage().to_be();
+        let mut bb: Option<(u16, u16, u16, u16)> = None;
+        let r2 = r * r;
+        if filled {
+            // One span per row; the half-width is the widest dx with dx^2 + dy^2 <= r^2
+            let mut half = r;
+            for dy in 0..=r {
+                while half * half + dy * dy > r2 {
+                    half -= 1;
+                }
+                Self::union_bb(&mut bb, self.span_fb(cy - dy, cx - half, cx + half, cbe));
+                if dy != 0 {
+                    Self::union_bb(&mut bb, self.span_fb(cy + dy, cx - half, cx + half, cbe));
+                }
+            }
+        } else {
+            let (mut x, mut y, mut err) = (r, 0i32, 1 - r);
+            while x >= y {
+                for (px, py) in [
+                    (x, y),
+                    (y, x),
+                    (-y, x),
+                    (-x, y),
+                    (-x, -y),
+                    (-y, -x),
+                    (y, -x),
+                    (x, -y),
+                ] {
+                    Self::union_bb(&mut bb, self.span_fb(cy + py, cx + px, cx + px, cbe));
+                }
+                y += 1;
+                if err < 0 {
+                    err += 2 * y + 1;
+                } else {
+                    x -= 1;
+                    err += 2 * (y - x) + 1;
+                }
+            }
+        }
+        if let Some((x0, y0, x1, y1)) = bb {
+            self.mark_dirty(x0, y0, x1, y1);
+        }
+        bb
+    }
+
+    // Fill an annular arc between `r_inner` and `r_outer` into the framebuffer (no flush).
+    // Angles are in degrees, 0 pointing right and increasing clockwise (screen y is down),
+    // swept from `a0` to `a1`; a1 <= a0 wraps past 360. Returns the drawn bounding box.
+    pub fn draw_arc_fb(
+        &mut self,
+        cx: i32,
+        cy: i32,
+        r_outer: i32,
+        r_inner: i32,
+        a0: f32,
+        a1: f32,
+        color: Rgb565,
+    ) -> Option<(u16, u16, u16, u16)> {
+        if r_outer < 0 || r_inner > r_outer {
+            return None;
+        }
+        let mut a0 = a0 % 360.0;
+        if a0 < 0.0 {
+            a0 += 360.0;
+        }
+        let mut a1 = a0 + (a1 - a0) % 360.0;
+        if a1 <= a0 {
+            a1 += 360.0;
+        }
+
+        let cbe = color.into_storage().to_be();
+        let r2_outer = r_outer * r_outer;
+        let r2_inner = r_inner.max(0) * r_inner.max(0);
+        let y_lo = (cy - r_outer).max(0);
+        let y_hi = (cy + r_outer).min(self.h as i32 - 1);
+        let x_lo = (cx - r_outer).max(0);
+        let x_hi = (cx + r_outer).min(self.w as i32 - 1);
+        let mut bb: Option<(u16, u16, u16, u16)> = None;
+        for y in y_lo..=y_hi {
+            let dy = y - cy;
+            // Runs of inside pixels along the row, written as spans
+            let mut run: Option<i32> = None;
+            for x in x_lo..=x_hi + 1 {
+                let inside = x <= x_hi && {
+                    let dx = x - cx;
+                    let d2 = dx * dx + dy * dy;
+                    d2 <= r2_outer && d2 >= r2_inner && {
+                        let mut ang = libm::atan2f(dy as f32, dx as f32).to_degrees();
+                        if ang < a0 {
+                            ang += 360.0;
+                        }
+                        if ang < a0 {
+                            ang += 360.0;
+                        }
+                        ang <= a1
+                    }
+                };
+                match (inside, run) {
+                    (true, None) => run = Some(x),
+                    (false, Some(start)) => {
+                        Self::union_bb(&mut bb, self.span_fb(y, start, x - 1, cbe));
+                        run = None;
+                    }
+                    _ => {}
+                }
+            }
+        }
+        if let Some((x0, y0, x1, y1)) = bb {
+            self.mark_dirty(x0, y0, x1, y1);
+        }
+        bb
+    }
+
+    // Write one horizontal FB span (BE color), clipped; returns what was written
+    fn span_fb(&mut self, y: i32, xa: i32, xb: i32, cbe: u16) -> Option<(u16, u16, u16, u16)> {
+        if y < 0 || y >= self.h as i32 {
+            return None;
+        }
+        let xa = xa.max(0);
+        let xb = xb.min(self.w as i32 - 1);
+        if xa > xb {
+            return None;
+        }
+        let base = (y as usize) * (self.w as usize);
+        self.fb[base + xa as usize..=base + xb as usize].fill(cbe);
+        Some((xa as u16, y as u16, xb as u16, y as u16))
+    }
+
+    fn union_bb(bb: &mut Option<(u16, u16, u16, u16)>, r: Option<(u16, u16, u16, u16)>) {
+        if let Some((x0, y0, x1, y1)) = r {
+            *bb = Some(match *bb {
+                None => (x0, y0, x1, y1),
+                Some((a, b, c, d)) => (a.min(x0), b.min(y0), c.max(x1), d.max(y1)),
+            });
+        }
+    }
+
+    // Anti-aliased variant of `draw_line_fb` (Xiaolin Wu, widened to `stroke`). Steps along the
+    // major axis and covers a span on the minor axis; the two span ends get fractional
+    // coverage blended against the current FB pixels. Returns the touched bounding box.
+    pub fn draw_line_fb_aa(
+        &mut self,
+        x0: i32,
+        y0: i32,
+        x1: i32,
+        y1: i32,
+        color: Rgb565,
+        stroke: u8,
+    ) -> Option<(u16, u16, u16, u16)> {
+        if self.w == 0 || self.h == 0 {
+            return None;
+        }
+        let steep = (y1 - y0).abs() > (x1 - x0).abs();
+        // Work in (major, minor) coordinates so one loop handles both orientations.
+        let (mut a0, mut b0, mut a1, mut b1) = if steep {
+            (y0, x0, y1, x1)
+        } else {
+            (x0, y0, x1, y1)
+        };
+        if a0 > a1 {
+            core::mem::swap(&mut a0, &mut a1);
+            core::mem::swap(&mut b0, &mut b1);
+        }
+        let da = (a1 - a0) as f32;
+        let grad = if da == 0.0 {
+            0.0
+        } else {
+            (b1 - b0) as f32 / da
+        };
+        // Minor-axis span that gives `stroke` pixels perpendicular to the line.
+        let half = stroke.max(1) as f32 * libm::sqrtf(1.0 + grad * grad) / 2.0;
+
+        let mut minx = i32::MAX;
+        let mut miny = i32::MAX;
+        let mut maxx = i32::MIN;
+        let mut maxy = i32::MIN;
+        let mut plot = |this: &mut Self, a: i32, b: i32, cov: f32| {
+            let (x, y) = if steep { (b, a) } else { (a, b) };
+            if cov <= 0.0 || x < 0 || y < 0 || x >= this.w as i32 || y >= this.h as i32 {
+                return;
+            }
+            this.blend_pixel_fb(x, y, color, (cov.min(1.0) * 255.0 + 0.5) as u8);
+            minx = minx.min(x);
+            miny = miny.min(y);
+            maxx = maxx.max(x);
+            maxy = maxy.max(y);
+        };
+
+        for a in a0..=a1 {
+            let center = b0 as f32 + grad * (a - a0) as f32;
+            let lo = center - half + 0.5; // span edges in pixel-edge coordinates
+            let hi = center + half + 0.5;
+            let lo_px = libm::floorf(lo) as i32;
+            let hi_px = libm::floorf(hi) as i32;
+            if lo_px == hi_px {
+                plot(self, a, lo_px, hi - lo);
+                continue;
+            }
+            plot(self, a, lo_px, 1.0 - (lo - lo_px as f32));
+            for b in lo_px + 1..hi_px {
+                plot(self, a, b, 1.0);
+            }
+            plot(self, a, hi_px, hi - hi_px as f32);
+        }
+
+        if minx == i32::MAX {
+            None
+        } else {
+            self.mark_dirty(minx as u16, miny as u16, maxx as u16, maxy as u16);
             Some((minx as u16, miny as u16, maxx as u16, maxy as u16))
         }
     }
@@ -586,15 +1547,163 @@ where
             return;
         }
         let fbw = self.w as usize;
-        let cbe = color.into_storage().to_be();
-        for yy in y0..=y1 {
-            let base = (yy as usize) * fbw + (x0 as usize);
-            let width = (x1 - x0 + 1) as usize;
-            let row = &mut self.fb[base..base + width];
-            for px in row.iter_mut() {
-                *px = cbe;
-            }
+        let cbe = color.into_storage().to_be();
+        for yy in y0..=y1 {
+            let base = (yy as usize) * fbw + (x0 as usize);
+            let width = (x1 - x0 + 1) as usize;
+            let row = &mut self.fb[base..base + width];
+            for px in row.iter_mut() {
+                *px = cbe;
+            }
+        }
+        self.mark_dirty(x0 as u16, y0 as u16, x1 as u16, y1 as u16);
+    }
+
+    // Vertical gradient from `top` (row y0) to `bottom` (row y1) into the FB, interpolated
+    // per RGB565 component (no flush). Returns the clipped bbox to flush, if any.
+    pub fn fill_gradient_fb(
+        &mut self,
+        x0: i32,
+        y0: i32,
+        x1: i32,
+        y1: i32,
+        top: Rgb565,
+        bottom: Rgb565,
+    ) -> Option<(u16, u16, u16, u16)> {
+        let w = self.w as i32;
+        let h = self.h as i32;
+        let (x0, x1) = (x0.min(x1), x0.max(x1));
+        let (gy0, gy1) = (y0.min(y1), y0.max(y1));
+        let (cx0, cx1) = (x0.max(0), x1.min(w - 1));
+        let (cy0, cy1) = (gy0.max(0), gy1.min(h - 1));
+        if cx0 > cx1 || cy0 > cy1 {
+            return None;
+        }
+        // Interpolate over the requested rows so clipping doesn't stretch the ramp
+        let span = (gy1 - gy0).max(1);
+        let lerp = |a: u8, b: u8, t: i32| (a as i32 + (b as i32 - a as i32) * t / span) as u8;
+        let fbw = self.w as usize;
+        let width = (cx1 - cx0 + 1) as usize;
+        for yy in cy0..=cy1 {
+            let t = yy - gy0;
+            let c = Rgb565::new(
+                lerp(top.r(), bottom.r(), t),
+                lerp(top.g(), bottom.g(), t),
+                lerp(top.b(), bottom.b(), t),
+            );
+            let cbe = c.into_storage().to_be();
+            let base = (yy as usize) * fbw + (cx0 as usize);
+            self.fb[base..base + width].fill(cbe);
+        }
+        self.mark_dirty(cx0 as u16, cy0 as u16, cx1 as u16, cy1 as u16);
+        Some((cx0 as u16, cy0 as u16, cx1 as u16, cy1 as u16))
+    }
+
+    // Alpha-blend `color` over the FB pixel at (x, y), alpha 255 = opaque (no flush).
+    pub fn blend_pixel_fb(&mut self, x: i32, y: i32, color: Rgb565, alpha: u8) {
+        if x < 0 || y < 0 || x >= self.w as i32 || y >= self.h as i32 {
+            return;
+        }
+        let i = (y as usize) * (self.w as usize) + (x as usize);
+        let dst = u16::from_be(self.fb[i]);
+        self.fb[i] = blend_rgb565(dst, color.into_storage(), alpha).to_be();
+    }
+
+    // Thick line alpha-blended into the framebuffer (no flush). Unlike `draw_line_fb`, each
+    // covered pixel is blended exactly once (distance-to-segment test), so overlap from the
+    // stroke width doesn't stack up. Returns the touched bounding box.
+    pub fn blend_line_fb(
+        &mut self,
+        x0: i32,
+        y0: i32,
+        x1: i32,
+        y1: i32,
+        color: Rgb565,
+        stroke: u8,
+        alpha: u8,
+    ) -> Option<(u16, u16, u16, u16)> {
+        let half = stroke.max(1) as f32 / 2.0;
+        let pad = half as i32 + 1;
+        let bx0 = (x0.min(x1) - pad).max(0);
+        let by0 = (y0.min(y1) - pad).max(0);
+        let bx1 = (x0.max(x1) + pad).min(self.w as i32 - 1);
+        let by1 = (y0.max(y1) + pad).min(self.h as i32 - 1);
+        if bx0 > bx1 || by0 > by1 {
+            return None;
+        }
+
+        let (dx, dy) = ((x1 - x0) as f32, (y1 - y0) as f32);
+        let len2 = dx * dx + dy * dy;
+        let r2 = half * half;
+        let src = color.into_storage();
+        let fbw = self.w as usize;
+        let mut bb: Option<(u16, u16, u16, u16)> = None;
+
+        for y in by0..=by1 {
+            for x in bx0..=bx1 {
+                // squared distance from pixel to the segment
+                let (px, py) = ((x - x0) as f32, (y - y0) as f32);
+                let t = if len2 > 0.0 {
+                    ((px * dx + py * dy) / len2).clamp(0.0, 1.0)
+                } else {
+                    0.0
+                };
+                let (ex, ey) = (px - t * dx, py - t * dy);
+                if ex * ex + ey * ey > r2 {
+                    continue;
+                }
+                let i = (y as usize) * fbw + (x as usize);
+                let dst = u16::from_be(self.fb[i]);
+                self.fb[i] = blend_rgb565(dst, src, alpha).to_be();
+                let (xu, yu) = (x as u16, y as u16);
+                bb = Some(match bb {
+                    None => (xu, yu, xu, yu),
+                    Some((a, b, c, d)) => (a.min(xu), b.min(yu), c.max(xu), d.max(yu)),
+                });
+            }
+        }
+        if let Some((a, b, c, d)) = bb {
+            self.mark_dirty(a, b, c, d);
+        }
+        bb
+    }
+
+    // Copy an inclusive FB rectangle out (raw FB order), e.g. to restore a background later.
+    pub fn save_fb_rect(&self, x0: u16, y0: u16, x1: u16, y1: u16, out: &mut alloc::vec::Vec<u16>) {
+        out.clear();
+        if x0 > x1 || y0 > y1 || x1 >= self.w || y1 >= self.h {
+            return;
+        }
+        let fbw = self.w as usize;
+        for y in y0..=y1 {
+            let base = (y as usize) * fbw;
+            out.extend_from_slice(&self.fb[base + x0 as usize..=base + x1 as usize]);
+        }
+    }
+
+    // Write back a rectangle saved with `save_fb_rect` (no flush).
+    pub fn restore_fb_rect(
+        &mut self,
+        x0: u16,
+        y0: u16,
+        x1: u16,
+        y1: u16,
+        data: &[u16],
+    ) -> Result<(), Co5300Error<(), RST::Error>> {
+        if x0 > x1 || y0 > y1 || x1 >= self.w || y1 >= self.h {
+            return Err(Co5300Error::OutOfBounds);
+        }
+        let rw = (x1 - x0 + 1) as usize;
+        if data.len() != rw * (y1 - y0 + 1) as usize {
+            return Err(Co5300Error::OutOfBounds);
+        }
+        let fbw = self.w as usize;
+        for (row, src) in data.chunks_exact(rw).enumerate() {
+            let base = (y0 as usize + row) * fbw + x0 as usize;
+            self.fb[base..base + rw].copy_from_slice(src);
         }
+        self.mark_dirty(x0, y0, x1, y1);
+        Ok(())
     }
 
     // Convenience: fill a rectangle with a solid color, using staging buffer.
@@ -606,7 +1715,11 @@ where
         h: u16,
         color: Rgb565,
     ) -> Result<(), Co5300Error<(), RST::Error>> {
-        self.fill_rect_solid_opt(x, y, w, h, color, true)
+        self.fill_rect_solid_opt(x, y, w, h, color, true)?;
+        if w > 0 && h > 0 {
+            self.sync_front(x, y, x + w - 1, y + h - 1);
+        }
+        Ok(())
     }
 
     // Same as `fill_rect_solid` but optionally skips framebuffer mirroring for speed.
@@ -668,7 +1781,7 @@ where
         let instruction = Command::_8Bit(0x32, DataMode::Quad);
         let data_mode = DataMode::Quad;
         let address_mode = DataMode::Quad;
-        let bus: &mut SpiDmaBus<'fb, Blocking> = &mut self.spi.bus;
+        let bus: &mut SpiDmaBus<'fb, Blocking> = self.spi.bus.ready();
         let mut current_cmd = RAMWR_OPCODE;
 
         // Stream full chunks
@@ -702,6 +1815,21 @@ where
         Ok(())
     }
 
+    // Enable (Some(gamma)) or disable the gamma LUT used by `blit_rect_be_fast*`. Rebuilds
+    // the table only when the value changes; returns true if anything changed, in which
+    // case content already on the panel was drawn with the old curve and wants a repaint.
+    pub fn set_gamma(&mut self, gamma: Option<f32>) -> bool {
+        if self.gamma() == gamma {
+            return false;
+        }
+        self.gamma = gamma.map(GammaLut::new);
+        true
+    }
+
+    pub fn gamma(&self) -> Option<f32> {
+        self.gamma.as_ref().map(GammaLut::gamma)
+    }
+
     // Chunked rect blit from BE bytes; send slices directly using quad and mirror into FB.
     pub fn blit_rect_be_fast(
         &mut self,
@@ -711,7 +1839,11 @@ where
         h: u16,
         data: &[u8],
     ) -> Result<(), Co5300Error<(), RST::Error>> {
-        self.blit_rect_be_fast_opt(x0, y0, w, h, data, true)
+        self.blit_rect_be_fast_opt(x0, y0, w, h, data, true)?;
+        if w > 0 && h > 0 {
+            self.sync_front(x0, y0, x0 + w - 1, y0 + h - 1);
+        }
+        Ok(())
     }
 
     // Same as `blit_rect_be_fast` but optionally skips framebuffer mirroring for speed.
@@ -767,13 +1899,42 @@ where
         // Set window
         self.qspi_set_window_raw(x0, y0, x1, y1)?;
 
+        // Rotated: pixels are gathered in panel order (gamma applied on the way)
+        if self.orientation != Orientation::Deg0 {
+            let lut = self.gamma.clone();
+            let wu = w as usize;
+            let pixel = |c: usize, r: usize| {
+                let i = (r * wu + c) * 2;
+                let v = u16::from_be_bytes([data[i], data[i + 1]]);
+                lut.as_ref().map_or(v, |l| l.apply(v))
+            };
+            self.stream_rotated(wu, h as usize, pixel)?;
+            if update_fb {
+                let fbw = self.w as usize;
+                for ry in 0..(h as usize) {
+                    let base = (y0 as usize + ry) * fbw + (x0 as usize);
+                    for (c, dst) in self.fb[base..base + wu].iter_mut().enumerate() {
+                        *dst = pixel(c, ry).to_be();
+                    }
+                }
+            }
+            return Ok(());
+        }
+
+        // Gamma correction needs a per-pixel copy; without it the data is streamed as-is
+        if let Some(lut) = self.gamma.take() {
+            let res = self.blit_rect_be_gamma(&lut, x0, y0, w, h, data, update_fb);
+            self.gamma = Some(lut);
+            return res;
+        }
+
         // Stream in chunks
         let mut off = 0usize;
         let mut current_cmd = RAMWR_OPCODE;
         let instruction = Command::_8Bit(0x32, DataMode::Quad);
         let address_mode = DataMode::Quad;
         let data_mode = DataMode::Quad;
-        let bus: &mut SpiDmaBus<'fb, Blocking> = &mut self.spi.bus;
+        let bus: &mut SpiDmaBus<'fb, Blocking> = self.spi.bus.ready();
 
         // Stream full chunks
         while off < data.len() {
@@ -807,12 +1968,350 @@ where
         Ok(())
     }
 
+    // Nearest-neighbor scale a src_w×src_h BE image into dst_w×dst_h at (dst_x, dst_y), up or
+    // down, through the FB; then flush the destination. Parts past the panel edge are clipped.
+    pub fn blit_rect_be_scaled(
+        &mut self,
+        dst_x: u16,
+        dst_y: u16,
+        dst_w: u16,
+        dst_h: u16,
+        src: &[u8],
+        src_w: u16,
+        src_h: u16,
+    ) -> Result<(), Co5300Error<(), RST::Error>> {
+        if src.len() != (src_w as usize) * (src_h as usize) * 2 {
+            return Err(Co5300Error::OutOfBounds);
+        }
+        if dst_w == 0 || dst_h == 0 || src_w == 0 || src_h == 0 {
+            return Ok(());
+        }
+        if dst_x >= self.w || dst_y >= self.h {
+            return Err(Co5300Error::OutOfBounds);
+        }
+
+        // Clip to the panel; sampling still spans the full destination size
+        let vis_w = dst_w.min(self.w - dst_x) as usize;
+        let vis_h = dst_h.min(self.h - dst_y) as usize;
+        let (sw, sh) = (src_w as usize, src_h as usize);
+        let (dw, dh) = (dst_w as usize, dst_h as usize);
+        let fbw = self.w as usize;
+        for dy in 0..vis_h {
+            let sy = dy * sh / dh;
+            let base = (dst_y as usize + dy) * fbw + dst_x as usize;
+            for dx in 0..vis_w {
+                let i = (sy * sw + dx * sw / dw) * 2;
+                let c = u16::from_be_bytes([src[i], src[i + 1]]);
+                let c = self.gamma.as_ref().map_or(c, |l| l.apply(c));
+                self.fb[base + dx] = c.to_be();
+            }
+        }
+
+        self.flush_fb_rect_even(
+            dst_x,
+            dst_y,
+            dst_x + vis_w as u16 - 1,
+            dst_y + vis_h as u16 - 1,
+        )
+    }
+
+    // Blit tightly packed RGB888 into the FB, reducing to RGB565 with a 4x4 Bayer dither so
+    // smooth gradients don't band, then flush the rect. The pattern is anchored to panel
+    // coordinates, so neighbouring blits tile seamlessly.
+    pub fn blit_rect_888_dithered(
+        &mut self,
+        x0: u16,
+        y0: u16,
+        w: u16,
+        h: u16,
+        rgb888: &[u8],
+    ) -> Result<(), Co5300Error<(), RST::Error>> {
+        if w == 0 || h == 0 {
+            return Ok(());
+        }
+        if x0 as u32 + w as u32 > self.w as u32 || y0 as u32 + h as u32 > self.h as u32 {
+            return Err(Co5300Error::OutOfBounds);
+        }
+        if rgb888.len() != (w as usize) * (h as usize) * 3 {
+            return Err(Co5300Error::OutOfBounds);
+        }
+
+        // Bayer thresholds scaled to 0..255: channel = (v * max + t) / 255 rounds up with
+        // probability equal to the fraction lost by truncation.
+        const BAYER4: [[u16; 4]; 4] =
+            [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+        let fbw = self.w as usize;
+        for (ry, row) in rgb888.chunks_exact((w as usize) * 3).enumerate() {
+            let y = y0 as usize + ry;
+            let base = y * fbw + (x0 as usize);
+            for (rx, px) in row.chunks_exact(3).enumerate() {
+                let x = x0 as usize + rx;
+                let t = BAYER4[y & 3][x & 3] * 17;
+                let r = (px[0] as u16 * 31 + t) / 255;
+                let g = (px[1] as u16 * 63 + t) / 255;
+                let b = (px[2] as u16 * 31 + t) / 255;
+                let c = (r << 11) | (g << 5) | b;
+                let c = self.gamma.as_ref().map_or(c, |l| l.apply(c));
+                self.fb[base + rx] = c.to_be();
+            }
+        }
+
+        self.flush_fb_rect_even(x0, y0, x0 + w - 1, y0 + h - 1)
+    }
+
+    // Blit BE bytes into the FB skipping every pixel equal to `key`, so an image composites
+    // over what is already there, then flush only the bounding box of the written pixels.
+    pub fn blit_rect_be_transparent(
+        &mut self,
+        x0: u16,
+        y0: u16,
+        w: u16,
+        h: u16,
+        data: &[u8],
+        key: Rgb565,
+    ) -> Result<(), Co5300Error<(), RST::Error>> {
+        if w == 0 || h == 0 {
+            return Ok(());
+        }
+        if x0 as u32 + w as u32 > self.w as u32 || y0 as u32 + h as u32 > self.h as u32 {
+            return Err(Co5300Error::OutOfBounds);
+        }
+        if data.len() != (w as usize) * (h as usize) * 2 {
+            return Err(Co5300Error::OutOfBounds);
+        }
+
+        let key = key.into_storage();
+        let fbw = self.w as usize;
+        let mut bb: Option<(u16, u16, u16, u16)> = None;
+        for (ry, row) in data.chunks_exact((w as usize) * 2).enumerate() {
+            let y = y0 + ry as u16;
+            let base = (y as usize) * fbw + (x0 as usize);
+            for (rx, px) in row.chunks_exact(2).enumerate() {
+                let c = u16::from_be_bytes([px[0], px[1]]);
+                if c == key {
+                    continue;
+                }
+                let c = self.gamma.as_ref().map_or(c, |l| l.apply(c));
+                self.fb[base + rx] = c.to_be();
+                let x = x0 + rx as u16;
+                bb = Some(match bb {
+                    None => (x, y, x, y),
+                    Some((a, b, cx, d)) => (a.min(x), b.min(y), cx.max(x), d.max(y)),
+                });
+            }
+        }
+
+        match bb {
+            Some((bx0, by0, bx1, by1)) => self.flush_fb_rect_even(bx0, by0, bx1, by1),
+            None => Ok(()),
+        }
+    }
+
+    // Gamma path of `blit_rect_be_fast_opt` (window already set): pixels go through the LUT
+    // into the staging buffer before sending, and the FB mirrors the corrected values so
+    // later FB flushes match what is on the panel.
+    fn blit_rect_be_gamma(
+        &mut self,
+        lut: &GammaLut,
+        x0: u16,
+        y0: u16,
+        w: u16,
+        h: u16,
+        data: &[u8],
+        update_fb: bool,
+    ) -> Result<(), Co5300Error<(), RST::Error>> {
+        let instruction = Command::_8Bit(0x32, DataMode::Quad);
+        let mut current_cmd = RAMWR_OPCODE;
+        for src in data.chunks(self.stage.len()) {
+            let n = src.len();
+            for (dst, px) in self.stage[..n].chunks_exact_mut(2).zip(src.chunks_exact(2)) {
+                let c = lut.apply(u16::from_be_bytes([px[0], px[1]]));
+                dst.copy_from_slice(&c.to_be_bytes());
+            }
+            let address = Address::_24Bit((current_cmd as u32) << 8, DataMode::Quad);
+            let _ = self.spi.cs.set_low();
+            let res = self.spi.bus.ready().half_duplex_write(
+                DataMode::Quad,
+                instruction,
+                address,
+                0,
+                &self.stage[..n],
+            );
+            let _ = self.spi.cs.set_high();
+            res.map_err(|_| Co5300Error::Spi(()))?;
+            current_cmd = RAMWRC_OPCODE;
+        }
+
+        if update_fb {
+            let fbw = self.w as usize;
+            let mut px = data.chunks_exact(2);
+            for ry in 0..(h as usize) {
+                let base = (y0 as usize + ry) * fbw + (x0 as usize);
+                for dst in self.fb[base..base + (w as usize)].iter_mut() {
+                    if let Some(p) = px.next() {
+                        *dst = lut.apply(u16::from_be_bytes([p[0], p[1]])).to_be();
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Blit a w×h sub-rectangle out of a larger RGB565 BE image (e.g. a sprite atlas).
+    // `data` starts at the sub-rect's top-left pixel; `stride` is the source row width in pixels.
+    pub fn blit_rect_be_strided(
+        &mut self,
+        x0: u16,
+        y0: u16,
+        w: u16,
+        h: u16,
+        data: &[u8],
+        stride: u16,
+    ) -> Result<(), Co5300Error<(), RST::Error>> {
+        self.blit_rect_be_strided_opt(x0, y0, w, h, data, stride, true)?;
+        if w > 0 && h > 0 {
+            self.sync_front(x0, y0, x0 + w - 1, y0 + h - 1);
+        }
+        Ok(())
+    }
+
+    // Same as `blit_rect_be_strided` but skips framebuffer mirroring.
+    pub fn blit_rect_be_strided_no_fb(
+        &mut self,
+        x0: u16,
+        y0: u16,
+        w: u16,
+        h: u16,
+        data: &[u8],
+        stride: u16,
+    ) -> Result<(), Co5300Error<(), RST::Error>> {
+        self.blit_rect_be_strided_opt(x0, y0, w, h, data, stride, false)
+    }
+
+    // Core strided blit: rows are packed into the staging buffer and streamed like the FB flush.
+    fn blit_rect_be_strided_opt(
+        &mut self,
+        x0: u16,
+        y0: u16,
+        w: u16,
+        h: u16,
+        data: &[u8],
+        stride: u16,
+        update_fb: bool,
+    ) -> Result<(), Co5300Error<(), RST::Error>> {
+        // early out
+        if w == 0 || h == 0 {
+            return Ok(());
+        }
+        if stride < w {
+            return Err(Co5300Error::OutOfBounds);
+        }
+
+        // overflow-safe bounds
+        let (pw, ph) = (self.w as u32, self.h as u32);
+        let (x32, y32, w32, h32) = (x0 as u32, y0 as u32, w as u32, h as u32);
+        if x32 >= pw || y32 >= ph {
+            return Err(Co5300Error::OutOfBounds);
+        }
+        if x32.checked_add(w32).unwrap_or(u32::MAX) > pw
+            || y32.checked_add(h32).unwrap_or(u32::MAX) > ph
+        {
+            return Err(Co5300Error::OutOfBounds);
+        }
+
+        // last row only needs w pixels, not a full stride
+        let row_bytes = (w as usize) * 2;
+        let pitch = (stride as usize) * 2;
+        let needed = (h as usize - 1) * pitch + row_bytes;
+        if data.len() < needed {
+            return Err(Co5300Error::OutOfBounds);
+        }
+
+        let (x1, y1) = ((x32 + w32 - 1) as u16, (y32 + h32 - 1) as u16);
+        self.qspi_set_window_raw(x0, y0, x1, y1)?;
+
+        // Rotated: pixels are gathered in panel order
+        if self.orientation != Orientation::Deg0 {
+            let pixel = |c: usize, r: usize| {
+                let i = r * pitch + c * 2;
+                u16::from_be_bytes([data[i], data[i + 1]])
+            };
+            self.stream_rotated(w as usize, h as usize, pixel)?;
+            if update_fb {
+                let fbw = self.w as usize;
+                for ry in 0..(h as usize) {
+                    let base = (y0 as usize + ry) * fbw + (x0 as usize);
+                    for (c, dst) in self.fb[base..base + (w as usize)].iter_mut().enumerate() {
+                        *dst = pixel(c, ry).to_be();
+                    }
+                }
+            }
+            return Ok(());
+        }
+
+        let instruction = Command::_8Bit(0x32, DataMode::Quad);
+        let mut current_cmd = RAMWR_OPCODE;
+        let address_mode = DataMode::Quad;
+        let data_mode = DataMode::Quad;
+        let bus: &mut SpiDmaBus<'fb, Blocking> = self.spi.bus.ready();
+        let stage = &mut self.stage;
+        let mut filled = 0usize;
+
+        for ry in 0..(h as usize) {
+            let row = &data[ry * pitch..ry * pitch + row_bytes];
+            let mut off = 0usize;
+            while off < row.len() {
+                let space = stage.len().saturating_sub(filled);
+                let take = core::cmp::min(space, row.len() - off);
+                stage[filled..filled + take].copy_from_slice(&row[off..off + take]);
+                filled += take;
+                off += take;
+
+                if filled == stage.len() {
+                    let ad: u32 = (current_cmd as u32) << 8;
+                    let address = Address::_24Bit(ad, address_mode);
+                    let _ = self.spi.cs.set_low();
+                    let res =
+                        bus.half_duplex_write(data_mode, instruction, address, 0, &stage[..filled]);
+                    let _ = self.spi.cs.set_high();
+                    res.map_err(|_| Co5300Error::Spi(()))?;
+                    current_cmd = RAMWRC_OPCODE;
+                    filled = 0;
+                }
+            }
+        }
+
+        if filled > 0 {
+            let ad: u32 = (current_cmd as u32) << 8;
+            let address = Address::_24Bit(ad, address_mode);
+            let _ = self.spi.cs.set_low();
+            let res = bus.half_duplex_write(data_mode, instruction, address, 0, &stage[..filled]);
+            let _ = self.spi.cs.set_high();
+            res.map_err(|_| Co5300Error::Spi(()))?;
+        }
+
+        // Update FB (convert BE bytes to native u16)
+        if update_fb {
+            let fbw = self.w as usize;
+            for ry in 0..(h as usize) {
+                let base = (y0 as usize + ry) * fbw + (x0 as usize);
+                let src = &data[ry * pitch..ry * pitch + row_bytes];
+                let row = &mut self.fb[base..base + (w as usize)];
+                for (px, b) in row.iter_mut().zip(src.chunks_exact(2)) {
+                    *px = u16::from_be_bytes([b[0], b[1]]).to_be();
+                }
+            }
+        }
+        Ok(())
+    }
+
     // ---- Low-level helpers ----
     // Low-level command send (with data)
     #[inline(always)]
     fn cmd(&mut self, cmd: u8, data: &[u8]) -> Result<(), Co5300Error<(), RST::Error>> {
+        self.flush_wait();
         let _ = self.spi.cs.set_low();
-        let res = self.spi.bus.half_duplex_write(
+        let res = self.spi.bus.ready().half_duplex_write(
             DataMode::Single,
             Command::_8Bit(0x02, DataMode::Single),
             Address::_24Bit((cmd as u32) << 8, DataMode::Single),
@@ -826,8 +2325,9 @@ where
     // Send a bare QSPI mode-change instruction (0x38 enter, 0x3B enter dual, 0xFF exit).
     // Must be sent in the *current* bus width (we enter from single, so use 1-wire).
     fn qspi_send_mode_instr(&mut self, instr: u8, mode: DataMode) {
+        self.flush_wait();
         let command = Command::_8Bit(instr as u16, mode);
-        let bus: &mut SpiDmaBus<'fb, Blocking> = &mut self.spi.bus;
+        let bus: &mut SpiDmaBus<'fb, Blocking> = self.spi.bus.ready();
         let _ = self.spi.cs.set_low();
         let _ = bus.half_duplex_write(mode, command, Address::None, 0, &[]);
         let _ = self.spi.cs.set_high();
@@ -836,11 +2336,13 @@ where
     // Enter quad-data mode (enable QPI, per CO5300 table: 0x38).
     fn qspi_enter_quad(&mut self) {
         self.qspi_send_mode_instr(0x38, DataMode::Single);
+        self.quad = true;
     }
 
     // go back to 1-wire SPI (0xFF).
     fn qspi_exit_single(&mut self) {
         self.qspi_send_mode_instr(0xFF, DataMode::Quad);
+        self.quad = false;
     }
 }
 
@@ -855,6 +2357,23 @@ where
     }
 }
 
+// Blend two native RGB565 values per channel: out = src*a + dst*(255-a), a in 0..=255.
+#[inline]
+fn blend_rgb565(dst: u16, src: u16, alpha: u8) -> u16 {
+    match alpha {
+        0 => return dst,
+        255 => return src,
+        _ => {}
+    }
+    let a = alpha as u32;
+    let ia = 255 - a;
+    let mix = |s: u32, d: u32| (s * a + d * ia + 127) / 255;
+    let r = mix(((src >> 11) & 0x1F) as u32, ((dst >> 11) & 0x1F) as u32);
+    let g = mix(((src >> 5) & 0x3F) as u32, ((dst >> 5) & 0x3F) as u32);
+    let b = mix((src & 0x1F) as u32, (dst & 0x1F) as u32);
+    ((r << 11) | (g << 5) | b) as u16
+}
+
 impl<'fb, RST> embedded_graphics::draw_target::DrawTarget for Co5300Display<'fb, RST>
 where
     RST: embedded_hal::digital::OutputPin,
@@ -1027,9 +2546,56 @@ where
 
 // Raw SPI container: manual CS + bus so we can wrap half_duplex writes ourselves.
 pub struct RawSpiDev<'a> {
-    pub bus: SpiDmaBus<'a, Blocking>,
+    pub bus: BusSlot<'a>,
     pub cs: Output<'a>,
 }
 
+// The SPI bus, or the DMA transfer that holds it while a `flush_rect_even_dma` chunk is
+// on the wire (esp-hal moves the bus into the transfer).
+pub enum BusSlot<'a> {
+    Idle(SpiDmaBus<'a, Blocking>),
+    Busy(SpiDmaTransfer<'a, Blocking, DmaTxBuf>, DmaRxBuf),
+    // Only seen in the middle of a handoff that failed
+    Empty,
+}
+
+impl<'a> BusSlot<'a> {
+    // The blocking bus, waiting for the transfer in flight (if any) to finish first
+    fn ready(&mut self) -> &mut SpiDmaBus<'a, Blocking> {
+        if let BusSlot::Busy(..) = self {
+            if let BusSlot::Busy(transfer, rx) = core::mem::replace(self, BusSlot::Empty) {
+                let (spi, tx) = transfer.wait();
+                *self = BusSlot::Idle(spi.with_buffers(rx, tx));
+            }
+        }
+        match self {
+            BusSlot::Idle(bus) => bus,
+            _ => panic!("CO5300: SPI bus lost in a DMA handoff"),
+        }
+    }
+
+    fn is_busy(&self) -> bool {
+        match self {
+            BusSlot::Busy(transfer, _) => !transfer.is_done(),
+            _ => false,
+        }
+    }
+}
+
+// Progress of a DMA flush: the even-aligned rect and the next FB row to send
+#[derive(Copy, Clone, Debug)]
+struct DmaJob {
+    x0: u16,
+    x1: u16,
+    y1: u16,
+    next_row: u16,
+}
+
+// Returned by `flush_rect_even_dma`: the (even-aligned) rect being flushed
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct FlushToken {
+    pub rect: (u16, u16, u16, u16),
+}
+
 // Keep this type alias in sync with display.rs
 pub type DisplayType<'a> = Co5300Display<'a, Output<'a>>;
@@ -16,7 +16,128 @@ use esp_hal::{
     timer::systimer::{SystemTimer, Unit},
 };
 
+use crate::ui::PanelRgb565;
 use crate::wiring::DisplayPins;
+use alloc::vec::Vec;
+use embedded_graphics::pixelcolor::Rgb565;
+
+extern crate alloc;
+
+// Error from a `FastPanel` operation. The UI treats every fast-path failure the same way
+// (skip or fall back), so the backend error is not carried through.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct PanelError;
+
+// Framebuffer/direct-write fast paths the UI uses when the backend has them. Reached through
+// `PanelRgb565::fast_panel`, so UI code stays independent of the concrete driver; backends
+// without a framebuffer (GC9A01) don't implement it and the UI uses embedded-graphics.
+// Rects are inclusive (x0, y0, x1, y1) unless the method takes a width and height.
+pub trait FastPanel {
+    fn fill_rect_fb(&mut self, x0: i32, y0: i32, x1: i32, y1: i32, color: Rgb565);
+    fn fill_gradient_fb(
+        &mut self,
+        x0: i32,
+        y0: i32,
+        x1: i32,
+        y1: i32,
+        top: Rgb565,
+        bottom: Rgb565,
+    ) -> Option<(u16, u16, u16, u16)>;
+    fn draw_line_fb(
+        &mut self,
+        x0: i32,
+        y0: i32,
+        x1: i32,
+        y1: i32,
+        color: Rgb565,
+        stroke: u8,
+    ) -> Option<(u16, u16, u16, u16)>;
+    fn draw_line_fb_aa(
+        &mut self,
+        x0: i32,
+        y0: i32,
+        x1: i32,
+        y1: i32,
+        color: Rgb565,
+        stroke: u8,
+    ) -> Option<(u16, u16, u16, u16)>;
+    fn blend_line_fb(
+        &mut self,
+        x0: i32,
+        y0: i32,
+        x1: i32,
+        y1: i32,
+        color: Rgb565,
+        stroke: u8,
+        alpha: u8,
+    ) -> Option<(u16, u16, u16, u16)>;
+    fn draw_circle_fb(
+        &mut self,
+        cx: i32,
+        cy: i32,
+        r: i32,
+        color: Rgb565,
+        filled: bool,
+    ) -> Option<(u16, u16, u16, u16)>;
+    fn write_rect_fb(
+        &mut self,
+        x: u16,
+        y: u16,
+        w: u16,
+        h: u16,
+        data: &[u8],
+    ) -> Result<(), PanelError>;
+    fn save_fb_rect(&self, x0: u16, y0: u16, x1: u16, y1: u16, out: &mut Vec<u16>);
+    fn restore_fb_rect(
+        &mut self,
+        x0: u16,
+        y0: u16,
+        x1: u16,
+        y1: u16,
+        data: &[u16],
+    ) -> Result<(), PanelError>;
+    fn flush_rect_even(&mut self, x0: u16, y0: u16, x1: u16, y1: u16) -> Result<(), PanelError>;
+    fn flush_rect_even_dma(&mut self, x0: u16, y0: u16, x1: u16, y1: u16)
+        -> Result<(), PanelError>;
+    fn flush_wait(&mut self);
+    fn double_buffered(&self) -> bool;
+    fn swap_buffers(&mut self) -> Result<(), PanelError>;
+    fn flush_dirty(&mut self) -> Result<(), PanelError>;
+    fn take_touched_rect(&mut self) -> Option<(u16, u16, u16, u16)>;
+    fn fill_rect_solid_no_fb(
+        &mut self,
+        x: u16,
+        y: u16,
+        w: u16,
+        h: u16,
+        color: Rgb565,
+    ) -> Result<(), PanelError>;
+    fn blit_rect_be_fast(
+        &mut self,
+        x0: u16,
+        y0: u16,
+        w: u16,
+        h: u16,
+        data: &[u8],
+    ) -> Result<(), PanelError>;
+    fn blit_rect_be_fast_no_fb(
+        &mut self,
+        x0: u16,
+        y0: u16,
+        w: u16,
+        h: u16,
+        data: &[u8],
+    ) -> Result<(), PanelError>;
+    fn blit_rect_be_strided_no_fb(
+        &mut self,
+        x0: u16,
+        y0: u16,
+        w: u16,
+        h: u16,
+        data: &[u8],
+        stride: u16,
+    ) -> Result<(), PanelError>;
+}
 
 // A delay provider that uses the ESP32-S3's high-resolution SystemTimer.
 pub struct TimerDelay;
@@ -127,6 +248,9 @@ mod gc9a01_backend {
             .init(&mut delay)
             .unwrap()
     }
+
+    // No framebuffer: the UI draws through embedded-graphics only
+    impl PanelRgb565 for DisplayType<'_> {}
 }
 
 // ==================================================================
@@ -135,7 +259,7 @@ mod gc9a01_backend {
 #[cfg(feature = "esp32s3-disp143Oled")]
 mod co5300_backend {
     use super::*;
-    use crate::co5300::{self, Co5300Display, RawSpiDev};
+    use crate::co5300::{self, BusSlot, Co5300Display, RawSpiDev};
     use embedded_hal::delay::DelayNs;
     use esp_hal::{
         dma::{DmaRxBuf, DmaTxBuf},
@@ -157,6 +281,7 @@ mod co5300_backend {
             rst,
             mut en,
             dma_ch0,
+            te: _, // not used by the driver, main keeps it for `wait_for_te`
         } = display_pins;
 
         let mut delay = TimerDelay;
@@ -189,10 +314,185 @@ mod co5300_backend {
         let tx = DmaTxBuf::new(tx_desc, tx_buf).unwrap();
 
         let spi_bus = spi.with_buffers(rx, tx);
-        let raw = RawSpiDev { bus: spi_bus, cs };
+        let raw = RawSpiDev {
+            bus: BusSlot::Idle(spi_bus),
+            cs,
+        };
 
         co5300::new_with_defaults(raw, Some(rst), &mut delay, fb).expect("CO5300 init failed")
     }
+
+    impl PanelRgb565 for DisplayType<'_> {
+        fn fast_panel(&mut self) -> Option<&mut dyn FastPanel> {
+            Some(self)
+        }
+    }
+
+    // Inherent methods take precedence, so each of these forwards to the driver
+    impl FastPanel for DisplayType<'_> {
+        fn fill_rect_fb(&mut self, x0: i32, y0: i32, x1: i32, y1: i32, color: Rgb565) {
+            self.fill_rect_fb(x0, y0, x1, y1, color)
+        }
+        fn fill_gradient_fb(
+            &mut self,
+            x0: i32,
+            y0: i32,
+            x1: i32,
+            y1: i32,
+            top: Rgb565,
+            bottom: Rgb565,
+        ) -> Option<(u16, u16, u16, u16)> {
+            self.fill_gradient_fb(x0, y0, x1, y1, top, bottom)
+        }
+        fn draw_line_fb(
+            &mut self,
+            x0: i32,
+            y0: i32,
+            x1: i32,
+            y1: i32,
+            color: Rgb565,
+            stroke: u8,
+        ) -> Option<(u16, u16, u16, u16)> {
+            self.draw_line_fb(x0, y0, x1, y1, color, stroke)
+        }
+        fn draw_line_fb_aa(
+            &mut self,
+            x0: i32,
+            y0: i32,
+            x1: i32,
+            y1: i32,
+            color: Rgb565,
+            stroke: u8,
+        ) -> Option<(u16, u16, u16, u16)> {
+            self.draw_line_fb_aa(x0, y0, x1, y1, color, stroke)
+        }
+        fn blend_line_fb(
+            &mut self,
+            x0: i32,
+            y0: i32,
+            x1: i32,
+            y1: i32,
+            color: Rgb565,
+            stroke: u8,
+            alpha: u8,
+        ) -> Option<(u16, u16, u16, u16)> {
+            self.blend_line_fb(x0, y0, x1, y1, color, stroke, alpha)
+        }
+        fn draw_circle_fb(
+            &mut self,
+            cx: i32,
+            cy: i32,
+            r: i32,
+            color: Rgb565,
+            filled: bool,
+        ) -> Option<(u16, u16, u16, u16)> {
+            self.draw_circle_fb(cx, cy, r, color, filled)
+        }
+        fn write_rect_fb(
+            &mut self,
+            x: u16,
+            y: u16,
+            w: u16,
+            h: u16,
+            data: &[u8],
+        ) -> Result<(), PanelError> {
+            self.write_rect_fb(x, y, w, h, data).map_err(|_| PanelError)
+        }
+        fn save_fb_rect(&self, x0: u16, y0: u16, x1: u16, y1: u16, out: &mut Vec<u16>) {
+            self.save_fb_rect(x0, y0, x1, y1, out)
+        }
+        fn restore_fb_rect(
+            &mut self,
+            x0: u16,
+            y0: u16,
+            x1: u16,
+            y1: u16,
+            data: &[u16],
+        ) -> Result<(), PanelError> {
+            self.restore_fb_rect(x0, y0, x1, y1, data)
+                .map_err(|_| PanelError)
+        }
+        fn flush_rect_even(
+            &mut self,
+            x0: u16,
+            y0: u16,
+            x1: u16,
+            y1: u16,
+        ) -> Result<(), PanelError> {
+            self.flush_rect_even(x0, y0, x1, y1).map_err(|_| PanelError)
+        }
+        fn flush_rect_even_dma(
+            &mut self,
+            x0: u16,
+            y0: u16,
+            x1: u16,
+            y1: u16,
+        ) -> Result<(), PanelError> {
+            self.flush_rect_even_dma(x0, y0, x1, y1)
+                .map(|_| ())
+                .map_err(|_| PanelError)
+        }
+        fn flush_wait(&mut self) {
+            self.flush_wait()
+        }
+        fn double_buffered(&self) -> bool {
+            self.double_buffered()
+        }
+        fn swap_buffers(&mut self) -> Result<(), PanelError> {
+            self.swap_buffers().map_err(|_| PanelError)
+        }
+        fn flush_dirty(&mut self) -> Result<(), PanelError> {
+            self.flush_dirty().map_err(|_| PanelError)
+        }
+        fn take_touched_rect(&mut self) -> Option<(u16, u16, u16, u16)> {
+            self.take_touched_rect()
+        }
+        fn fill_rect_solid_no_fb(
+            &mut self,
+            x: u16,
+            y: u16,
+            w: u16,
+            h: u16,
+            color: Rgb565,
+        ) -> Result<(), PanelError> {
+            self.fill_rect_solid_no_fb(x, y, w, h, color)
+                .map_err(|_| PanelError)
+        }
+        fn blit_rect_be_fast(
+            &mut self,
+            x0: u16,
+            y0: u16,
+            w: u16,
+            h: u16,
+            data: &[u8],
+        ) -> Result<(), PanelError> {
+            self.blit_rect_be_fast(x0, y0, w, h, data)
+                .map_err(|_| PanelError)
+        }
+        fn blit_rect_be_fast_no_fb(
+            &mut self,
+            x0: u16,
+            y0: u16,
+            w: u16,
+            h: u16,
+            data: &[u8],
+        ) -> Result<(), PanelError> {
+            self.blit_rect_be_fast_no_fb(x0, y0, w, h, data)
+                .map_err(|_| PanelError)
+        }
+        fn blit_rect_be_strided_no_fb(
+            &mut self,
+            x0: u16,
+            y0: u16,
+            w: u16,
+            h: u16,
+            data: &[u8],
+            stride: u16,
+        ) -> Result<(), PanelError> {
+            self.blit_rect_be_strided_no_fb(x0, y0, w, h, data, stride)
+                .map_err(|_| PanelError)
+        }
+    }
 }
 
 #[cfg(feature = "devkit-esp32s3-disp128")]
@@ -3,7 +3,12 @@
 //! This module provides:
 //! - `ButtonState` and `RotaryState` structs for tracking input state
 //! - Debounced button event handling via `handle_button_generic`
-//! - Rotary encoder quadrature decoding via `handle_encoder_generic`
+//! - Press vs. long-press (hold) on one button via `handle_button_with_longpress`
+//! - Hold-to-repeat via `handle_button_with_repeat`
+//! - Rotary encoder quadrature decoding via `handle_encoder_generic`, plus spin-speed
+//!   tracking for accelerated adjustments via `handle_encoder_with_accel`
+//! - The encoder's push switch via `handle_encoder_switch_generic`
+//! - Two-button chords (hold the first, press the second) via `ChordDetector`
 //!
 //! All input state is protected with `critical_section` for safe concurrent access in interrupt and main contexts.
 //! Designed for use with ESP-HAL GPIO and embedded Rust applications.
@@ -17,13 +22,67 @@ use critical_section::Mutex;
 // ESP-HAL imports
 use esp_hal::gpio::Input;
 
+// Debounce used by `ButtonState::new`
+pub const DEFAULT_DEBOUNCE_MS: u64 = 240;
+
 // Button state struct
 pub struct ButtonState<'a> {
     // pub pressed: Mutex<Cell<bool>>,
     pub input: Mutex<RefCell<Option<Input<'a>>>>,
-    pub last_level: Mutex<Cell<bool>>,
+    pub last_level: Mutex<Cell<bool>>, // true while released
     pub last_interrupt: Mutex<Cell<u64>>,
+    // Long-press and repeat modes: when the current press started (None = up / already
+    // reported), whether its long press has fired, whether it is repeating yet (the initial
+    // delay has passed), and the last repeat event
+    pub press_start: Mutex<Cell<Option<u64>>>,
+    pub long_fired: Mutex<Cell<bool>>,
+    pub repeating: Mutex<Cell<bool>>,
+    pub last_repeat: Mutex<Cell<u64>>,
     pub name: &'static str,
+    // Minimum time between two presses, longer for bouncier switches
+    pub debounce_ms: u64,
+    // Pressed reads low (switch to ground with a pull-up); false for switches to VCC
+    pub active_low: bool,
+}
+
+impl<'a> ButtonState<'a> {
+    // Active-low button with the default debounce, pin installed later
+    pub const fn new(name: &'static str) -> Self {
+        Self {
+            input: Mutex::new(RefCell::new(None)),
+            last_level: Mutex::new(Cell::new(true)),
+            last_interrupt: Mutex::new(Cell::new(0)),
+            press_start: Mutex::new(Cell::new(None)),
+            long_fired: Mutex::new(Cell::new(false)),
+            repeating: Mutex::new(Cell::new(false)),
+            last_repeat: Mutex::new(Cell::new(0)),
+            name,
+            debounce_ms: DEFAULT_DEBOUNCE_MS,
+            active_low: true,
+        }
+    }
+
+    pub const fn with_debounce_ms(mut self, debounce_ms: u64) -> Self {
+        self.debounce_ms = debounce_ms;
+        self
+    }
+
+    pub const fn active_high(mut self) -> Self {
+        self.active_low = false;
+        self
+    }
+
+    // Whether `input` reads as pressed for this button
+    fn is_pressed(&self, input: &Input) -> bool {
+        input.is_low() == self.active_low
+    }
+}
+
+// Down and waiting for release or the long-press time (always false outside long-press mode)
+pub fn button_press_pending(btn: &ButtonState) -> bool {
+    critical_section::with(|cs| {
+        btn.press_start.borrow(cs).get().is_some() && !btn.long_fired.borrow(cs).get()
+    })
 }
 
 // Rotary encoder state struct
@@ -34,21 +93,90 @@ pub struct RotaryState<'a> {
     pub position: Mutex<Cell<i32>>,
     pub last_qstate: Mutex<Cell<u8>>,
     pub last_step: Mutex<Cell<i8>>,
+    // Push switch (active-low), None where it isn't wired; debounced like a button
+    pub sw: Mutex<RefCell<Option<Input<'a>>>>,
+    pub sw_last_level: Mutex<Cell<bool>>,
+    pub sw_last_interrupt: Mutex<Cell<u64>>,
+    pub sw_debounce_ms: u64,
+    // Acceleration (`handle_encoder_with_accel` only): time of the last step and the
+    // smoothed time between steps
+    pub last_step_ms: Mutex<Cell<u64>>,
+    pub step_interval_ms: Mutex<Cell<u64>>,
 }
 
+// Encoder acceleration: (smoothed step interval below this many ms, delta multiplier),
+// fastest first. With 4 steps per detent, 5 ms is a hard flick (about 50 detents/s) and
+// 20 ms a brisk turn (about 12 detents/s); slower turns keep single steps.
+const ENCODER_ACCEL: [(u64, i32); 3] = [(5, 10), (10, 5), (20, 2)];
+// Step gaps longer than this count as a fresh start, so one slow click after a fast spin
+// isn't multiplied
+const ENCODER_ACCEL_IDLE_MS: u64 = 100;
+
 // Generic IMU interrupt state (active-low)
 pub struct ImuIntState<'a> {
     pub input: Mutex<RefCell<Option<Input<'a>>>>,
 }
 
-// Handle button press events
+// Whether a button is held down now, false if the pin isn't installed
+pub fn button_is_down(btn: &ButtonState) -> bool {
+    critical_section::with(|cs| {
+        btn.input
+            .borrow_ref(cs)
+            .as_ref()
+            .map(|p| btn.is_pressed(p))
+            .unwrap_or(false)
+    })
+}
+
+// Two-button chord: hold the first button, then press the second.
+//
+// Suppression: only a press of the second member made while the first is down is held
+// back, and it is the chord, so it is dropped; every other press is passed straight
+// through. The first member's own press must not be delivered while it is still held,
+// which long-press mode (`handle_button_with_longpress`, press reported on release)
+// guarantees: by the time it arrives it is known whether it was part of a chord. After a
+// chord, member presses (including that release) are swallowed until both buttons are up.
+pub struct ChordDetector<'a> {
+    buttons: [&'a ButtonState<'a>; 2],
+    latched: bool,
+}
+
+impl<'a> ChordDetector<'a> {
+    pub const fn new(buttons: [&'a ButtonState<'a>; 2]) -> Self {
+        Self {
+            buttons,
+            latched: false,
+        }
+    }
+
+    // Feed this loop's debounced press events for the two members. Calls `on_chord` when
+    // the chord is recognized and returns the presses to handle as normal button actions.
+    pub fn update(&mut self, pressed: [bool; 2], on_chord: impl FnOnce()) -> [bool; 2] {
+        let held = [
+            button_is_down(self.buttons[0]),
+            button_is_down(self.buttons[1]),
+        ];
+        if self.latched {
+            if held[0] || held[1] || pressed[0] || pressed[1] {
+                return [false; 2];
+            }
+            self.latched = false;
+        }
+
+        // First member down (or its press pending release, or released this very loop)
+        let first_down = held[0] || pressed[0] || button_press_pending(self.buttons[0]);
+        if pressed[1] && first_down {
+            self.latched = true;
+            on_chord();
+            return [false; 2];
+        }
+        pressed
+    }
+}
+
+// Handle button press events, debounced and with the active level from `btn`
 #[esp_hal::ram]
-pub fn handle_button_generic(
-    btn: &ButtonState,
-    now_ms: u64,
-    debounce_ms: u64,
-    on_press: impl Fn(),
-) {
+pub fn handle_button_generic(btn: &ButtonState, now_ms: u64, on_press: impl Fn()) {
     // Access button state within critical section
     critical_section::with(|cs| {
         let mut btn_binding = btn.input.borrow_ref_mut(cs);
@@ -63,16 +191,16 @@ pub fn handle_button_generic(
         }
         input.clear_interrupt();
 
-        // Debounce logic: check for falling edge and time since last event
-        let level_is_low = input.is_low();
-        let last_high = btn.last_level.borrow(cs).get();
-        btn.last_level.borrow(cs).set(!level_is_low);
+        // Debounce logic: check for a press edge and time since last event
+        let down = btn.is_pressed(input);
+        let last_up = btn.last_level.borrow(cs).get();
+        btn.last_level.borrow(cs).set(!down);
 
-        if last_high && level_is_low {
-            // Falling edge detected
+        if last_up && down {
+            // Press edge detected
             let last_debounce = btn.last_interrupt.borrow(cs).get();
             // Check debounce time
-            if now_ms.saturating_sub(last_debounce) > debounce_ms {
+            if now_ms.saturating_sub(last_debounce) > btn.debounce_ms {
                 btn.last_interrupt.borrow(cs).set(now_ms);
                 on_press();
             }
@@ -80,6 +208,113 @@ pub fn handle_button_generic(
     });
 }
 
+// Handle button press and long-press events. The short press is reported on release, so
+// that a hold reaching `long_ms` can report `on_longpress` (once) instead of it. Call this
+// from the GPIO interrupt for the edges and from the main loop too: a held button raises no
+// interrupts, so only the polling call notices the hold crossing `long_ms`.
+#[esp_hal::ram]
+pub fn handle_button_with_longpress(
+    btn: &ButtonState,
+    now_ms: u64,
+    long_ms: u64,
+    on_press: impl Fn(),
+    on_longpress: impl Fn(),
+) {
+    critical_section::with(|cs| {
+        let mut btn_binding = btn.input.borrow_ref_mut(cs);
+        let Some(input) = btn_binding.as_mut() else {
+            return; // pin not yet installed
+        };
+        if input.is_interrupt_set() {
+            input.clear_interrupt();
+        }
+
+        let down = btn.is_pressed(input);
+        let last_up = btn.last_level.borrow(cs).get();
+        btn.last_level.borrow(cs).set(!down);
+        let press_start = btn.press_start.borrow(cs);
+        let long_fired = btn.long_fired.borrow(cs);
+
+        if last_up && down {
+            // Press edge: start timing the press (debounced like handle_button_generic)
+            let last_debounce = btn.last_interrupt.borrow(cs).get();
+            if now_ms.saturating_sub(last_debounce) > btn.debounce_ms {
+                btn.last_interrupt.borrow(cs).set(now_ms);
+                press_start.set(Some(now_ms));
+                long_fired.set(false);
+            }
+        } else if !last_up && !down {
+            // Released: a short press unless the long press already fired
+            if press_start.take().is_some() && !long_fired.get() {
+                on_press();
+            }
+        } else if let Some(t0) = press_start.get() {
+            // Still held
+            if down && !long_fired.get() && now_ms.saturating_sub(t0) >= long_ms {
+                long_fired.set(true);
+                on_longpress();
+            }
+        }
+    });
+}
+
+// Handle button events with hold-to-repeat: `on_event` fires on the press, then again every
+// `repeat_ms` once the button has been held for `initial_delay_ms`. Releasing stops it. Like
+// `handle_button_with_longpress`, call it from the interrupt and poll it from the main loop;
+// a slow poll gives one event per call rather than a burst of the missed ones.
+#[esp_hal::ram]
+pub fn handle_button_with_repeat(
+    btn: &ButtonState,
+    now_ms: u64,
+    initial_delay_ms: u64,
+    repeat_ms: u64,
+    on_event: impl Fn(),
+) {
+    critical_section::with(|cs| {
+        let mut btn_binding = btn.input.borrow_ref_mut(cs);
+        let Some(input) = btn_binding.as_mut() else {
+            return; // pin not yet installed
+        };
+        if input.is_interrupt_set() {
+            input.clear_interrupt();
+        }
+
+        let down = btn.is_pressed(input);
+        let last_up = btn.last_level.borrow(cs).get();
+        btn.last_level.borrow(cs).set(!down);
+        let press_start = btn.press_start.borrow(cs);
+        let repeating = btn.repeating.borrow(cs);
+        let last_repeat = btn.last_repeat.borrow(cs);
+
+        if last_up && down {
+            // Press edge (debounced): one event right away
+            let last_debounce = btn.last_interrupt.borrow(cs).get();
+            if now_ms.saturating_sub(last_debounce) > btn.debounce_ms {
+                btn.last_interrupt.borrow(cs).set(now_ms);
+                press_start.set(Some(now_ms));
+                repeating.set(false);
+                last_repeat.set(now_ms);
+                on_event();
+            }
+        } else if !down {
+            press_start.set(None);
+            repeating.set(false);
+        } else if let Some(t0) = press_start.get() {
+            // Held: first repeat after the initial delay, then every repeat_ms
+            let due = if repeating.get() {
+                last_repeat.get().saturating_add(repeat_ms)
+            } else {
+                t0.saturating_add(initial_delay_ms)
+            };
+            if now_ms >= due {
+                repeating.set(true);
+                last_repeat.set(now_ms);
+                on_event();
+            }
+        }
+    });
+}
+
 // Handle rotary encoder events
 #[esp_hal::ram]
 pub fn handle_encoder_generic(encoder: &RotaryState) {
@@ -139,6 +374,75 @@ pub fn handle_encoder_generic(encoder: &RotaryState) {
     });
 }
 
+// Handle encoder push switch events, debounced by `encoder.sw_debounce_ms` like a button
+#[esp_hal::ram]
+pub fn handle_encoder_switch_generic(encoder: &RotaryState, now_ms: u64, on_press: impl Fn()) {
+    critical_section::with(|cs| {
+        let mut sw_binding = encoder.sw.borrow_ref_mut(cs);
+        let Some(sw) = sw_binding.as_mut() else {
+            return; // switch not wired / not yet installed
+        };
+        if !sw.is_interrupt_set() {
+            return;
+        }
+        sw.clear_interrupt();
+
+        let level_is_low = sw.is_low();
+        let last_high = encoder.sw_last_level.borrow(cs).get();
+        encoder.sw_last_level.borrow(cs).set(!level_is_low);
+
+        if last_high && level_is_low {
+            let last_debounce = encoder.sw_last_interrupt.borrow(cs).get();
+            if now_ms.saturating_sub(last_debounce) > encoder.sw_debounce_ms {
+                encoder.sw_last_interrupt.borrow(cs).set(now_ms);
+                on_press();
+            }
+        }
+    });
+}
+
+// `handle_encoder_generic` plus spin-speed tracking for `encoder_accel_delta`
+#[esp_hal::ram]
+pub fn handle_encoder_with_accel(encoder: &RotaryState, now_ms: u64) {
+    let before = critical_section::with(|cs| encoder.position.borrow(cs).get());
+    handle_encoder_generic(encoder);
+    critical_section::with(|cs| {
+        if encoder.position.borrow(cs).get() == before {
+            return;
+        }
+        let gap = now_ms
+            .saturating_sub(encoder.last_step_ms.borrow(cs).get())
+            .min(ENCODER_ACCEL_IDLE_MS);
+        let interval = encoder.step_interval_ms.borrow(cs);
+        // Idle gaps restart the average; otherwise 3/4 old + 1/4 new
+        interval.set(if gap >= ENCODER_ACCEL_IDLE_MS {
+            gap
+        } else {
+            (interval.get() * 3 + gap) / 4
+        });
+        encoder.last_step_ms.borrow(cs).set(now_ms);
+    });
+}
+
+// Scale a detent delta by how fast the encoder is spinning (see `ENCODER_ACCEL`), for
+// adjustments over wide ranges. Needs `handle_encoder_with_accel`; the raw delta is
+// returned unchanged otherwise or after a pause.
+pub fn encoder_accel_delta(encoder: &RotaryState, delta: i32, now_ms: u64) -> i32 {
+    let (last, interval) = critical_section::with(|cs| {
+        (
+            encoder.last_step_ms.borrow(cs).get(),
+            encoder.step_interval_ms.borrow(cs).get(),
+        )
+    });
+    if now_ms.saturating_sub(last) >= ENCODER_ACCEL_IDLE_MS {
+        return delta;
+    }
+    ENCODER_ACCEL
+        .iter()
+        .find(|&&(below_ms, _)| interval < below_ms)
+        .map_or(delta, |&(_, mult)| delta.saturating_mul(mult))
+}
+
 // Handle IMU interrupt events
 #[esp_hal::ram]
 pub fn handle_imu_int_generic(state: &ImuIntState, flag: &AtomicBool) {
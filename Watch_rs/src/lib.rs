@@ -1,7 +1,9 @@
-#![no_std]
+#![cfg_attr(not(test), no_std)]
 
 pub mod display;
 pub mod input;
+pub mod sched;
+pub mod settings;
 pub mod ui;
 pub mod wiring;
 
@@ -11,3 +13,5 @@ pub mod co5300;
 pub mod qmi8658_imu;
 #[cfg(feature = "esp32s3-disp143Oled")]
 pub mod rtc_pcf85063;
+#[cfg(feature = "esp32s3-disp143Oled")]
+pub mod serial;
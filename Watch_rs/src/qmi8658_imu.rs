@@ -3,32 +3,125 @@
 // Touch AMOLED 1.43" board (QMI8658 on the touch I2C bus)
 // Datasheet: https://files.waveshare.com/wiki/common/QMI8658C_datasheet_rev_0.9.pdf
 
+use embedded_hal::delay::DelayNs;
 use embedded_hal::i2c;
 
 pub const DEFAULT_I2C_ADDR: u8 = 0x6B; // AD0 pulled high on the Waveshare board
 
 const REG_WHO_AM_I: u8 = 0x00;
-const REG_CTRL1: u8 = 0x02; // accel config
-const REG_CTRL2: u8 = 0x03; // gyro config
+const REG_CTRL1: u8 = 0x02; // serial interface / INT pin enables
+const REG_CTRL2: u8 = 0x03; // accel config: full scale (bits 6:4), ODR (bits 3:0)
+const REG_CTRL3: u8 = 0x04; // gyro config: full scale (bits 6:4), ODR (bits 3:0)
 const REG_CTRL7: u8 = 0x08; // power / enable
 const REG_CTRL8: u8 = 0x09; // reset/power settings
                             // const REG_STATUS_INT: u8 = 0x2D;
                             // const REG_STATUS0: u8 = 0x2E;
+const REG_TEMP_L: u8 = 0x33; // TEMP_L, TEMP_H follows
 const REG_ACC_START: u8 = 0x35; // AX_L .. GZ_H
 const INT_ENABLE_BITS: u8 = 0x18; // INT1_ENABLE (0x08) | INT2_ENABLE (0x10) per qmi8658c.h
+const CTRL1_IF_CFG: u8 = 0x60; // address auto-increment and data byte order, as tuned
 const CTRL8_DATAVALID_INT1: u8 = 0x40; // route data-ready to INT1
 
+// CTRL9 host command interface
+const REG_CTRL9: u8 = 0x0A; // host command
+const REG_STATUSINT: u8 = 0x2D;
+const STATUSINT_CMD_DONE: u8 = 0x80;
+const CTRL9_CMD_ACK: u8 = 0x00;
+const CTRL9_POLL_LIMIT: u32 = 1000;
+
+// Wake-on-motion (WoM) registers and values
+const REG_CAL1_L: u8 = 0x0B; // WoM threshold in mg
+const REG_CAL1_H: u8 = 0x0C; // WoM INT select / initial level (bits 7:6), blanking (bits 5:0)
+const CTRL9_CMD_WRITE_WOM_SETTING: u8 = 0x08;
+const WOM_ACCEL_CFG: u8 = 0x2D; // +/-8g, 21 Hz low-power ODR
+const WOM_INT1_IDLE_HIGH: u8 = 0xC0; // INT1, idles high and toggles low on motion
+const WOM_BLANKING_SAMPLES: u8 = 0x04; // ignore the first samples after enabling
+
+// FIFO registers. Layout relied on (datasheet FIFO section):
+// - FIFO_WTM_TH: watermark, in samples (one sample = accel + gyro set here)
+// - FIFO_CTRL: RD_MODE (bit 7), size (bits 3:2: 16/32/64/128), mode (bits 1:0)
+// - FIFO_SMPL_CNT + FIFO_STATUS bits 1:0: fill level in 2-byte words (10 bits)
+// - FIFO_STATUS: full (bit 7), watermark hit (bit 6), overflow (bit 5), not empty (bit 4)
+// - FIFO_DATA: read port; with accel and gyro on, each sample is 12 bytes
+//   AX AY AZ GX GY GZ (little endian), the same order as the AX_L.. output registers
+// Reading: CTRL9 REQ_FIFO sets RD_MODE, burst-read FIFO_DATA (the address does not
+// advance on this register), then clear RD_MODE to hand the FIFO back to the sensor.
+const REG_FIFO_WTM_TH: u8 = 0x13;
+const REG_FIFO_CTRL: u8 = 0x14;
+const REG_FIFO_SMPL_CNT: u8 = 0x15;
+const REG_FIFO_STATUS: u8 = 0x16;
+const REG_FIFO_DATA: u8 = 0x17;
+const FIFO_CTRL_RD_MODE: u8 = 0x80;
+const FIFO_CTRL_SIZE_64: u8 = 0x08; // 64 samples x 12 bytes fills the 1536-byte FIFO
+const FIFO_CAPACITY: u8 = 64;
+const FIFO_SAMPLE_BYTES: usize = 12;
+const FIFO_READ_CHUNK: usize = 8; // samples per I2C burst
+const CTRL1_FIFO_INT1: u8 = 0x04; // FIFO watermark/full interrupt on INT1 instead of INT2
+const CTRL9_CMD_RST_FIFO: u8 = 0x04;
+const CTRL9_CMD_REQ_FIFO: u8 = 0x05;
+
+// Self-test: the aST / gST trigger bits in CTRL2 / CTRL3, STATUSINT Avail (follows INT2)
+// to pace it, and the response deltas in dVX_L..dVZ_H. Pass limits from the datasheet:
+// every accel axis above 200 mg (U5.11, 2048 per g), every gyro axis above 300 dps
+// (U12.4, 16 per dps).
+const REG_DVX_L: u8 = 0x51;
+const CTRL_SELF_TEST: u8 = 0x80;
+const STATUSINT_AVAIL: u8 = 0x01;
+const SELF_TEST_POLL_LIMIT: u32 = 5000;
+const SELF_TEST_ACCEL_MIN: u16 = 410; // 200 mg
+const SELF_TEST_GYRO_MIN: u16 = 4800; // 300 dps
+
 // Expected chip ID for QMI8658. Some revisions report 0x05 or 0x0F; keep it loose.
 const WHO_AM_I_FALLBACK: u8 = 0x05;
 const WHO_AM_I_ALT: u8 = 0x0F;
 
+// Gyro bias calibration: time between stationary reads (a fresh sample at >= 500 Hz ODR)
+const GYRO_CAL_SAMPLE_DELAY_US: u32 = 2000;
+
+// Probe backoff: first retry waits this long, doubling up to the cap
+const PROBE_BACKOFF_START_MS: u32 = 2;
+const PROBE_BACKOFF_MAX_MS: u32 = 64;
+
+// Raw counts per unit at the `Qmi8658Config::default` ranges (~1000 counts per g, 16 dps)
+pub const DEFAULT_ACCEL_COUNTS_PER_G: i32 = AccelRange::Legacy.counts_per_g();
+pub const DEFAULT_GYRO_COUNTS_PER_DPS: i32 = GyroRange::Dps16.counts_per_dps();
+
+// One accel + gyro reading in raw counts, tagged with the full scale it was taken at so
+// it can be converted to g / dps
 #[derive(Clone, Copy, Debug)]
 pub struct ImuSample {
     pub accel: [i16; 3],
     pub gyro: [i16; 3],
+    pub accel_range: AccelRange,
+    pub gyro_range: GyroRange,
+}
+
+// Zeroed sample at the default ranges (e.g. to fill a buffer for `Qmi8658::read_fifo`)
+impl Default for ImuSample {
+    fn default() -> Self {
+        let config = Qmi8658Config::default();
+        Self {
+            accel: [0; 3],
+            gyro: [0; 3],
+            accel_range: config.accel_range,
+            gyro_range: config.gyro_range,
+        }
+    }
 }
 
 impl ImuSample {
+    // Acceleration per axis in g
+    pub fn accel_g(&self) -> [f32; 3] {
+        let per_g = self.accel_range.counts_per_g() as f32;
+        self.accel.map(|v| v as f32 / per_g)
+    }
+
+    // Angular rate per axis in degrees per second
+    pub fn gyro_dps(&self) -> [f32; 3] {
+        let per_dps = self.gyro_range.counts_per_dps() as f32;
+        self.gyro.map(|v| v as f32 / per_dps)
+    }
+
     #[inline]
     pub fn accel_mag_sq(&self) -> i64 {
         self.accel
@@ -57,6 +150,10 @@ impl ImuSample {
 pub enum ImuError<E> {
     Bus(E),
     BadWhoAmI(u8),
+    // No address in the probe list acknowledged after all retries
+    NotFound,
+    // A CTRL9 host command never reported done
+    Timeout,
 }
 
 // Allow automatic conversion from I2C errors
@@ -66,10 +163,202 @@ impl<E> From<E> for ImuError<E> {
     }
 }
 
+// Accelerometer full scale. Raw counts per g = 32768 / range, so thresholds given in raw
+// counts (SmashDetector, TapDetector, ...) scale by `counts_per_g()` between ranges.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AccelRange {
+    G2,
+    G4,
+    G8,
+    G16,
+    // Full-scale code 0b110, what init has always written (CTRL2 = 0x64). It is outside the
+    // datasheet's table; the raw thresholds in this module were tuned on it at the observed
+    // ~1000 counts per g, so that is the scale used for it.
+    Legacy,
+}
+
+impl AccelRange {
+    const fn bits(self) -> u8 {
+        match self {
+            AccelRange::G2 => 0,
+            AccelRange::G4 => 1,
+            AccelRange::G8 => 2,
+            AccelRange::G16 => 3,
+            AccelRange::Legacy => 6,
+        }
+    }
+
+    pub const fn counts_per_g(self) -> i32 {
+        match self {
+            AccelRange::Legacy => 1000,
+            _ => 16_384 >> self.bits(),
+        }
+    }
+}
+
+// Accelerometer output data rate. The LowPower rates only apply with the gyro disabled
+// (as in wake-on-motion); with both sensors on the chip runs the normal rates.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AccelOdr {
+    Hz8000,
+    Hz4000,
+    Hz2000,
+    Hz1000,
+    Hz500,
+    Hz250,
+    Hz125,
+    Hz62,
+    Hz31,
+    LowPower128,
+    LowPower21,
+    LowPower11,
+    LowPower3,
+}
+
+impl AccelOdr {
+    fn bits(self) -> u8 {
+        match self {
+            AccelOdr::Hz8000 => 0x0,
+            AccelOdr::Hz4000 => 0x1,
+            AccelOdr::Hz2000 => 0x2,
+            AccelOdr::Hz1000 => 0x3,
+            AccelOdr::Hz500 => 0x4,
+            AccelOdr::Hz250 => 0x5,
+            AccelOdr::Hz125 => 0x6,
+            AccelOdr::Hz62 => 0x7,
+            AccelOdr::Hz31 => 0x8,
+            AccelOdr::LowPower128 => 0xC,
+            AccelOdr::LowPower21 => 0xD,
+            AccelOdr::LowPower11 => 0xE,
+            AccelOdr::LowPower3 => 0xF,
+        }
+    }
+}
+
+// Gyro full scale. Raw counts per dps = 32768 / range (2048 at the default 16 dps).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum GyroRange {
+    Dps16,
+    Dps32,
+    Dps64,
+    Dps128,
+    Dps256,
+    Dps512,
+    Dps1024,
+    Dps2048,
+}
+
+impl GyroRange {
+    const fn bits(self) -> u8 {
+        match self {
+            GyroRange::Dps16 => 0,
+            GyroRange::Dps32 => 1,
+            GyroRange::Dps64 => 2,
+            GyroRange::Dps128 => 3,
+            GyroRange::Dps256 => 4,
+            GyroRange::Dps512 => 5,
+            GyroRange::Dps1024 => 6,
+            GyroRange::Dps2048 => 7,
+        }
+    }
+
+    pub const fn counts_per_dps(self) -> i32 {
+        2048 >> self.bits()
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum GyroOdr {
+    Hz8000,
+    Hz4000,
+    Hz2000,
+    Hz1000,
+    Hz500,
+    Hz250,
+    Hz125,
+    Hz62,
+    Hz31,
+}
+
+impl GyroOdr {
+    fn bits(self) -> u8 {
+        match self {
+            GyroOdr::Hz8000 => 0x0,
+            GyroOdr::Hz4000 => 0x1,
+            GyroOdr::Hz2000 => 0x2,
+            GyroOdr::Hz1000 => 0x3,
+            GyroOdr::Hz500 => 0x4,
+            GyroOdr::Hz250 => 0x5,
+            GyroOdr::Hz125 => 0x6,
+            GyroOdr::Hz62 => 0x7,
+            GyroOdr::Hz31 => 0x8,
+        }
+    }
+}
+
+// FIFO operating mode for `Qmi8658::enable_fifo`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FifoMode {
+    // FIFO off; samples only through the output registers (the default after init)
+    Bypass,
+    // Fill up to capacity, then stop storing until drained
+    Fifo,
+    // Keep the newest samples, dropping the oldest once full
+    Stream,
+}
+
+impl FifoMode {
+    fn bits(self) -> u8 {
+        match self {
+            FifoMode::Bypass => 0,
+            FifoMode::Fifo => 1,
+            FifoMode::Stream => 2,
+        }
+    }
+}
+
+// Sensor setup applied by `Qmi8658::new_with_config`. The `Default` reproduces the registers
+// init has always set: CTRL2 = 0x64 (`AccelRange::Legacy`, 500 Hz) and CTRL3 left at its
+// power-on 0x00 (+/-16 dps, 8 kHz). The detector default profiles are tuned at it; other
+// ranges need their raw thresholds scaled by the counts-per-unit ratio.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Qmi8658Config {
+    pub accel_range: AccelRange,
+    pub accel_odr: AccelOdr,
+    pub gyro_range: GyroRange,
+    pub gyro_odr: GyroOdr,
+}
+
+impl Default for Qmi8658Config {
+    fn default() -> Self {
+        Self {
+            accel_range: AccelRange::Legacy,
+            accel_odr: AccelOdr::Hz500,
+            gyro_range: GyroRange::Dps16,
+            gyro_odr: GyroOdr::Hz8000,
+        }
+    }
+}
+
+impl Qmi8658Config {
+    // CTRL2 (accel) byte
+    fn ctrl2(&self) -> u8 {
+        (self.accel_range.bits() << 4) | self.accel_odr.bits()
+    }
+
+    // CTRL3 (gyro) byte
+    fn ctrl3(&self) -> u8 {
+        (self.gyro_range.bits() << 4) | self.gyro_odr.bits()
+    }
+}
+
 // QMI8658 IMU driver
 pub struct Qmi8658<I2C> {
     i2c: I2C,
     address: u8,
+    config: Qmi8658Config,
+    // Zero-rate gyro offset in raw counts, subtracted by `read_sample_corrected`
+    gyro_bias: [i16; 3],
 }
 
 // Implement driver methods
@@ -77,19 +366,121 @@ impl<I2C> Qmi8658<I2C>
 where
     I2C: i2c::ErrorType + i2c::I2c,
 {
-    // Create a new instance and initialize the IMU
+    // Create a new instance and initialize the IMU with the default ranges and rates
     pub fn new(i2c: I2C, address: u8) -> Result<Self, ImuError<I2C::Error>> {
-        let mut this = Self { i2c, address };
+        Self::new_with_config(i2c, address, Qmi8658Config::default())
+    }
+
+    // Create a new instance and initialize the IMU with the given ranges and rates
+    pub fn new_with_config(
+        i2c: I2C,
+        address: u8,
+        config: Qmi8658Config,
+    ) -> Result<Self, ImuError<I2C::Error>> {
+        let mut this = Self {
+            i2c,
+            address,
+            config,
+            gyro_bias: [0; 3],
+        };
         this.init()?;
         Ok(this)
     }
 
+    // Ranges and rates in effect (for scaling raw samples)
+    pub fn config(&self) -> Qmi8658Config {
+        self.config
+    }
+
+    // Probe `addrs` in order and open the first one reporting a QMI8658 WHO_AM_I.
+    // The chip can miss the first transactions after power-up, so the whole list is
+    // retried `retries` more times with a doubling delay between rounds.
+    // Errors: BadWhoAmI if something answered with the wrong ID, otherwise NotFound.
+    pub fn probe_and_open<D: DelayNs>(
+        mut i2c: I2C,
+        addrs: &[u8],
+        delay: &mut D,
+        retries: u8,
+    ) -> Result<Self, ImuError<I2C::Error>> {
+        let mut bad_who: Option<u8> = None;
+        let mut backoff_ms = PROBE_BACKOFF_START_MS;
+
+        for attempt in 0..=retries {
+            if attempt > 0 {
+                delay.delay_ms(backoff_ms);
+                backoff_ms = (backoff_ms * 2).min(PROBE_BACKOFF_MAX_MS);
+            }
+            for &addr in addrs {
+                let mut who = [0u8];
+                if i2c.write_read(addr, &[REG_WHO_AM_I], &mut who).is_err() {
+                    continue;
+                }
+                if who[0] == WHO_AM_I_FALLBACK || who[0] == WHO_AM_I_ALT {
+                    return Self::new(i2c, addr);
+                }
+                bad_who = Some(who[0]);
+            }
+        }
+
+        Err(match bad_who {
+            Some(who) => ImuError::BadWhoAmI(who),
+            None => ImuError::NotFound,
+        })
+    }
+
     // Read WHO_AM_I register
     pub fn who_am_i(&mut self) -> Result<u8, ImuError<I2C::Error>> {
         self.read_reg(REG_WHO_AM_I)
     }
 
-    // Initialize the IMU with default settings
+    // Whether the chip still answers with a QMI8658 WHO_AM_I
+    pub fn is_responsive(&mut self) -> bool {
+        matches!(self.who_am_i(), Ok(WHO_AM_I_FALLBACK | WHO_AM_I_ALT))
+    }
+
+    // Run the built-in accel and gyro self-tests. Ok(true) when every axis responded
+    // beyond the datasheet minimum, Ok(false) when one fell short. Takes some tens of ms;
+    // the chip is re-initialised with `self.config` afterwards (FIFO / WoM settings are
+    // dropped, the gyro bias is kept). The watch should be still while it runs.
+    pub fn self_test(&mut self) -> Result<bool, ImuError<I2C::Error>> {
+        let accel = self.self_test_axis(REG_CTRL2, self.config.ctrl2())?;
+        let gyro = self.self_test_axis(REG_CTRL3, self.config.ctrl3())?;
+        self.init()?;
+        Ok(accel.iter().all(|d| d.unsigned_abs() > SELF_TEST_ACCEL_MIN)
+            && gyro.iter().all(|d| d.unsigned_abs() > SELF_TEST_GYRO_MIN))
+    }
+
+    // One self-test per the datasheet sequence: sensors off, set the trigger bit, wait for
+    // Avail high, clear the bit, wait for Avail low, read the three deltas
+    fn self_test_axis(&mut self, ctrl: u8, cfg: u8) -> Result<[i16; 3], ImuError<I2C::Error>> {
+        self.write_reg(REG_CTRL7, 0x00)?;
+        self.write_reg(ctrl, cfg | CTRL_SELF_TEST)?;
+        self.wait_avail(true)?;
+        self.write_reg(ctrl, cfg)?;
+        self.wait_avail(false)?;
+
+        let mut buf = [0u8; 6];
+        self.i2c
+            .write_read(self.address, &[REG_DVX_L], &mut buf)
+            .map_err(ImuError::Bus)?;
+        Ok([
+            i16::from_le_bytes([buf[0], buf[1]]),
+            i16::from_le_bytes([buf[2], buf[3]]),
+            i16::from_le_bytes([buf[4], buf[5]]),
+        ])
+    }
+
+    // Poll STATUSINT until Avail reads `level`
+    fn wait_avail(&mut self, level: bool) -> Result<(), ImuError<I2C::Error>> {
+        for _ in 0..SELF_TEST_POLL_LIMIT {
+            if (self.read_reg(REG_STATUSINT)? & STATUSINT_AVAIL != 0) == level {
+                return Ok(());
+            }
+        }
+        Err(ImuError::Timeout)
+    }
+
+    // Initialize the IMU with `self.config`
     fn init(&mut self) -> Result<(), ImuError<I2C::Error>> {
         let who = self.who_am_i()?;
         if who != WHO_AM_I_FALLBACK && who != WHO_AM_I_ALT {
@@ -100,11 +491,14 @@ where
         // Soft reset and clear low-power.
         // Ignore errors here to avoid blocking subsequent config steps.
         let _ = self.write_reg(REG_CTRL8, 0x10);
+        // Left armed if we woke from deep sleep by motion
+        let _ = self.disable_wake_on_motion();
 
-        // Accelerometer: +/-8g, ~1 kHz ODR (0x60 per datasheet examples), enable INT1/INT2
-        let _ = self.write_reg(REG_CTRL1, 0x60 | INT_ENABLE_BITS);
-        // Gyro: +/-512 dps, ~1 kHz ODR (0x64 per datasheet examples)
-        let _ = self.write_reg(REG_CTRL2, 0x64);
+        // Interface settings, enable INT1/INT2
+        let _ = self.write_reg(REG_CTRL1, CTRL1_IF_CFG | INT_ENABLE_BITS);
+        // Accelerometer and gyro full scale + ODR
+        let _ = self.write_reg(REG_CTRL2, self.config.ctrl2());
+        let _ = self.write_reg(REG_CTRL3, self.config.ctrl3());
 
         // Enable accel + gyro, set to Active
         self.write_reg(REG_CTRL7, 0x03)?;
@@ -115,6 +509,45 @@ where
         Ok(())
     }
 
+    // Switch to wake-on-motion for deep sleep: accel only at a low-power ODR, INT1 (GPIO8)
+    // idles high and goes low once any axis moves more than `threshold` mg.
+    // Sequence per the datasheet WoM section: disable the sensors, set the accel ODR,
+    // write the threshold (CAL1_L) and INT select/blanking (CAL1_H), latch them with
+    // CTRL9 WRITE_WOM_SETTING, then re-enable the accel. Data-ready routing is dropped
+    // so only motion drives INT1. The IMU has to stay powered through deep sleep;
+    // call `new` again after waking to get back to normal sampling (it also clears WoM,
+    // see `disable_wake_on_motion`).
+    pub fn enable_wake_on_motion(&mut self, threshold: u8) -> Result<(), ImuError<I2C::Error>> {
+        self.write_reg(REG_CTRL7, 0x00)?;
+        self.write_reg(REG_CTRL8, 0x00)?;
+        self.write_reg(REG_CTRL2, WOM_ACCEL_CFG)?;
+        self.write_reg(REG_CAL1_L, threshold)?;
+        self.write_reg(REG_CAL1_H, WOM_INT1_IDLE_HIGH | WOM_BLANKING_SAMPLES)?;
+        self.ctrl9_command(CTRL9_CMD_WRITE_WOM_SETTING)?;
+        self.write_reg(REG_CTRL7, 0x01)
+    }
+
+    // Turn wake-on-motion off: a zero threshold latched with WRITE_WOM_SETTING. The chip
+    // stays powered through deep sleep, so WoM is still armed after waking until this runs;
+    // `init` does it before setting up normal sampling.
+    pub fn disable_wake_on_motion(&mut self) -> Result<(), ImuError<I2C::Error>> {
+        self.write_reg(REG_CTRL7, 0x00)?;
+        self.write_reg(REG_CAL1_L, 0)?;
+        self.write_reg(REG_CAL1_H, 0)?;
+        self.ctrl9_command(CTRL9_CMD_WRITE_WOM_SETTING)
+    }
+
+    // Run a CTRL9 host command: write it, poll STATUSINT for CmdDone, then ack
+    fn ctrl9_command(&mut self, cmd: u8) -> Result<(), ImuError<I2C::Error>> {
+        self.write_reg(REG_CTRL9, cmd)?;
+        for _ in 0..CTRL9_POLL_LIMIT {
+            if self.read_reg(REG_STATUSINT)? & STATUSINT_CMD_DONE != 0 {
+                return self.write_reg(REG_CTRL9, CTRL9_CMD_ACK);
+            }
+        }
+        Err(ImuError::Timeout)
+    }
+
     // Read an 8-bit register
     pub fn read_reg8(&mut self, reg: u8) -> Result<u8, ImuError<I2C::Error>> {
         self.read_reg(reg)
@@ -154,7 +587,129 @@ where
             i16::from_le_bytes([buf[10], buf[11]]),
         ];
 
-        Ok(ImuSample { accel, gyro })
+        Ok(ImuSample {
+            accel,
+            gyro,
+            accel_range: self.config.accel_range,
+            gyro_range: self.config.gyro_range,
+        })
+    }
+
+    // Read a sample with the stored gyro bias subtracted (see `calibrate_gyro`)
+    pub fn read_sample_corrected(&mut self) -> Result<ImuSample, ImuError<I2C::Error>> {
+        let mut sample = self.read_sample()?;
+        for (g, b) in sample.gyro.iter_mut().zip(self.gyro_bias) {
+            *g = g.saturating_sub(b);
+        }
+        Ok(sample)
+    }
+
+    // Estimate the gyro zero-rate offset by averaging `samples` reads, store it and return
+    // it. The watch must lie still meanwhile: any rotation ends up in the bias. Failed reads
+    // are skipped; if none succeed the stored bias is left as it was.
+    pub fn calibrate_gyro(&mut self, samples: u16, delay: &mut impl DelayNs) -> [i16; 3] {
+        let mut sum = [0i32; 3];
+        let mut n = 0i32;
+        for _ in 0..samples {
+            if let Ok(sample) = self.read_sample() {
+                for (s, g) in sum.iter_mut().zip(sample.gyro) {
+                    *s += g as i32;
+                }
+                n += 1;
+            }
+            delay.delay_us(GYRO_CAL_SAMPLE_DELAY_US);
+        }
+        if n > 0 {
+            self.gyro_bias = sum.map(|s| (s / n) as i16);
+        }
+        self.gyro_bias
+    }
+
+    // Current gyro bias in raw counts (zero until calibrated)
+    pub fn gyro_bias(&self) -> [i16; 3] {
+        self.gyro_bias
+    }
+
+    // Restore a bias measured earlier (e.g. kept across a re-init)
+    pub fn set_gyro_bias(&mut self, bias: [i16; 3]) {
+        self.gyro_bias = bias;
+    }
+
+    // Buffer samples in the chip's FIFO so none are lost between slow polls. INT1 (GPIO8)
+    // then fires when `watermark` samples (1..=64) are waiting instead of per sample.
+    // `FifoMode::Bypass` turns the FIFO off again and restores data-ready on INT1.
+    pub fn enable_fifo(
+        &mut self,
+        mode: FifoMode,
+        watermark: u8,
+    ) -> Result<(), ImuError<I2C::Error>> {
+        if mode == FifoMode::Bypass {
+            self.write_reg(REG_FIFO_CTRL, 0)?;
+            self.write_reg(REG_CTRL1, CTRL1_IF_CFG | INT_ENABLE_BITS)?;
+            return self.write_reg(REG_CTRL8, CTRL8_DATAVALID_INT1);
+        }
+        // Reconfigure with the sensors stopped, then start from an empty FIFO
+        self.write_reg(REG_CTRL7, 0x00)?;
+        self.write_reg(REG_CTRL8, 0x00)?;
+        self.write_reg(REG_CTRL1, CTRL1_IF_CFG | INT_ENABLE_BITS | CTRL1_FIFO_INT1)?;
+        self.write_reg(REG_FIFO_WTM_TH, watermark.clamp(1, FIFO_CAPACITY))?;
+        self.write_reg(REG_FIFO_CTRL, FIFO_CTRL_SIZE_64 | mode.bits())?;
+        self.ctrl9_command(CTRL9_CMD_RST_FIFO)?;
+        self.write_reg(REG_CTRL7, 0x03)
+    }
+
+    // Drain up to `out.len()` buffered samples, oldest first; returns how many were
+    // written. Samples beyond `out` stay in the FIFO for the next call.
+    pub fn read_fifo(&mut self, out: &mut [ImuSample]) -> Result<usize, ImuError<I2C::Error>> {
+        let status = self.read_reg(REG_FIFO_STATUS)?;
+        let count = self.read_reg(REG_FIFO_SMPL_CNT)?;
+        let words = (((status & 0x03) as usize) << 8) | count as usize;
+        let available = (words * 2 / FIFO_SAMPLE_BYTES).min(out.len());
+        if available == 0 {
+            return Ok(0);
+        }
+
+        self.ctrl9_command(CTRL9_CMD_REQ_FIFO)?;
+        let mut buf = [0u8; FIFO_SAMPLE_BYTES * FIFO_READ_CHUNK];
+        let mut done = 0;
+        let mut result = Ok(());
+        while done < available {
+            let n = (available - done).min(FIFO_READ_CHUNK);
+            let bytes = &mut buf[..n * FIFO_SAMPLE_BYTES];
+            if let Err(e) = self.i2c.write_read(self.address, &[REG_FIFO_DATA], bytes) {
+                result = Err(ImuError::Bus(e));
+                break;
+            }
+            for (slot, raw) in out[done..done + n]
+                .iter_mut()
+                .zip(bytes.chunks_exact(FIFO_SAMPLE_BYTES))
+            {
+                let word = |i: usize| i16::from_le_bytes([raw[i * 2], raw[i * 2 + 1]]);
+                *slot = ImuSample {
+                    accel: [word(0), word(1), word(2)],
+                    gyro: [word(3), word(4), word(5)],
+                    accel_range: self.config.accel_range,
+                    gyro_range: self.config.gyro_range,
+                };
+            }
+            done += n;
+        }
+        // Leave read mode even after a failed burst, or the FIFO stops filling
+        let fifo_ctrl = self.read_reg(REG_FIFO_CTRL)?;
+        self.write_reg(REG_FIFO_CTRL, fifo_ctrl & !FIFO_CTRL_RD_MODE)?;
+        result.map(|_| done)
+    }
+
+    // Die temperature in degC (TEMP_H:TEMP_L, signed, 1/256 degC per LSB). This is the
+    // sensor chip's own temperature, so on the wrist it reads a few degrees above ambient
+    // (body heat, board and panel self-heating) and lags real changes; treat it as a
+    // rough indication only. Needs the sensors enabled (updated with each sample).
+    pub fn read_temperature(&mut self) -> Result<f32, ImuError<I2C::Error>> {
+        let mut buf = [0u8; 2];
+        self.i2c
+            .write_read(self.address, &[REG_TEMP_L], &mut buf)
+            .map_err(ImuError::Bus)?;
+        Ok(i16::from_le_bytes(buf) as f32 / 256.0)
     }
 
     // Consume the driver and return the underlying I2C bus
@@ -163,6 +718,15 @@ where
     }
 }
 
+// Direction of a detected smash relative to the learned gravity vector
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SmashKind {
+    // Spike along gravity (the slam down onto the watch)
+    Down,
+    // Spike against gravity (the pop up / rebound)
+    Up,
+}
+
 // Simple smash detector using acceleration magnitude and rise detection
 pub struct SmashDetector {
     threshold_sq: i64,
@@ -175,15 +739,124 @@ pub struct SmashDetector {
     cooldown_ms: u32,
     last_mag_sq: i64,
     last_freefall: bool,
-    last_trigger_ms: u64,
+    // Last hit per kind, indexed by `SmashKind as usize`: each kind has its own cooldown,
+    // so the rebound of one smash can't swallow the slam that follows it
+    last_trigger_ms: [u64; 2],
     gravity_dir: [i32; 3],
     gravity_samples: u16,
     baseline_mag_sq: i64,
     gravity_mag_sq: i64,
     baseline_dot: i64,
     last_dot: i64,
+    // Explicit gravity calibration in progress (see `begin_calibration`)
+    calibrating: bool,
+    cal_sum: [i64; 3],
+}
+
+// `SmashDetector` settings in physical units, converted to raw counts by `build` using
+// `counts_per_g` / `counts_per_dps` (defaults: `DEFAULT_ACCEL_COUNTS_PER_G` and
+// `DEFAULT_GYRO_COUNTS_PER_DPS`, the scale the default profile was tuned against). Unset
+// values keep the `default_rough` numbers. A gentler profile, for example:
+//
+//     let detector = SmashDetector::builder()
+//         .threshold_g(1.4)
+//         .rise_g(0.5)
+//         .cooldown_ms(250)
+//         .build();
+#[derive(Copy, Clone, Debug)]
+pub struct SmashDetectorBuilder {
+    threshold_g: f32,
+    rise_g: f32,
+    gyro_limit_dps: f32,
+    freefall_g: f32,
+    cooldown_ms: u32,
+    axis_ratio: (i32, i32),
+    counts_per_g: f32,
+    counts_per_dps: f32,
+}
+
+impl Default for SmashDetectorBuilder {
+    fn default() -> Self {
+        Self {
+            threshold_g: 1.8,
+            rise_g: 0.7,
+            // default_rough's 60k-count gyro gate
+            gyro_limit_dps: 60_000.0 / DEFAULT_GYRO_COUNTS_PER_DPS as f32,
+            freefall_g: 0.2,
+            cooldown_ms: 160,
+            axis_ratio: (2, 1),
+            counts_per_g: DEFAULT_ACCEL_COUNTS_PER_G as f32,
+            counts_per_dps: DEFAULT_GYRO_COUNTS_PER_DPS as f32,
+        }
+    }
 }
 
+impl SmashDetectorBuilder {
+    // Acceleration magnitude that counts as a hit
+    pub fn threshold_g(mut self, g: f32) -> Self {
+        self.threshold_g = g;
+        self
+    }
+
+    // Minimum jump in magnitude since the previous sample
+    pub fn rise_g(mut self, g: f32) -> Self {
+        self.rise_g = g;
+        self
+    }
+
+    // Rotation rate above which a hit is taken as a swing, not a smash
+    pub fn gyro_limit_dps(mut self, dps: f32) -> Self {
+        self.gyro_limit_dps = dps;
+        self
+    }
+
+    // Magnitude below which the watch is considered in freefall
+    pub fn freefall_g(mut self, g: f32) -> Self {
+        self.freefall_g = g;
+        self
+    }
+
+    pub fn cooldown_ms(mut self, ms: u32) -> Self {
+        self.cooldown_ms = ms;
+        self
+    }
+
+    // Dominant axis must exceed the others by num:den (num 0 turns the check off)
+    pub fn axis_ratio(mut self, num: i32, den: i32) -> Self {
+        self.axis_ratio = (num, den.max(1));
+        self
+    }
+
+    // Raw counts per g of the samples fed to the detector (see `AccelRange::counts_per_g`)
+    pub fn counts_per_g(mut self, counts: f32) -> Self {
+        self.counts_per_g = counts;
+        self
+    }
+
+    // Raw counts per dps of the samples fed to the detector (see `GyroRange::counts_per_dps`)
+    pub fn counts_per_dps(mut self, counts: f32) -> Self {
+        self.counts_per_dps = counts;
+        self
+    }
+
+    pub fn build(self) -> SmashDetector {
+        let g = |v: f32| (v * self.counts_per_g + 0.5) as i32;
+        let dps = |v: f32| (v * self.counts_per_dps + 0.5) as i32;
+        let mut s = SmashDetector::new(
+            g(self.threshold_g),
+            g(self.rise_g),
+            dps(self.gyro_limit_dps),
+            g(self.freefall_g),
+            self.cooldown_ms,
+        );
+        (s.axis_ratio_num, s.axis_ratio_den) = self.axis_ratio;
+        s
+    }
+}
+
+// Stationary samples averaged by an explicit gravity calibration
+const GRAVITY_CAL_SAMPLES: u16 = 64;
+
 // Implement smash detector methods
 impl SmashDetector {
     pub fn new(
@@ -203,13 +876,15 @@ impl SmashDetector {
             cooldown_ms,
             last_mag_sq: 0,
             last_freefall: false,
-            last_trigger_ms: 0,
+            last_trigger_ms: [0; 2],
             gravity_dir: [0; 3],
             gravity_samples: 0,
             baseline_mag_sq: 0,
             gravity_mag_sq: 0,
             baseline_dot: 0,
             last_dot: 0,
+            calibrating: false,
+            cal_sum: [0; 3],
         }
     }
 
@@ -224,12 +899,81 @@ impl SmashDetector {
         s
     }
 
-    // Update with a new sample, return true if a smash event is detected
-    pub fn update(&mut self, now_ms: u64, sample: &ImuSample) -> bool {
+    // Builder taking the thresholds in g / dps; starts from the `default_rough` profile
+    pub fn builder() -> SmashDetectorBuilder {
+        SmashDetectorBuilder::default()
+    }
+
+    // Forget the learned gravity and re-measure it from GRAVITY_CAL_SAMPLES consecutive
+    // stationary samples. Any movement restarts the count; no smashes fire meanwhile.
+    pub fn begin_calibration(&mut self) {
+        self.gravity_dir = [0; 3];
+        self.gravity_samples = 0;
+        self.gravity_mag_sq = 0;
+        self.baseline_dot = 0;
+        self.last_dot = 0;
+        self.cal_sum = [0; 3];
+        self.calibrating = true;
+    }
+
+    pub fn is_calibrating(&self) -> bool {
+        self.calibrating
+    }
+
+    // 0..=100 while calibrating, 100 once gravity is known (explicitly or learned), else 0
+    pub fn calibration_progress(&self) -> u8 {
+        if self.calibrating {
+            (self.gravity_samples as u32 * 100 / GRAVITY_CAL_SAMPLES as u32) as u8
+        } else if self.gravity_mag_sq > 0 {
+            100
+        } else {
+            0
+        }
+    }
+
+    // One calibration sample: average it in if the watch is still, otherwise start over
+    fn calibration_step(&mut self, sample: &ImuSample, mag_sq: i64, gyro_sq: i64) {
+        let still = gyro_sq < 10_000 && mag_sq > 600_000 && mag_sq < 4_000_000;
+        if !still {
+            self.gravity_samples = 0;
+            self.cal_sum = [0; 3];
+            return;
+        }
+        for i in 0..3 {
+            self.cal_sum[i] += sample.accel[i] as i64;
+        }
+        self.gravity_samples += 1;
+        if self.gravity_samples < GRAVITY_CAL_SAMPLES {
+            return;
+        }
+        // Done: lock the average in; lazy learning keeps refining it from here
+        for i in 0..3 {
+            self.gravity_dir[i] = (self.cal_sum[i] / GRAVITY_CAL_SAMPLES as i64) as i32;
+        }
+        self.gravity_mag_sq = self
+            .gravity_dir
+            .iter()
+            .map(|v| {
+                let vv = *v as i64;
+                vv * vv
+            })
+            .sum();
+        self.baseline_dot = self.gravity_mag_sq;
+        self.last_dot = self.baseline_dot;
+        self.calibrating = false;
+    }
+
+    // Update with a new sample, return the smash direction if one is detected.
+    // Until gravity has been learned the direction can't be told apart, so hits count as Down.
+    pub fn update(&mut self, now_ms: u64, sample: &ImuSample) -> Option<SmashKind> {
         let mag_sq = sample.accel_mag_sq();
         let gyro_sq = sample.gyro_mag_sq();
-        let in_cooldown = now_ms.saturating_sub(self.last_trigger_ms) < self.cooldown_ms as u64;
-
+        if self.calibrating {
+            self.calibration_step(sample, mag_sq, gyro_sq);
+            self.last_mag_sq = mag_sq;
+            self.last_freefall = mag_sq < self.freefall_sq;
+            return None;
+        }
         // Freefall guard: if the previous sample was near zero-g, treat the spike as a drop.
         let freefall_guard = self.last_freefall;
         self.last_freefall = mag_sq < self.freefall_sq;
@@ -265,18 +1009,25 @@ impl SmashDetector {
             }
         }
 
-        // Axis bias check: projection should move further along gravity than the baseline (smash down).
-        let mut axis_ok = true;
+        // Axis bias check: the projection must jump away from the baseline along gravity
+        // (smash down) or against it (pop up); which one decides the kind.
+        let mut kind = Some(SmashKind::Down);
         if self.gravity_mag_sq > 0 {
-            let dot: i64 = (sample.accel[0] as i64 * self.gravity_dir[0] as i64)
-                + (sample.accel[1] as i64 * self.gravity_dir[1] as i64)
-                + (sample.accel[2] as i64 * self.gravity_dir[2] as i64);
+            let dot = self.gravity_dot(sample);
             let delta = dot.saturating_sub(self.baseline_dot); // positive if more along gravity
+            let step = dot - self.last_dot;
             let rise_min = self.gravity_mag_sq / 2; // need ~0.5g^2 additional projection
             let dot_rise_min = self.rise_threshold_sq / 2;
-            axis_ok = (dot * self.baseline_dot) > 0 // same general direction as gravity
+            kind = if (dot * self.baseline_dot) > 0 // same general direction as gravity
                 && delta >= rise_min
-                && (dot - self.last_dot) >= dot_rise_min;
+                && step >= dot_rise_min
+            {
+                Some(SmashKind::Down)
+            } else if delta <= -rise_min && step <= -dot_rise_min {
+                Some(SmashKind::Up)
+            } else {
+                None
+            };
             self.last_dot = dot;
         }
 
@@ -321,20 +1072,25 @@ impl SmashDetector {
             jump_ok = mag_sq.saturating_mul(1) > self.baseline_mag_sq.saturating_mul(4);
         }
 
+        let in_cooldown = kind.is_some_and(|k| {
+            now_ms.saturating_sub(self.last_trigger_ms[k as usize]) < self.cooldown_ms as u64
+        });
         let hit = !in_cooldown
             && !freefall_guard
             && mag_sq >= self.threshold_sq
             && rising_fast
             && gyro_ok
-            && axis_ok
+            && kind.is_some()
             && ratio_ok
             && jump_ok;
 
-        if hit {
-            self.last_trigger_ms = now_ms;
+        if !hit {
+            return None;
         }
-
-        hit
+        if let Some(k) = kind {
+            self.last_trigger_ms[k as usize] = now_ms;
+        }
+        kind
     }
 
     // Compute the dot product of the sample acceleration with the learned gravity direction
@@ -344,3 +1100,723 @@ impl SmashDetector {
             + (sample.accel[2] as i64 * self.gravity_dir[2] as i64)
     }
 }
+
+// Drop detector: |a| stays below the freefall level (as in `SmashDetector`'s freefall
+// guard) for at least `min_ms`. Reports once per fall; the watch has to see normal
+// acceleration again (caught or landed) before another drop can fire.
+pub struct FreefallDetector {
+    freefall_sq: i64,
+    min_ms: u32,
+    since_ms: Option<u64>,
+    reported: bool,
+}
+
+impl FreefallDetector {
+    pub const fn new(freefall_raw: i32, min_ms: u32) -> Self {
+        Self {
+            freefall_sq: (freefall_raw as i64) * (freefall_raw as i64),
+            min_ms,
+            since_ms: None,
+            reported: false,
+        }
+    }
+
+    // Default profile: below ~0.2g (~1000 counts per g) for 120 ms, about a 7 cm fall,
+    // so a drop from wrist height is reported well before it lands
+    pub const fn default_profile() -> Self {
+        Self::new(200, 120)
+    }
+
+    // True on the sample where a fall has lasted `min_ms`
+    pub fn update(&mut self, now_ms: u64, sample: &ImuSample) -> bool {
+        if sample.accel_mag_sq() >= self.freefall_sq {
+            self.since_ms = None;
+            self.reported = false;
+            return false;
+        }
+        let since = *self.since_ms.get_or_insert(now_ms);
+        if !self.reported && now_ms.saturating_sub(since) >= self.min_ms as u64 {
+            self.reported = true;
+            return true;
+        }
+        false
+    }
+
+    // In freefall right now (any duration)
+    pub fn is_falling(&self) -> bool {
+        self.since_ms.is_some()
+    }
+}
+
+// Longest lowered -> raising gap that still counts as a raise gesture
+const WRIST_GESTURE_MS: u32 = 1000;
+
+// Wrist raise / lower events from `WristRaiseDetector`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum WristEvent {
+    Raised,
+    Lowered,
+}
+
+// Tilt-based wrist raise detector. "Raised" means the display normal (+Z) points within
+// ~40 deg of straight up, "lowered" means it tilts past ~60 deg; between the two nothing
+// changes (hysteresis band). A new posture also has to hold for a dwell time, and samples
+// taken while the arm is moving (|a| far from 1g) are ignored, so small jitters and swings
+// don't toggle the state.
+// On top of that, a raise gesture: a Raised that started within `gesture_ms` of the face
+// last being in the lowered band (tilted past ~60 deg: hanging at the side, face down).
+// That is the quick flick into view, as opposed to slowly settling face up. Tilt comes
+// straight from the accelerometer's gravity reading at rest, so nothing needs learning
+// first (unlike `SmashDetector`'s gravity).
+pub struct WristRaiseDetector {
+    // cos(tilt) thresholds in percent
+    raise_cos_pct: i64,
+    lower_cos_pct: i64,
+    raise_dwell_ms: u32,
+    lower_dwell_ms: u32,
+    gesture_ms: u32,
+    raised: bool,
+    pending_since: Option<u64>,
+    // Last trusted sample in the lowered band
+    lowered_at: Option<u64>,
+    gesture: bool,
+}
+
+impl WristRaiseDetector {
+    pub fn new(
+        raise_cos_pct: u8,
+        lower_cos_pct: u8,
+        raise_dwell_ms: u32,
+        lower_dwell_ms: u32,
+    ) -> Self {
+        Self {
+            raise_cos_pct: raise_cos_pct.min(100) as i64,
+            lower_cos_pct: lower_cos_pct.min(raise_cos_pct).min(100) as i64,
+            raise_dwell_ms,
+            lower_dwell_ms,
+            gesture_ms: WRIST_GESTURE_MS,
+            raised: true,
+            pending_since: None,
+            lowered_at: None,
+            gesture: false,
+        }
+    }
+
+    // Default profile: raise within ~40 deg (cos 0.77) held 200 ms, lower past ~60 deg (cos 0.5) held 800 ms
+    pub fn default_profile() -> Self {
+        Self::new(77, 50, 200, 800)
+    }
+
+    pub fn is_raised(&self) -> bool {
+        self.raised
+    }
+
+    pub fn set_gesture_ms(&mut self, gesture_ms: u32) {
+        self.gesture_ms = gesture_ms;
+    }
+
+    // Update and report only raise gestures (see the type comment); for callers that
+    // don't need the Raised/Lowered state
+    pub fn update_gesture(&mut self, now_ms: u64, sample: &ImuSample) -> bool {
+        self.update(now_ms, sample) == Some(WristEvent::Raised) && self.gesture
+    }
+
+    // Whether the latest Raised was a raise gesture
+    pub fn raised_by_gesture(&self) -> bool {
+        self.gesture
+    }
+
+    // Update with a new sample, return an event when the raised/lowered state flips
+    pub fn update(&mut self, now_ms: u64, sample: &ImuSample) -> Option<WristEvent> {
+        let mag_sq = sample.accel_mag_sq();
+        // ~1000 counts per g: only trust tilt while roughly at rest (0.7g .. 1.3g)
+        if !(490_000..=1_690_000).contains(&mag_sq) {
+            return None;
+        }
+        // z/|a| >= cos  <=>  z >= 0 && z^2 * 100^2 >= cos^2 * |a|^2 (no sqrt needed)
+        let z = sample.accel[2] as i64;
+        let facing = |cos_pct: i64| z > 0 && z * z * 10_000 >= cos_pct * cos_pct * mag_sq;
+        if !facing(self.lower_cos_pct) {
+            self.lowered_at = Some(now_ms);
+        }
+
+        let wants_flip = if self.raised {
+            !facing(self.lower_cos_pct)
+        } else {
+            facing(self.raise_cos_pct)
+        };
+        if !wants_flip {
+            self.pending_since = None;
+            return None;
+        }
+
+        let since = *self.pending_since.get_or_insert(now_ms);
+        let dwell = if self.raised {
+            self.lower_dwell_ms
+        } else {
+            self.raise_dwell_ms
+        };
+        if now_ms.saturating_sub(since) < dwell as u64 {
+            return None;
+        }
+        self.pending_since = None;
+        self.raised = !self.raised;
+        self.gesture = self.raised
+            && self
+                .lowered_at
+                .is_some_and(|t| since.saturating_sub(t) <= self.gesture_ms as u64);
+        Some(if self.raised {
+            WristEvent::Raised
+        } else {
+            WristEvent::Lowered
+        })
+    }
+}
+
+// Coarse device posture from gravity: which face or panel edge points up. Axes as for
+// `OrientationDetector` (+Y top edge, +X right edge, +Z out of the display).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DeviceOrientation {
+    FaceUp,
+    FaceDown,
+    // Top edge up (upright)
+    PortraitUp,
+    // Bottom edge up
+    PortraitDown,
+    // Left edge up
+    LandscapeLeft,
+    // Right edge up
+    LandscapeRight,
+}
+
+impl DeviceOrientation {
+    // Axis (0 = X, 1 = Y, 2 = Z) and sign of gravity for this posture
+    fn axis(self) -> (usize, i64) {
+        match self {
+            DeviceOrientation::FaceUp => (2, 1),
+            DeviceOrientation::FaceDown => (2, -1),
+            DeviceOrientation::PortraitUp => (1, 1),
+            DeviceOrientation::PortraitDown => (1, -1),
+            DeviceOrientation::LandscapeLeft => (0, -1),
+            DeviceOrientation::LandscapeRight => (0, 1),
+        }
+    }
+
+    fn from_axis(axis: usize, positive: bool) -> Self {
+        match (axis, positive) {
+            (0, true) => DeviceOrientation::LandscapeRight,
+            (0, false) => DeviceOrientation::LandscapeLeft,
+            (1, true) => DeviceOrientation::PortraitUp,
+            (1, false) => DeviceOrientation::PortraitDown,
+            (_, true) => DeviceOrientation::FaceUp,
+            (_, false) => DeviceOrientation::FaceDown,
+        }
+    }
+}
+
+// Which panel edge points up, for auto-rotate. Assumes the IMU's +Y points at the top of
+// the upright display and +X at its right edge (gravity reads as +1g along "up").
+// Reports quarter turns clockwise: 0 = upright, 1 = right edge up, 2 = upside down,
+// 3 = left edge up. A new quadrant must beat the current one by `hysteresis_pct` (so
+// wobbling around 45 deg doesn't flip-flop) and hold for a dwell time. Samples taken while
+// moving, or with the face nearly flat (no clear "up" in the panel plane), are ignored.
+pub struct OrientationDetector {
+    hysteresis_pct: i64,
+    dwell_ms: u32,
+    quarter: u8,
+    // Quadrant waiting out the dwell, and since when
+    candidate: Option<(u8, u64)>,
+    // Last posture seen at rest (see `orientation`)
+    posture: DeviceOrientation,
+}
+
+impl OrientationDetector {
+    pub fn new(hysteresis_pct: u8, dwell_ms: u32) -> Self {
+        Self {
+            hysteresis_pct: hysteresis_pct as i64,
+            dwell_ms,
+            quarter: 0,
+            candidate: None,
+            posture: DeviceOrientation::FaceUp,
+        }
+    }
+
+    // Default profile: switch past ~52 deg (30% margin over the 45 deg boundary), held 500 ms
+    pub fn default_profile() -> Self {
+        Self::new(30, 500)
+    }
+
+    pub fn quarter_turns(&self) -> u8 {
+        self.quarter
+    }
+
+    // Posture as of the last resting sample passed to `update`
+    pub fn posture(&self) -> DeviceOrientation {
+        self.posture
+    }
+
+    // Classify `sample` by the axis gravity mostly lies along. The current posture is kept
+    // until another axis beats it by `hysteresis_pct`, so a watch held near 45 deg between
+    // two postures doesn't chatter. Does not check that the watch is at rest.
+    pub fn orientation(&self, sample: &ImuSample) -> DeviceOrientation {
+        let a = sample.accel.map(|v| v as i64);
+        let best = (0..3).max_by_key(|&i| a[i].abs()).unwrap_or(2);
+        let candidate = DeviceOrientation::from_axis(best, a[best] > 0);
+        let (axis, sign) = self.posture.axis();
+        if candidate == self.posture
+            || a[best].abs() * 100 < a[axis] * sign * (100 + self.hysteresis_pct)
+        {
+            self.posture
+        } else {
+            candidate
+        }
+    }
+
+    // Update with a new sample, return the new quarter turns when the orientation changes
+    pub fn update(&mut self, now_ms: u64, sample: &ImuSample) -> Option<u8> {
+        let mag_sq = sample.accel_mag_sq();
+        // ~1000 counts per g: only trust gravity while roughly at rest (0.7g .. 1.3g)
+        if !(490_000..=1_690_000).contains(&mag_sq) {
+            return None;
+        }
+        self.posture = self.orientation(sample);
+        let (x, y) = (sample.accel[0] as i64, sample.accel[1] as i64);
+        // Less than half of gravity in the panel plane (within ~30 deg of flat): keep
+        if 4 * (x * x + y * y) < mag_sq {
+            self.candidate = None;
+            return None;
+        }
+
+        let up = |q: u8| match q {
+            0 => y,
+            1 => x,
+            2 => -y,
+            _ => -x,
+        };
+        let q = if x.abs() > y.abs() {
+            if x > 0 {
+                1
+            } else {
+                3
+            }
+        } else if y > 0 {
+            0
+        } else {
+            2
+        };
+        if q == self.quarter || up(q) * 100 < up(self.quarter) * (100 + self.hysteresis_pct) {
+            self.candidate = None;
+            return None;
+        }
+
+        let since = match self.candidate {
+            Some((c, t)) if c == q => t,
+            _ => {
+                self.candidate = Some((q, now_ms));
+                now_ms
+            }
+        };
+        if now_ms.saturating_sub(since) < self.dwell_ms as u64 {
+            return None;
+        }
+        self.candidate = None;
+        self.quarter = q;
+        Some(q)
+    }
+}
+
+// Shake gesture: the wrist rotating quickly back and forth. Only samples above the gyro
+// threshold count, and each counts toward a shake only when the dominant rotation axis
+// flips direction. A smash is one accel spike (plus maybe a single rebound), so it can't
+// produce the several strong reversals a shake needs.
+pub struct ShakeDetector {
+    gyro_threshold_sq: i64,
+    reversals_needed: u8,
+    window_ms: u32,
+    cooldown_ms: u32,
+    // (axis, positive) of the last strong sample in the current window
+    last_dir: Option<(usize, bool)>,
+    reversals: u8,
+    window_start_ms: u64,
+    last_trigger_ms: Option<u64>,
+}
+
+impl ShakeDetector {
+    pub fn new(
+        gyro_threshold_raw: i32,
+        reversals_needed: u8,
+        window_ms: u32,
+        cooldown_ms: u32,
+    ) -> Self {
+        Self {
+            gyro_threshold_sq: (gyro_threshold_raw as i64) * (gyro_threshold_raw as i64),
+            reversals_needed: reversals_needed.max(1),
+            window_ms,
+            cooldown_ms,
+            last_dir: None,
+            reversals: 0,
+            window_start_ms: 0,
+            last_trigger_ms: None,
+        }
+    }
+
+    // Default profile, in raw counts at the default config: 12000-count swings, 3 reversals
+    // within 800 ms, then 1 s cooldown
+    pub fn default_profile() -> Self {
+        Self::new(12_000, 3, 800, 1000)
+    }
+
+    // Update with a new sample, return true once per detected shake
+    pub fn update(&mut self, now_ms: u64, sample: &ImuSample) -> bool {
+        if now_ms.saturating_sub(self.window_start_ms) > self.window_ms as u64 {
+            self.last_dir = None;
+            self.reversals = 0;
+        }
+        if sample.gyro_mag_sq() < self.gyro_threshold_sq {
+            return false;
+        }
+
+        // Direction of the dominant rotation axis
+        let axis = (0..3)
+            .max_by_key(|&i| (sample.gyro[i] as i32).abs())
+            .unwrap_or(0);
+        let dir = (axis, sample.gyro[axis] > 0);
+        match self.last_dir {
+            None => {
+                self.window_start_ms = now_ms;
+                self.reversals = 0;
+            }
+            Some((last_axis, last_pos)) if last_axis == axis && last_pos != dir.1 => {
+                self.reversals = self.reversals.saturating_add(1);
+            }
+            _ => {}
+        }
+        self.last_dir = Some(dir);
+
+        if self.reversals < self.reversals_needed {
+            return false;
+        }
+        self.last_dir = None;
+        self.reversals = 0;
+        let cooling = self
+            .last_trigger_ms
+            .is_some_and(|t| now_ms.saturating_sub(t) < self.cooldown_ms as u64);
+        if cooling {
+            return false;
+        }
+        self.last_trigger_ms = Some(now_ms);
+        true
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TapEvent {
+    Tap,
+    Hold,
+}
+
+// Knock on the case: a sharp change in acceleration between consecutive samples while the
+// wrist itself isn't rotating (so arm swings and shakes don't count). Jerks less than
+// `settle_ms` apart belong to the same contact. A contact that dies out before `hold_ms`
+// is a Tap; one that keeps going (finger pressed against the case) reports Hold once,
+// as soon as it reaches `hold_ms`.
+pub struct TapDetector {
+    jerk_threshold_sq: i64,
+    gyro_quiet_sq: i64,
+    settle_ms: u32,
+    hold_ms: u32,
+    prev_accel: Option<[i16; 3]>,
+    // (start, last jerk) of the contact in progress
+    contact: Option<(u64, u64)>,
+    held: bool,
+}
+
+impl TapDetector {
+    pub fn new(jerk_threshold_raw: i32, gyro_quiet_raw: i32, settle_ms: u32, hold_ms: u32) -> Self {
+        Self {
+            jerk_threshold_sq: (jerk_threshold_raw as i64) * (jerk_threshold_raw as i64),
+            gyro_quiet_sq: (gyro_quiet_raw as i64) * (gyro_quiet_raw as i64),
+            settle_ms,
+            hold_ms,
+            prev_accel: None,
+            contact: None,
+            held: false,
+        }
+    }
+
+    // Default profile: ~0.5g jump between samples, wrist turning slower than ~60 dps,
+    // 80 ms to settle, hold after 600 ms
+    pub fn default_profile() -> Self {
+        Self::new(500, 4000, 80, 600)
+    }
+
+    pub fn set_hold_ms(&mut self, hold_ms: u32) {
+        self.hold_ms = hold_ms;
+    }
+
+    // Update with a new sample, return an event when a contact is classified
+    pub fn update(&mut self, now_ms: u64, sample: &ImuSample) -> Option<TapEvent> {
+        let jerk_sq: i64 = match self.prev_accel {
+            Some(prev) => (0..3)
+                .map(|i| {
+                    let d = sample.accel[i] as i64 - prev[i] as i64;
+                    d * d
+                })
+                .sum(),
+            None => 0,
+        };
+        self.prev_accel = Some(sample.accel);
+        let strong = jerk_sq >= self.jerk_threshold_sq && sample.gyro_mag_sq() < self.gyro_quiet_sq;
+
+        let Some((start, last)) = self.contact else {
+            if strong {
+                self.contact = Some((now_ms, now_ms));
+            }
+            return None;
+        };
+        let last = if strong { now_ms } else { last };
+        self.contact = Some((start, last));
+
+        if !self.held && last.saturating_sub(start) >= self.hold_ms as u64 {
+            self.held = true;
+            return Some(TapEvent::Hold);
+        }
+        if now_ms.saturating_sub(last) <= self.settle_ms as u64 {
+            return None;
+        }
+        self.contact = None;
+        if core::mem::take(&mut self.held) {
+            None
+        } else {
+            Some(TapEvent::Tap)
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TapAction {
+    Next,
+    Select,
+    Back,
+}
+
+// Tap patterns for button-free navigation: one tap = Next, two taps within `double_ms`
+// = Select, a tap-hold = Back. A single tap is held back for `double_ms` to see whether
+// a second one follows, so keep the window short for quick tappers.
+pub struct TapNavigator {
+    double_ms: u32,
+    // Time of a first tap still waiting for a partner
+    pending: Option<u64>,
+}
+
+impl TapNavigator {
+    pub const fn new(double_ms: u32) -> Self {
+        Self {
+            double_ms,
+            pending: None,
+        }
+    }
+
+    pub fn set_double_ms(&mut self, double_ms: u32) {
+        self.double_ms = double_ms;
+    }
+
+    // Feed this loop's tap event (if any), return the action to perform
+    pub fn update(&mut self, now_ms: u64, event: Option<TapEvent>) -> Option<TapAction> {
+        match event {
+            Some(TapEvent::Hold) => {
+                self.pending = None;
+                Some(TapAction::Back)
+            }
+            Some(TapEvent::Tap) => match self.pending.take() {
+                Some(_) => Some(TapAction::Select),
+                None => {
+                    self.pending = Some(now_ms);
+                    None
+                }
+            },
+            None => match self.pending {
+                Some(t0) if now_ms.saturating_sub(t0) > self.double_ms as u64 => {
+                    self.pending = None;
+                    Some(TapAction::Next)
+                }
+                _ => None,
+            },
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TapCount {
+    Single,
+    Double,
+}
+
+// Single/double taps on their own, for uses that don't want the full navigation mapping
+// (e.g. double-tap to wake). Built from a `TapDetector` and a `TapNavigator`, so taps are
+// detected and paired exactly as in tap navigation; tap-holds are ignored. Independent of
+// `SmashDetector`: a smash still reaches this as a (large) tap, so filter on the caller's
+// side where both are live.
+pub struct DoubleTapDetector {
+    taps: TapDetector,
+    pairing: TapNavigator,
+}
+
+impl DoubleTapDetector {
+    // `double_ms`: the longest gap between two taps of a double tap
+    pub fn new(taps: TapDetector, double_ms: u32) -> Self {
+        Self {
+            taps,
+            pairing: TapNavigator::new(double_ms),
+        }
+    }
+
+    // TapDetector defaults (~0.5g knock at the board's ~1000 counts/g), 400 ms window
+    pub fn default_profile() -> Self {
+        Self::new(TapDetector::default_profile(), 400)
+    }
+
+    pub fn set_double_ms(&mut self, double_ms: u32) {
+        self.pairing.set_double_ms(double_ms);
+    }
+
+    // Update with a new sample. A single tap is reported once the window for a second one
+    // has passed, a double tap as soon as the second tap ends.
+    pub fn update(&mut self, now_ms: u64, sample: &ImuSample) -> Option<TapCount> {
+        let event = self.taps.update(now_ms, sample);
+        match self.pairing.update(now_ms, event)? {
+            TapAction::Next => Some(TapCount::Single),
+            TapAction::Select => Some(TapCount::Double),
+            TapAction::Back => None,
+        }
+    }
+}
+
+// Step counter on the acceleration magnitude, so it works in any wrist orientation.
+// Band-pass: a fast EMA smooths sensor noise, a slow EMA tracks gravity/posture and is
+// subtracted. The fast one is kept light (1/2): at 20 Hz a 1/4 EMA already took more than
+// half of a 2 Hz stride away and normal walking stayed under the threshold. A step is the peak of a swing above `threshold_raw` (counted once the signal
+// falls back below half of it), at least `refractory_samples` after the previous step.
+// Counting in samples assumes `update` is fed at a steady rate (20 Hz for the defaults).
+pub struct StepCounter {
+    threshold: i32,
+    refractory_samples: u32,
+    fast: i32,
+    slow: i32,
+    primed: bool,
+    // In a swing above the threshold
+    in_peak: bool,
+    since_step: u32,
+    count: u32,
+}
+
+impl StepCounter {
+    pub const fn new(threshold_raw: i32, refractory_samples: u32) -> Self {
+        Self {
+            threshold: threshold_raw,
+            refractory_samples,
+            fast: 0,
+            slow: 0,
+            primed: false,
+            in_peak: false,
+            since_step: u32::MAX,
+            count: 0,
+        }
+    }
+
+    // Default profile at 20 Hz: ~0.12g swing (~1000 counts per g), >= 300 ms between steps
+    pub const fn default_profile() -> Self {
+        Self::new(120, 6)
+    }
+
+    // Feed one sample, return the running count
+    pub fn update(&mut self, sample: &ImuSample) -> u32 {
+        let mag = isqrt(sample.accel_mag_sq()) as i32;
+        if !self.primed {
+            self.fast = mag;
+            self.slow = mag;
+            self.primed = true;
+            return self.count;
+        }
+        self.fast += (mag - self.fast) / 2;
+        self.slow += (mag - self.slow) / 32;
+        let signal = self.fast - self.slow;
+        self.since_step = self.since_step.saturating_add(1);
+
+        if signal >= self.threshold {
+            self.in_peak = true;
+        } else if self.in_peak && signal < self.threshold / 2 {
+            self.in_peak = false;
+            if self.since_step >= self.refractory_samples {
+                self.count = self.count.saturating_add(1);
+                self.since_step = 0;
+            }
+        }
+        self.count
+    }
+
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+
+    // Start a new count (e.g. at midnight); the filters keep running
+    pub fn reset(&mut self) {
+        self.count = 0;
+    }
+}
+
+// Integer square root (Newton), for magnitudes without floats
+fn isqrt(v: i64) -> i64 {
+    if v < 2 {
+        return v.max(0);
+    }
+    let mut x = v;
+    let mut y = (x + 1) / 2;
+    while y < x {
+        x = y;
+        y = (x + v / x) / 2;
+    }
+    x
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Wrist at rest on 1 g with a +/-0.3 g bounce at `step_hz`, fed at the 20 Hz the
+    // defaults assume; returns the count after `secs`
+    fn walk(step_hz: f32, secs: u32) -> u32 {
+        let g = DEFAULT_ACCEL_COUNTS_PER_G as f32;
+        let mut counter = StepCounter::default_profile();
+        let mut sample = ImuSample::default();
+        for i in 0..secs * 20 {
+            let t = i as f32 / 20.0;
+            let bounce = 0.3 * g * (2.0 * core::f32::consts::PI * step_hz * t).sin();
+            sample.accel = [0, 0, (g + bounce) as i16];
+            counter.update(&sample);
+        }
+        counter.count()
+    }
+
+    #[test]
+    fn steps_counted_at_walking_cadence() {
+        // 2 steps/s for 30 s; allow a few short while the slow EMA settles
+        let count = walk(2.0, 30);
+        assert!((57..=60).contains(&count), "count {}", count);
+        let count = walk(1.5, 30);
+        assert!((42..=45).contains(&count), "count {}", count);
+    }
+
+    #[test]
+    fn resting_wrist_counts_nothing() {
+        let mut counter = StepCounter::default_profile();
+        let sample = ImuSample {
+            accel: [0, 0, DEFAULT_ACCEL_COUNTS_PER_G as i16],
+            ..ImuSample::default()
+        };
+        for _ in 0..600 {
+            counter.update(&sample);
+        }
+        assert_eq!(counter.count(), 0);
+    }
+}
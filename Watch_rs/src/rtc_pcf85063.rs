@@ -3,7 +3,7 @@
 
 use embedded_hal::i2c::I2c;
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub struct DateTime {
     pub year: u16,  // full year, e.g., 2024
     pub month: u8,  // 1-12
@@ -13,6 +13,50 @@ pub struct DateTime {
     pub second: u8, // 0-59
 }
 
+impl DateTime {
+    // Day of week, 0 = Sunday .. 6 = Saturday (Sakamoto's method), the same numbering as
+    // the RTC's weekday register
+    pub fn weekday(&self) -> u8 {
+        const T: [u16; 12] = [0, 3, 2, 5, 0, 3, 5, 1, 4, 6, 2, 4];
+        let m = self.month.clamp(1, 12);
+        let y = if m < 3 { self.year - 1 } else { self.year };
+        ((y + y / 4 - y / 100 + y / 400 + T[(m - 1) as usize] + self.day as u16) % 7) as u8
+    }
+}
+
+// Offset register (0x02) correction mode, bit 7
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum OffsetMode {
+    // Correction applied once every two hours, 4.34 ppm per LSB (lowest power)
+    Normal,
+    // Correction applied every four minutes, 4.069 ppm per LSB (finer, draws more current)
+    Fast,
+}
+
+impl OffsetMode {
+    // Correction per register LSB in ppm
+    pub fn ppm_per_lsb(self) -> f32 {
+        match self {
+            OffsetMode::Normal => 4.34,
+            OffsetMode::Fast => 4.069,
+        }
+    }
+}
+
+// CLKOUT frequency, Control_2 COF bits 2:0. The chip powers up at 32.768 kHz.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ClkoutFreq {
+    Hz32768 = 0b000,
+    Hz16384 = 0b001,
+    Hz8192 = 0b010,
+    Hz4096 = 0b011,
+    Hz2048 = 0b100,
+    Hz1024 = 0b101,
+    Hz1 = 0b110,
+    // Pin driven LOW
+    Off = 0b111,
+}
+
 pub struct Pcf85063<I2C> {
     i2c: I2C,
 }
@@ -30,22 +74,19 @@ where
     }
 
     // Read datetime. Returns (dt, vl_flag) where vl_flag == true means time is unreliable (power loss).
+    // The weekday register is not read back; `DateTime::weekday` derives it from the date.
     pub fn read_datetime(&mut self) -> Result<(DateTime, bool), E> {
         let mut buf = [0u8; 7];
         // Time registers start at 0x04: sec, min, hour, day, weekday, month, year
         self.i2c.write_read(0x51, &[0x04], &mut buf)?;
-        let vl = (buf[0] & 0x80) != 0;
+        let century = self.read_century()?;
+        let vl = (buf[0] & SECONDS_VL) != 0;
         let sec = bcd_decode(buf[0] & 0x7F);
         let min = bcd_decode(buf[1] & 0x7F);
         let hour = bcd_decode(buf[2] & 0x3F);
         let day = bcd_decode(buf[3] & 0x3F);
-        let month_raw = buf[5];
-        let month = bcd_decode(month_raw & 0x1F);
-        let year = if (month_raw & 0x80) != 0 {
-            1900u16 + bcd_decode(buf[6]) as u16
-        } else {
-            2000u16 + bcd_decode(buf[6]) as u16
-        };
+        let month = bcd_decode(buf[5] & 0x1F);
+        let year = century as u16 * 100 + bcd_decode(buf[6]) as u16;
         Ok((
             DateTime {
                 year,
@@ -59,7 +100,9 @@ where
         ))
     }
 
-    // Set datetime. Ignores weekday field.
+    // Set datetime; the weekday register is filled from the date. The chip only keeps a
+    // two-digit year, so the century goes in the RAM_byte register (see `read_century`).
+    // Leaves VL clear, so the next `read_datetime` reports the time as reliable.
     pub fn set_datetime(&mut self, dt: &DateTime) -> Result<(), E> {
         let yr = (dt.year % 100) as u8;
         let data = [
@@ -68,15 +111,190 @@ where
             bcd_encode(dt.minute),
             bcd_encode(dt.hour),
             bcd_encode(dt.day),
-            0, // weekday not used
+            dt.weekday(),
             bcd_encode(dt.month),
             bcd_encode(yr),
         ];
         self.i2c.write(0x51, &data)?;
-        Ok(())
+        let century = (dt.year / 100).saturating_sub(CENTURY_BASE).min(3) as u8;
+        let ram = self.read_ram_byte()?;
+        self.i2c
+            .write(0x51, &[REG_RAM_BYTE, (ram & !RAM_CENTURY_MASK) | century])?;
+        self.clear_power_loss()
+    }
+
+    // Clear VL (Seconds register bit 7). The chip sets VL when the oscillator stopped, e.g.
+    // the backup supply ran out, and only software clears it, so a set VL means "the time
+    // may be wrong until someone sets it". Writing the seconds register with VL = 0 clears
+    // it; no write is made when it is already clear. If the oscillator still isn't running,
+    // the chip sets VL again right away.
+    pub fn clear_power_loss(&mut self) -> Result<(), E> {
+        let mut buf = [0u8; 1];
+        self.i2c.write_read(0x51, &[0x04], &mut buf)?;
+        if buf[0] & SECONDS_VL == 0 {
+            return Ok(());
+        }
+        self.i2c.write(0x51, &[0x04, buf[0] & !SECONDS_VL])
+    }
+
+    // Century of the stored date. The PCF85063 has no century bit (the month register's
+    // upper bits are unused), so `set_datetime` keeps it in bits 1:0 of the free RAM_byte
+    // (0x03) as an offset from 20xx; a never-set byte reads as 0, i.e. 20xx.
+    // The chip's own leap-year rule is "year % 4 == 0", so across 2100 it inserts a
+    // 29 February that doesn't exist; re-set the time after that date.
+    fn read_century(&mut self) -> Result<u8, E> {
+        let ram = self.read_ram_byte()?;
+        Ok(CENTURY_BASE as u8 + (ram & RAM_CENTURY_MASK))
+    }
+
+    // User bits of the RAM_byte. The chip has a single byte of RAM, kept as long as the
+    // backup cell holds; its low two bits carry the century, so 6 bits (0..=63) are free
+    // for the application, returned here in bits 5:0. The byte is undefined after a full
+    // power loss (VL set), so validate what comes back.
+    pub fn read_ram(&mut self) -> Result<u8, E> {
+        Ok(self.read_ram_byte()? >> RAM_USER_SHIFT)
+    }
+
+    // Store `v` (0..=63, higher bits dropped) in the RAM_byte user bits, keeping the century
+    pub fn write_ram(&mut self, v: u8) -> Result<(), E> {
+        let ram = self.read_ram_byte()?;
+        let user = (v << RAM_USER_SHIFT) & !RAM_CENTURY_MASK;
+        self.i2c
+            .write(0x51, &[REG_RAM_BYTE, (ram & RAM_CENTURY_MASK) | user])
+    }
+
+    fn read_ram_byte(&mut self) -> Result<u8, E> {
+        let mut buf = [0u8; 1];
+        self.i2c.write_read(0x51, &[REG_RAM_BYTE], &mut buf)?;
+        Ok(buf[0])
+    }
+
+    // Daily alarm at hour:minute (24 h). Alarm registers 0x0B..0x0F are second, minute,
+    // hour, day, weekday, each with an enable bit 7 that is active LOW (0 = field compared).
+    // Only minute and hour are compared, so the alarm fires every day at the start of that
+    // minute. Also clears a pending AF and sets AIE (Control_2 bit 7) so INT goes low on a
+    // match, e.g. to wake the ESP32 from deep sleep.
+    pub fn set_alarm(&mut self, hour: u8, minute: u8) -> Result<(), E> {
+        let data = [
+            0x0B,
+            ALARM_DISABLE,              // second: ignored
+            bcd_encode(minute.min(59)), // minute: compared
+            bcd_encode(hour.min(23)),   // hour: compared (24 h mode)
+            ALARM_DISABLE,              // day: ignored
+            ALARM_DISABLE,              // weekday: ignored
+        ];
+        self.i2c.write(0x51, &data)?;
+        let ctrl2 = self.read_control2()?;
+        self.i2c
+            .write(0x51, &[0x01, (ctrl2 | CTRL2_AIE) & !CTRL2_AF])
+    }
+
+    // Disable every alarm field, the alarm interrupt and any pending AF
+    pub fn clear_alarm(&mut self) -> Result<(), E> {
+        let data = [
+            0x0B,
+            ALARM_DISABLE,
+            ALARM_DISABLE,
+            ALARM_DISABLE,
+            ALARM_DISABLE,
+            ALARM_DISABLE,
+        ];
+        self.i2c.write(0x51, &data)?;
+        let ctrl2 = self.read_control2()?;
+        self.i2c
+            .write(0x51, &[0x01, ctrl2 & !(CTRL2_AIE | CTRL2_AF)])
+    }
+
+    // AF (Control_2 bit 6): the alarm matched since it was last cleared. Stays set (and
+    // INT stays low) until `set_alarm` or `clear_alarm`.
+    pub fn alarm_triggered(&mut self) -> Result<bool, E> {
+        Ok(self.read_control2()? & CTRL2_AF != 0)
+    }
+
+    // One-shot countdown on the RTC's own timer: Timer_value (0x10) counts down at the
+    // clock picked in Timer_mode (0x11), then TF (Control_2 bit 3) is set and INT goes low.
+    // The counter is 8 bits: up to 255 s uses the 1 Hz source (1 s resolution); longer
+    // times switch to the 1/60 Hz source and round up to whole minutes (up to 255 min).
+    // The first period can be short by up to one source tick. 0 stops the timer.
+    pub fn set_timer(&mut self, seconds: u16) -> Result<(), E> {
+        // Stop and clear before reloading, per the datasheet
+        self.i2c.write(0x51, &[0x11, 0])?;
+        let ctrl2 = self.read_control2()?;
+        self.i2c.write(0x51, &[0x01, ctrl2 & !CTRL2_TF])?;
+        if seconds == 0 {
+            return Ok(());
+        }
+        let (value, source) = if seconds <= 255 {
+            (seconds as u8, TIMER_CLK_1HZ)
+        } else {
+            (seconds.div_ceil(60).min(255) as u8, TIMER_CLK_1_60HZ)
+        };
+        self.i2c.write(0x51, &[0x10, value])?;
+        self.i2c.write(0x51, &[0x11, source | TIMER_TE | TIMER_TIE])
+    }
+
+    // TF: the countdown reached zero (stays set until the next `set_timer`)
+    pub fn timer_expired(&mut self) -> Result<bool, E> {
+        Ok(self.read_control2()? & CTRL2_TF != 0)
+    }
+
+    // Trim crystal drift. `ppm` is the correction in the datasheet's sign convention:
+    // positive speeds the clock up (for a watch that loses time), negative slows it down.
+    // It is rounded to the nearest LSB (see `OffsetMode`) and clamped to the 7-bit two's
+    // complement range -64..=63, so about +/-275 ppm in normal mode and +/-260 ppm in fast
+    // mode. A watch gaining 2 s/day is 2 / 86400 = 23 ppm fast, i.e. `set_offset(Normal, -23.0)`.
+    pub fn set_offset(&mut self, mode: OffsetMode, ppm: f32) -> Result<(), E> {
+        let steps = libm::roundf(ppm / mode.ppm_per_lsb()).clamp(-64.0, 63.0) as i8;
+        let mode_bit = match mode {
+            OffsetMode::Normal => 0,
+            OffsetMode::Fast => OFFSET_MODE_FAST,
+        };
+        self.i2c
+            .write(0x51, &[REG_OFFSET, mode_bit | (steps as u8 & 0x7F)])
+    }
+
+    // Select the CLKOUT square wave. 1 Hz gives a tick on exact second boundaries. The
+    // 32.768 kHz default toggles the pin constantly, so pick `Off` when nothing listens
+    // to it to save power, especially on battery backup. Other Control_2 bits are kept.
+    pub fn set_clkout(&mut self, freq: ClkoutFreq) -> Result<(), E> {
+        let ctrl2 = self.read_control2()?;
+        self.i2c
+            .write(0x51, &[0x01, (ctrl2 & !CTRL2_COF_MASK) | freq as u8])
+    }
+
+    fn read_control2(&mut self) -> Result<u8, E> {
+        let mut buf = [0u8; 1];
+        self.i2c.write_read(0x51, &[0x01], &mut buf)?;
+        Ok(buf[0])
     }
 }
 
+// Offset register: bit 7 = MODE (1 = fast), bits 6:0 = signed correction
+const REG_OFFSET: u8 = 0x02;
+const OFFSET_MODE_FAST: u8 = 0x80;
+// Seconds register clock-integrity flag (VL / OS)
+const SECONDS_VL: u8 = 0x80;
+// Free user byte: bits 1:0 = century - 20 for `set_datetime` / `read_datetime`,
+// bits 7:2 = application data (`read_ram` / `write_ram`)
+const REG_RAM_BYTE: u8 = 0x03;
+const RAM_CENTURY_MASK: u8 = 0x03;
+const RAM_USER_SHIFT: u8 = 2;
+const CENTURY_BASE: u16 = 20;
+// Control_2 bits: alarm interrupt enable, alarm flag (cleared by writing 0)
+const CTRL2_AIE: u8 = 0x80;
+const CTRL2_AF: u8 = 0x40;
+// Control_2 timer flag (cleared by writing 0)
+const CTRL2_TF: u8 = 0x08;
+// Control_2 CLKOUT frequency field
+const CTRL2_COF_MASK: u8 = 0x07;
+// Timer_mode: clock source TCF (bits 4:3), timer enable, timer interrupt enable
+const TIMER_CLK_1HZ: u8 = 0x10;
+const TIMER_CLK_1_60HZ: u8 = 0x18;
+const TIMER_TE: u8 = 0x04;
+const TIMER_TIE: u8 = 0x02;
+// Alarm register enable bit: set = field not compared
+const ALARM_DISABLE: u8 = 0x80;
+
 // BCD encode/decode helpers
 fn bcd_decode(v: u8) -> u8 {
     (v & 0x0F) + ((v >> 4) * 10)
@@ -87,7 +305,7 @@ fn bcd_encode(v: u8) -> u8 {
     ((v / 10) << 4) | (v % 10)
 }
 
-// Days since 1970-01-01 for UTC conversion (simple, handles leap years through 2099).
+// Days since 1970-01-01 for UTC conversion (full Gregorian rules, e.g. 2100 is not a leap year).
 fn days_since_unix(year: u16, month: u8, day: u8) -> u32 {
     let y = year as i32;
     let m = month as i32;
@@ -100,20 +318,25 @@ fn days_since_unix(year: u16, month: u8, day: u8) -> u32 {
     (era * 146097 + doe - 719468) as u32 // 719468 = days from 0000-03-01 to 1970-01-01
 }
 
-// Convert DateTime to Unix timestamp (seconds since 1970-01-01).
+// Convert DateTime to Unix timestamp (seconds since 1970-01-01). Saturates at u32::MAX
+// (2106-02-07 06:28:15), the range of the software clock, although the RTC itself runs
+// to 2199; dates past it need `datetime_to_unix64`.
 pub fn datetime_to_unix(dt: &DateTime) -> u32 {
+    datetime_to_unix64(dt).min(u32::MAX as u64) as u32
+}
+
+// Convert DateTime to a 64-bit Unix timestamp, exact for any year from 1970
+pub fn datetime_to_unix64(dt: &DateTime) -> u64 {
     let days = days_since_unix(dt.year, dt.month, dt.day) as u64;
-    let secs = days
-        .saturating_mul(86_400) // 86400 seconds in a day
+    days.saturating_mul(86_400) // 86400 seconds in a day
         .saturating_add((dt.hour as u64) * 3600) // 3600 seconds in an hour
         .saturating_add((dt.minute as u64) * 60) // 60 seconds in a minute
-        .saturating_add(dt.second as u64); // add seconds
-    secs.min(u32::MAX as u64) as u32
+        .saturating_add(dt.second as u64) // add seconds
 }
 
 // Basic sanity check on decoded RTC time.
 pub fn datetime_is_valid(dt: &DateTime) -> bool {
-    (2020..=2099).contains(&dt.year)
+    (2020..=2199).contains(&dt.year)
         && (1..=12).contains(&dt.month)
         && (1..=31).contains(&dt.day)
         && dt.hour < 24
@@ -121,8 +344,24 @@ pub fn datetime_is_valid(dt: &DateTime) -> bool {
         && dt.second < 60
 }
 
-// Convert Unix timestamp (seconds since 1970-01-01) to DateTime.
-pub fn unix_to_datetime(mut ts: u32) -> DateTime {
+// Number of days in `month` (1-12) of `year`, Gregorian leap years
+pub fn days_in_month(year: u16, month: u8) -> u8 {
+    match month {
+        2 if (year % 4 == 0 && year % 100 != 0) || year % 400 == 0 => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+// Convert Unix timestamp (seconds since 1970-01-01) to DateTime. A u32 only reaches
+// 2106-02-07 06:28:15; see `unix64_to_datetime` for later dates.
+pub fn unix_to_datetime(ts: u32) -> DateTime {
+    unix64_to_datetime(ts as u64)
+}
+
+// Convert a 64-bit Unix timestamp to DateTime (exact through year 65535)
+pub fn unix64_to_datetime(mut ts: u64) -> DateTime {
     let days = ts / 86400;
     ts %= 86400;
     let hour = (ts / 3600) as u8;
@@ -130,7 +369,7 @@ pub fn unix_to_datetime(mut ts: u32) -> DateTime {
     let minute = (ts / 60) as u8;
     let second = (ts % 60) as u8;
 
-    // Convert days since 1970-01-01 back to date (valid until 2099).
+    // Convert days since 1970-01-01 back to date (proleptic Gregorian, era-based).
     let z = days as i32 + 719468; // 719468 = days from 0000-03-01 to 1970-01-01
     let era = (z >= 0)
         .then(|| z / 146097) // 146097 = days in 400 years
@@ -153,3 +392,133 @@ pub fn unix_to_datetime(mut ts: u32) -> DateTime {
         second,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_hal::i2c::{ErrorType, Operation};
+
+    // Register file standing in for the chip: a write sets the register pointer from its
+    // first byte, and reads and writes auto-increment it
+    struct FakeRtc {
+        regs: [u8; 0x12],
+        ptr: usize,
+    }
+
+    impl ErrorType for FakeRtc {
+        type Error = core::convert::Infallible;
+    }
+
+    impl I2c for FakeRtc {
+        fn transaction(
+            &mut self,
+            _address: u8,
+            operations: &mut [Operation<'_>],
+        ) -> Result<(), Self::Error> {
+            for op in operations {
+                match op {
+                    Operation::Write(bytes) => {
+                        if let Some((&reg, data)) = bytes.split_first() {
+                            self.ptr = reg as usize;
+                            for &b in data {
+                                self.regs[self.ptr] = b;
+                                self.ptr += 1;
+                            }
+                        }
+                    }
+                    Operation::Read(buf) => {
+                        for b in buf.iter_mut() {
+                            *b = self.regs[self.ptr];
+                            self.ptr += 1;
+                        }
+                    }
+                }
+            }
+            Ok(())
+        }
+    }
+
+    fn round_trip(rtc: &mut Pcf85063<FakeRtc>, dt: DateTime) {
+        rtc.set_datetime(&dt).unwrap();
+        let (back, vl) = rtc.read_datetime().unwrap();
+        assert!(!vl);
+        assert_eq!(back, dt);
+    }
+
+    fn date(year: u16, month: u8, day: u8, hour: u8, minute: u8, second: u8) -> DateTime {
+        DateTime {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+        }
+    }
+
+    #[test]
+    fn century_survives_in_ram_byte() {
+        let mut rtc = Pcf85063::new(FakeRtc {
+            regs: [0; 0x12],
+            ptr: 0,
+        });
+        rtc.write_ram(0x2A).unwrap();
+
+        round_trip(&mut rtc, date(2096, 2, 29, 12, 34, 56));
+        // Across the century rollover, both ways
+        round_trip(&mut rtc, date(2099, 12, 31, 23, 59, 59));
+        round_trip(&mut rtc, date(2100, 1, 1, 0, 0, 0));
+        round_trip(&mut rtc, date(2199, 12, 31, 23, 59, 59));
+        round_trip(&mut rtc, date(2024, 6, 1, 8, 0, 0));
+
+        // The application's RAM bits are left alone
+        assert_eq!(rtc.read_ram().unwrap(), 0x2A);
+    }
+
+    #[test]
+    fn unix64_round_trip_keeps_every_field() {
+        for dt in [
+            date(1970, 1, 1, 0, 0, 0),
+            date(2096, 2, 29, 12, 34, 56),
+            date(2099, 12, 31, 23, 59, 59),
+            date(2100, 1, 1, 0, 0, 0),
+            // 2100 is not a leap year: Feb 28 runs straight into Mar 1
+            date(2100, 2, 28, 23, 59, 59),
+            date(2100, 3, 1, 0, 0, 0),
+            date(2199, 12, 31, 23, 59, 59),
+        ] {
+            assert_eq!(unix64_to_datetime(datetime_to_unix64(&dt)), dt);
+        }
+        assert_eq!(
+            datetime_to_unix64(&date(2100, 3, 1, 0, 0, 0))
+                - datetime_to_unix64(&date(2100, 2, 28, 23, 59, 59)),
+            1
+        );
+    }
+
+    #[test]
+    fn u32_pair_ends_in_2106() {
+        assert_eq!(unix_to_datetime(u32::MAX), date(2106, 2, 7, 6, 28, 15));
+        assert_eq!(datetime_to_unix(&date(2106, 2, 7, 6, 28, 15)), u32::MAX);
+        assert_eq!(datetime_to_unix(&date(2199, 12, 31, 0, 0, 0)), u32::MAX);
+    }
+
+    #[test]
+    fn weekday_of_known_dates() {
+        assert_eq!(date(2024, 1, 1, 0, 0, 0).weekday(), 1); // Monday
+        assert_eq!(date(1970, 1, 1, 0, 0, 0).weekday(), 4); // Thursday
+        assert_eq!(date(2000, 1, 1, 0, 0, 0).weekday(), 6); // Saturday
+        assert_eq!(date(2096, 2, 29, 0, 0, 0).weekday(), 3); // Wednesday
+        assert_eq!(date(2100, 3, 1, 0, 0, 0).weekday(), 1); // Monday, 2100 not a leap year
+    }
+
+    #[test]
+    fn weekday_register_written_from_date() {
+        let mut rtc = Pcf85063::new(FakeRtc {
+            regs: [0; 0x12],
+            ptr: 0,
+        });
+        rtc.set_datetime(&date(2024, 1, 1, 9, 30, 0)).unwrap();
+        assert_eq!(rtc.into_inner().regs[0x08], 1);
+    }
+}
@@ -0,0 +1,111 @@
+//! Cooperative per-iteration time budget for the main loop.
+//!
+//! This module provides:
+//! - `monotonic_us` for microsecond timestamps from the SystemTimer
+//! - `LoopBudget`, which decides whether an expensive task (IMU read, redraw) still fits in
+//!   this loop iteration or should wait for the next one
+//!
+//! Costs are learned from the tasks' own run times, so no tuning per screen is needed. A task
+//! is never starved: after `max_defer` deferrals in a row it runs regardless of the budget.
+//! A task that costs more than the whole budget (a full-frame redraw) can never fit, so it
+//! runs whenever it is the first one admitted in an iteration.
+
+use esp_hal::timer::systimer::{SystemTimer, Unit};
+
+// Microseconds since boot (SystemTimer unit 0). Widened to u128 for the multiply, a u64
+// would overflow after about 13 days of ticks at 16 MHz.
+pub fn monotonic_us() -> u64 {
+    let t = SystemTimer::unit_value(Unit::Unit0) as u128;
+    (t * 1_000_000 / SystemTimer::ticks_per_second() as u128) as u64
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LoopTask {
+    Imu,
+    Redraw,
+}
+
+impl LoopTask {
+    fn index(self) -> usize {
+        match self {
+            LoopTask::Imu => 0,
+            LoopTask::Redraw => 1,
+        }
+    }
+}
+
+pub struct LoopBudget {
+    budget_us: u32,
+    max_defer: u8,
+    started_us: u64,
+    // Something was already admitted this iteration
+    busy: bool,
+    // Running estimate of each task's cost, and its deferrals in a row
+    cost_us: [u32; 2],
+    deferred: [u8; 2],
+}
+
+impl LoopBudget {
+    pub const fn new(budget_us: u32, max_defer: u8) -> Self {
+        Self {
+            budget_us,
+            max_defer,
+            started_us: 0,
+            busy: false,
+            cost_us: [0; 2],
+            deferred: [0; 2],
+        }
+    }
+
+    // Start of a loop iteration
+    pub fn begin(&mut self) {
+        self.started_us = monotonic_us();
+        self.busy = false;
+    }
+
+    pub fn remaining_us(&self) -> u32 {
+        let used = monotonic_us().saturating_sub(self.started_us);
+        (self.budget_us as u64).saturating_sub(used) as u32
+    }
+
+    // Whether `task` should run now: its estimated cost fits in what is left of this
+    // iteration, it is over the whole budget and nothing ran yet this iteration, or it has
+    // already been put off `max_defer` times. Counts a deferral on no.
+    pub fn admit(&mut self, task: LoopTask) -> bool {
+        let i = task.index();
+        let cost = self.cost_us[i];
+        if cost <= self.remaining_us()
+            || (cost > self.budget_us && !self.busy)
+            || self.deferred[i] >= self.max_defer
+        {
+            self.deferred[i] = 0;
+            self.busy = true;
+            true
+        } else {
+            self.deferred[i] += 1;
+            false
+        }
+    }
+
+    // Report how long an admitted task took (3/4 old estimate + 1/4 new sample)
+    pub fn record(&mut self, task: LoopTask, took_us: u64) {
+        let took = took_us.min(u32::MAX as u64) as u32;
+        let cost = &mut self.cost_us[task.index()];
+        *cost = if *cost == 0 {
+            took
+        } else {
+            ((*cost as u64 * 3 + took as u64) / 4) as u32
+        };
+    }
+
+    // Admit, run and time `f`; None when the task was deferred
+    pub fn run<R>(&mut self, task: LoopTask, f: impl FnOnce() -> R) -> Option<R> {
+        if !self.admit(task) {
+            return None;
+        }
+        let t0 = monotonic_us();
+        let r = f();
+        self.record(task, monotonic_us().saturating_sub(t0));
+        Some(r)
+    }
+}
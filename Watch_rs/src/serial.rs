@@ -0,0 +1,196 @@
+// Tiny line-based command reader on the USB-Serial-JTAG port.
+//
+// Commands (one per line, case-insensitive, \n or \r\n terminated):
+//   SET <unix_seconds>   set the software clock
+//   GET                  print the current clock as unix seconds
+//   PAGE <name>          jump to a page: home, omnitrix, watch, digital, temp, light,
+//                        settings
+//   STREAM ON|OFF        stream raw IMU samples as CSV (t_ms,ax,ay,az,gx,gy,gz)
+//
+// `poll` only drains what is already in the RX FIFO, so it never blocks the UI loop.
+// Partial lines are kept until their newline arrives; overlong or malformed lines are dropped.
+
+use core::fmt::Write;
+use esp_hal::{
+    peripherals::USB_DEVICE,
+    usb_serial_jtag::{UsbSerialJtag, UsbSerialJtagRx, UsbSerialJtagTx},
+    Blocking,
+};
+use heapless::{String, Vec};
+
+use crate::qmi8658_imu::ImuSample;
+use crate::ui::{MainMenuState, OmnitrixState, Page, SettingsMenuState, WatchAppState};
+
+// Longest accepted line (without the newline); "SET 4294967295" fits comfortably
+const LINE_MAX: usize = 32;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SerialCommand {
+    Set(u32),
+    Get,
+    Page(Page),
+    Stream(bool),
+}
+
+pub struct SerialConsole<'d> {
+    rx: UsbSerialJtagRx<'d, Blocking>,
+    _tx: UsbSerialJtagTx<'d, Blocking>, // kept so the port stays configured; output goes through esp_println
+    line: Vec<u8, LINE_MAX>,
+    overflow: bool, // current line got too long, drop it at the next newline
+}
+
+impl<'d> SerialConsole<'d> {
+    pub fn new(usb: USB_DEVICE<'d>) -> Self {
+        let (rx, tx) = UsbSerialJtag::new(usb).split();
+        Self {
+            rx,
+            _tx: tx,
+            line: Vec::new(),
+            overflow: false,
+        }
+    }
+
+    // Drain the RX FIFO and return the first complete, valid command (if any).
+    // Bytes after that command stay buffered in the FIFO for the next poll.
+    pub fn poll(&mut self) -> Option<SerialCommand> {
+        let mut byte = [0u8];
+        while self.rx.drain_rx_fifo(&mut byte) == 1 {
+            match byte[0] {
+                b'\r' => {}
+                b'\n' => {
+                    let cmd = if self.overflow {
+                        None
+                    } else {
+                        parse_line(&self.line)
+                    };
+                    if cmd.is_none() && !self.line.is_empty() {
+                        esp_println::println!(
+                            "ERR expected `SET <unix_seconds>`, `GET`, `PAGE <name>` or `STREAM ON|OFF`"
+                        );
+                    }
+                    self.line.clear();
+                    self.overflow = false;
+                    if cmd.is_some() {
+                        return cmd;
+                    }
+                }
+                b => {
+                    if self.line.push(b).is_err() {
+                        self.overflow = true;
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+// Parse one line (without newline) into a command
+pub fn parse_line(line: &[u8]) -> Option<SerialCommand> {
+    let text = core::str::from_utf8(line).ok()?.trim();
+    let mut parts = text.split_ascii_whitespace();
+    let word = parts.next()?;
+    let cmd = if word.eq_ignore_ascii_case("SET") {
+        SerialCommand::Set(parts.next()?.parse().ok()?)
+    } else if word.eq_ignore_ascii_case("GET") {
+        SerialCommand::Get
+    } else if word.eq_ignore_ascii_case("PAGE") {
+        SerialCommand::Page(page_by_name(parts.next()?)?)
+    } else if word.eq_ignore_ascii_case("STREAM") {
+        let arg = parts.next()?;
+        if arg.eq_ignore_ascii_case("ON") {
+            SerialCommand::Stream(true)
+        } else if arg.eq_ignore_ascii_case("OFF") {
+            SerialCommand::Stream(false)
+        } else {
+            return None;
+        }
+    } else {
+        return None;
+    };
+    // trailing junk makes the whole line invalid
+    if parts.next().is_some() {
+        return None;
+    }
+    Some(cmd)
+}
+
+// Page names accepted by `PAGE`
+fn page_by_name(name: &str) -> Option<Page> {
+    const PAGES: [(&str, Page); 9] = [
+        ("home", Page::Main(MainMenuState::Home)),
+        ("omnitrix", Page::Omnitrix(OmnitrixState::Alien1)),
+        ("watch", Page::Watch(WatchAppState::Analog)),
+        ("analog", Page::Watch(WatchAppState::Analog)),
+        ("digital", Page::Watch(WatchAppState::Digital)),
+        ("temp", Page::Temperature),
+        ("light", Page::Flashlight),
+        ("launcher", Page::Launcher(0)),
+        (
+            "settings",
+            Page::Settings(SettingsMenuState::BrightnessPrompt),
+        ),
+    ];
+    PAGES
+        .iter()
+        .find(|(n, _)| n.eq_ignore_ascii_case(name))
+        .map(|(_, p)| *p)
+}
+
+// Raw IMU capture for offline detector tuning. Each sample becomes one CSV line, formatted
+// on the stack and printed in a single call. Lines are rate limited to `period_ms`, and
+// samples arriving faster are skipped rather than queued.
+//
+// Printing blocks: esp_println feeds the USB-Serial-JTAG TX FIFO (64 bytes) and waits for
+// the host to drain it, so each line stalls the main loop for as long as that takes. With
+// a host reading that is well under a millisecond per line; a host that stops reading holds
+// the loop up to esp_println's own give-up timeout. The rate limit bounds this to one line
+// per `period_ms`. It's a bench tool, leave it off in normal use.
+pub struct ImuStream {
+    enabled: bool,
+    period_ms: u64,
+    next_ms: u64,
+    skipped: u32,
+}
+
+impl ImuStream {
+    pub const fn new(period_ms: u64) -> Self {
+        Self {
+            enabled: false,
+            period_ms,
+            next_ms: 0,
+            skipped: 0,
+        }
+    }
+
+    // Turning it on prints the CSV header; turning it off reports how many samples the
+    // rate limit dropped.
+    pub fn set_enabled(&mut self, on: bool) {
+        if on && !self.enabled {
+            esp_println::println!("t_ms,ax,ay,az,gx,gy,gz");
+            self.next_ms = 0;
+            self.skipped = 0;
+        } else if !on && self.enabled {
+            esp_println::println!("# stream off, {} samples skipped", self.skipped);
+        }
+        self.enabled = on;
+    }
+
+    pub fn push(&mut self, now_ms: u64, sample: &ImuSample) {
+        if !self.enabled {
+            return;
+        }
+        if now_ms < self.next_ms {
+            self.skipped = self.skipped.saturating_add(1);
+            return;
+        }
+        self.next_ms = now_ms.saturating_add(self.period_ms);
+        // 20 digits of time + 6 fields of up to 6 chars + separators fits in 64
+        let mut line: String<64> = String::new();
+        let [ax, ay, az] = sample.accel;
+        let [gx, gy, gz] = sample.gyro;
+        if write!(line, "{},{},{},{},{},{},{}", now_ms, ax, ay, az, gx, gy, gz).is_ok() {
+            esp_println::println!("{}", line);
+        }
+    }
+}
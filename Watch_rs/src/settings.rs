@@ -0,0 +1,196 @@
+//! User preferences that survive deep sleep.
+//!
+//! Deep sleep resets the SoC, so every `ui` setting would otherwise come back at its default
+//! on wake. This module provides:
+//! - `save_settings`, which packs the preferences into a small record in RTC fast memory
+//!   (kept powered through deep sleep)
+//! - `load_settings`, which puts them back at boot
+//!
+//! Record layout, version 2:
+//! 0 version, 1 brightness %, 2 flags (`FLAG_*`), 3 date style, 4 temperature unit,
+//! 5 carousel seconds, 6 screen timeout seconds, 7 wake sources (`WakeSources` bits),
+//! 8 battery warn %, 9 battery critical %, 10 brightness min %, 11 brightness max %,
+//! 12 tap cadence, 13..=14 wake alarm minute of day (little endian, `ALARM_OFF` = none),
+//! 15 checksum
+//!
+//! After a power-on the memory holds garbage, which the checksum rejects. A firmware that
+//! changes the layout bumps `SETTINGS_VERSION`, so an older record is ignored (defaults)
+//! instead of being misread.
+
+use crate::ui::{
+    always_on_enabled, always_on_set, animations_enabled, animations_set, auto_rotate_enabled,
+    auto_rotate_set, battery_set_thresholds, battery_thresholds, brightness_limits, brightness_pct,
+    brightness_set_limits, brightness_set_pct, carousel_secs, carousel_set_secs, clock_12h,
+    clock_12h_set, clock_seconds_enabled, clock_seconds_set, colon_blink_enabled, colon_blink_set,
+    date_style, date_style_set, encoder_direction, encoder_direction_set, screen_timeout_secs,
+    screen_timeout_set_secs, tap_cadence, tap_cadence_set, temp_unit, temp_unit_set, wake_alarm_at,
+    wake_alarm_set, wake_sources, wake_sources_set, wrist, wrist_set, DateStyle, EncoderDirection,
+    TapCadence, TempUnit, WakeSources, Wrist,
+};
+
+const SETTINGS_VERSION: u8 = 2;
+const SETTINGS_LEN: usize = 16;
+// Mixed into the checksum so an all-zero record doesn't pass
+const CHECKSUM_SEED: u8 = 0xA5;
+
+// Flags byte
+const FLAG_CLOCK_12H: u8 = 1 << 0;
+const FLAG_CLOCK_SECONDS: u8 = 1 << 1;
+const FLAG_COLON_BLINK: u8 = 1 << 2;
+const FLAG_ALWAYS_ON: u8 = 1 << 3;
+const FLAG_ANIMATIONS: u8 = 1 << 4;
+const FLAG_AUTO_ROTATE: u8 = 1 << 5;
+const FLAG_WRIST_RIGHT: u8 = 1 << 6;
+const FLAG_ENCODER_REVERSED: u8 = 1 << 7;
+
+// Wake alarm minute of day meaning "no alarm"
+const ALARM_OFF: u16 = 0xFFFF;
+
+// Not initialized on any reset, so it keeps the last record across deep sleep
+#[esp_hal::ram(unstable(rtc_fast, persistent))]
+static mut SETTINGS_RTC: [u8; SETTINGS_LEN] = [0; SETTINGS_LEN];
+
+fn checksum(data: &[u8]) -> u8 {
+    data.iter()
+        .fold(CHECKSUM_SEED, |acc, b| acc.rotate_left(1) ^ b)
+}
+
+// Current preferences as a record
+pub fn encode_settings() -> [u8; SETTINGS_LEN] {
+    let mut flags = 0;
+    for (on, bit) in [
+        (clock_12h(), FLAG_CLOCK_12H),
+        (clock_seconds_enabled(), FLAG_CLOCK_SECONDS),
+        (colon_blink_enabled(), FLAG_COLON_BLINK),
+        (always_on_enabled(), FLAG_ALWAYS_ON),
+        (animations_enabled(), FLAG_ANIMATIONS),
+        (auto_rotate_enabled(), FLAG_AUTO_ROTATE),
+        (wrist() == Wrist::Right, FLAG_WRIST_RIGHT),
+        (
+            encoder_direction() == EncoderDirection::Reversed,
+            FLAG_ENCODER_REVERSED,
+        ),
+    ] {
+        if on {
+            flags |= bit;
+        }
+    }
+    let (warn, critical) = battery_thresholds();
+    let (min, max) = brightness_limits();
+    let alarm = match wake_alarm_at() {
+        Some(at) => (at / 60) as u16,
+        None => ALARM_OFF,
+    }
+    .to_le_bytes();
+    let mut rec = [
+        SETTINGS_VERSION,
+        brightness_pct(),
+        flags,
+        match date_style() {
+            DateStyle::Iso => 0,
+            DateStyle::Us => 1,
+            DateStyle::Eu => 2,
+        },
+        match temp_unit() {
+            TempUnit::Celsius => 0,
+            TempUnit::Fahrenheit => 1,
+        },
+        carousel_secs(),
+        screen_timeout_secs(),
+        wake_sources().bits(),
+        warn,
+        critical,
+        min,
+        max,
+        match tap_cadence() {
+            TapCadence::Off => 0,
+            TapCadence::Fast => 1,
+            TapCadence::Normal => 2,
+            TapCadence::Slow => 3,
+        },
+        alarm[0],
+        alarm[1],
+        0,
+    ];
+    rec[SETTINGS_LEN - 1] = checksum(&rec[..SETTINGS_LEN - 1]);
+    rec
+}
+
+// Apply a record to the ui settings. Returns false (changing nothing) when it is corrupt,
+// from another layout version, or holds out-of-range values.
+pub fn decode_settings(rec: &[u8; SETTINGS_LEN]) -> bool {
+    if rec[0] != SETTINGS_VERSION || rec[SETTINGS_LEN - 1] != checksum(&rec[..SETTINGS_LEN - 1]) {
+        return false;
+    }
+    let style = match rec[3] {
+        0 => DateStyle::Iso,
+        1 => DateStyle::Us,
+        2 => DateStyle::Eu,
+        _ => return false,
+    };
+    let unit = match rec[4] {
+        0 => TempUnit::Celsius,
+        1 => TempUnit::Fahrenheit,
+        _ => return false,
+    };
+    let cadence = match rec[12] {
+        0 => TapCadence::Off,
+        1 => TapCadence::Fast,
+        2 => TapCadence::Normal,
+        3 => TapCadence::Slow,
+        _ => return false,
+    };
+    let alarm = match u16::from_le_bytes([rec[13], rec[14]]) {
+        ALARM_OFF => None,
+        m if m < 24 * 60 => Some(m as u32 * 60),
+        _ => return false,
+    };
+    let flags = rec[2];
+    let on = |bit: u8| flags & bit != 0;
+
+    // Limits first, brightness_set_pct clamps to them
+    brightness_set_limits(rec[10], rec[11]);
+    brightness_set_pct(rec[1].min(100) as i32);
+    clock_12h_set(on(FLAG_CLOCK_12H));
+    clock_seconds_set(on(FLAG_CLOCK_SECONDS));
+    colon_blink_set(on(FLAG_COLON_BLINK));
+    always_on_set(on(FLAG_ALWAYS_ON));
+    animations_set(on(FLAG_ANIMATIONS));
+    auto_rotate_set(on(FLAG_AUTO_ROTATE));
+    // wrist_set also picks the encoder direction, so the saved direction goes after it
+    wrist_set(if on(FLAG_WRIST_RIGHT) {
+        Wrist::Right
+    } else {
+        Wrist::Left
+    });
+    encoder_direction_set(if on(FLAG_ENCODER_REVERSED) {
+        EncoderDirection::Reversed
+    } else {
+        EncoderDirection::Normal
+    });
+    date_style_set(style);
+    temp_unit_set(unit);
+    carousel_set_secs(rec[5]);
+    screen_timeout_set_secs(rec[6]);
+    wake_sources_set(WakeSources::from_bits(rec[7]));
+    battery_set_thresholds(rec[8], rec[9]);
+    tap_cadence_set(cadence);
+    wake_alarm_set(alarm);
+    true
+}
+
+// Store the current preferences in RTC memory
+pub fn save_settings() {
+    let rec = encode_settings();
+    critical_section::with(|_| unsafe {
+        core::ptr::addr_of_mut!(SETTINGS_RTC).write_volatile(rec);
+    });
+}
+
+// Restore the preferences saved before the last deep sleep. Returns false (defaults kept)
+// when there is no valid record, e.g. after a power-on.
+pub fn load_settings() -> bool {
+    let rec =
+        critical_section::with(|_| unsafe { core::ptr::addr_of!(SETTINGS_RTC).read_volatile() });
+    decode_settings(&rec)
+}
@@ -10,7 +10,8 @@
 
 extern crate alloc;
 use alloc::vec::Vec;
-use core::cell::RefCell;
+use core::cell::{Cell, RefCell};
+use core::sync::atomic::{fence, AtomicU32, Ordering};
 use critical_section::Mutex;
 
 use esp_backtrace as _;
@@ -18,23 +19,36 @@ use esp_backtrace as _;
 // Embedded-graphics, a ton are unused but this is a work in progress
 use embedded_graphics::{
     draw_target::DrawTarget,
-    image::{Image, ImageRawBE},
-    mono_font::{ascii::FONT_10X20, MonoFont, MonoTextStyleBuilder},
-    pixelcolor::Rgb565,
+    geometry::Dimensions,
+    image::{Image, ImageDrawableExt, ImageRawBE},
+    mono_font::{
+        ascii::{FONT_10X20, FONT_6X10},
+        iso_8859_1::FONT_10X20 as FONT_10X20_LATIN1,
+        MonoFont, MonoTextStyle, MonoTextStyleBuilder,
+    },
+    pixelcolor::{IntoStorage, Rgb565},
     prelude::{OriginDimensions, Point, Primitive, RgbColor, Size},
     primitives::{Line, PrimitiveStyle, Rectangle},
-    text::{Alignment, Text},
+    text::{Alignment, Baseline, Text},
     Drawable,
 };
 use esp_hal::timer::systimer::{SystemTimer, Unit};
-use libm::{atan2f, cosf, sinf};
+use libm::{atan2f, cosf, fabsf, floorf, roundf, sinf};
 
-use core::any::Any;
-use miniz_oxide::inflate::decompress_to_vec_zlib_with_limit;
+use crate::display::FastPanel;
+#[cfg(feature = "esp32s3-disp143Oled")]
+use crate::rtc_pcf85063::{datetime_to_unix, days_in_month, unix_to_datetime, DateTime};
+use miniz_oxide::inflate::core::{decompress, inflate_flags, DecompressorOxide};
+use miniz_oxide::inflate::TINFLStatus;
 
 // Make a lightweight trait bound we’ll use for the factory’s return type.
-pub trait PanelRgb565: DrawTarget<Color = Rgb565> + OriginDimensions + Any {}
-impl<T> PanelRgb565 for T where T: DrawTarget<Color = Rgb565> + OriginDimensions + Any {}
+// Each display backend implements it; `fast_panel` hands out the framebuffer fast paths
+// when the backend has them (None = embedded-graphics only).
+pub trait PanelRgb565: DrawTarget<Color = Rgb565> + OriginDimensions {
+    fn fast_panel(&mut self) -> Option<&mut dyn FastPanel> {
+        None
+    }
+}
 
 // Display configuration, (0,0) is top-left corner
 
@@ -66,6 +80,7 @@ pub enum AssetId {
     InfoPage,
     SettingsImage,
     WatchIcon,
+    StatusIcons,
 }
 
 #[derive(Copy, Clone)]
@@ -76,7 +91,7 @@ struct AssetSlot {
 }
 
 // Number of asset slots
-const ASSET_MAX: usize = 14;
+const ASSET_MAX: usize = 15;
 
 macro_rules! res {
     () => {
@@ -116,6 +131,8 @@ static INFO_PAGE_IMAGE: &[u8] =
 static SETTINGS_IMAGE: &[u8] = include_bytes!("assets/settings_image_400x344_rgb565_be.raw.zlib");
 static WATCH_ICON_IMAGE: &[u8] = include_bytes!("assets/watch_icon_316x316_rgb565_be.raw.zlib");
 static WATCH_BG_IMAGE: &[u8] = include_bytes!("assets/watch_background_466x466_rgb565_be.raw.zlib");
+// Sprite atlas of small status glyphs, generated by assets/status_icons.py
+static STATUS_ICONS_IMAGE: &[u8] = include_bytes!("assets/status_icons_96x48_rgb565_be.raw.zlib");
 
 // Generic asset cache
 static ASSETS: Mutex<RefCell<[AssetSlot; ASSET_MAX]>> = Mutex::new(RefCell::new(
@@ -134,11 +151,18 @@ enum PageKind {
     Omnitrix,
     EasterEgg,
     Watch,
+    Temperature,
+    Flashlight,
+    SmashCalibration,
+    Service,
+    Launcher,
 }
 static LAST_PAGE_KIND: Mutex<RefCell<Option<PageKind>>> = Mutex::new(RefCell::new(None));
 
 // Omnitrix transform active tracker
 static LAST_OMNI_TRANSFORM_ACTIVE: Mutex<RefCell<bool>> = Mutex::new(RefCell::new(false));
+static TRANSFORM_ANIM: Mutex<RefCell<AnimTimer>> = Mutex::new(RefCell::new(AnimTimer::new()));
+static TRANSFORM_PHASE: Mutex<RefCell<f32>> = Mutex::new(RefCell::new(0.0));
 
 // Navigation history management
 static NAV_HISTORY: Mutex<RefCell<Vec<Page>>> = Mutex::new(RefCell::new(Vec::new()));
@@ -149,12 +173,62 @@ static HAND_CACHE: Mutex<RefCell<HandCache>> = Mutex::new(RefCell::new(HandCache
 static WATCH_BG: Mutex<RefCell<Option<alloc::vec::Vec<u8>>>> = Mutex::new(RefCell::new(None));
 static WATCH_FACE_DIRTY: Mutex<RefCell<bool>> = Mutex::new(RefCell::new(false));
 static LAST_TRANSFORM_ACTIVE: Mutex<RefCell<bool>> = Mutex::new(RefCell::new(false));
+// Drop warning drawn; closing it repaints the page underneath from scratch
+static LAST_DROP_ACTIVE: Mutex<RefCell<bool>> = Mutex::new(RefCell::new(false));
+// FB snapshot of the helix region taken when the transform starts (alien behind the overlay)
+static TRANSFORM_BG: Mutex<RefCell<Option<alloc::vec::Vec<u16>>>> = Mutex::new(RefCell::new(None));
 static BRIGHTNESS_PCT: Mutex<RefCell<u8>> = Mutex::new(RefCell::new(100));
 static BRIGHTNESS_EDIT: Mutex<RefCell<bool>> = Mutex::new(RefCell::new(false));
-static BRIGHTNESS_LAST: Mutex<RefCell<Option<u8>>> = Mutex::new(RefCell::new(None));
+// User floor/ceiling for brightness (min, max) in percent, e.g. a floor so the panel never goes dark
+static BRIGHTNESS_LIMITS: Mutex<RefCell<(u8, u8)>> = Mutex::new(RefCell::new((0, 100)));
+// Last drawn brightness ring: (percent, end angle the fg arc was actually painted to)
+static BRIGHTNESS_LAST: Mutex<RefCell<Option<(u8, f32)>>> = Mutex::new(RefCell::new(None));
 static LAST_SETTINGS_STATE: Mutex<RefCell<Option<SettingsMenuState>>> =
     Mutex::new(RefCell::new(None));
 static BRIGHTNESS_DIRTY: Mutex<RefCell<bool>> = Mutex::new(RefCell::new(false));
+static TEMP_UNIT: Mutex<RefCell<TempUnit>> = Mutex::new(RefCell::new(TempUnit::Celsius));
+static TEMP_STATS: Mutex<RefCell<TempStats>> = Mutex::new(RefCell::new(TempStats::new()));
+static TEMP_DIRTY: Mutex<RefCell<bool>> = Mutex::new(RefCell::new(false));
+static SMASH_CAL_PROGRESS: Mutex<RefCell<Option<u8>>> = Mutex::new(RefCell::new(None));
+static SMASH_CAL_DIRTY: Mutex<RefCell<bool>> = Mutex::new(RefCell::new(false));
+static CAROUSEL_SECS: Mutex<RefCell<u8>> = Mutex::new(RefCell::new(0));
+static CLOCK_RELIABLE: Mutex<RefCell<bool>> = Mutex::new(RefCell::new(true));
+static ANIMATIONS_ENABLED: Mutex<RefCell<bool>> = Mutex::new(RefCell::new(true));
+static COLON_BLINK: Mutex<RefCell<bool>> = Mutex::new(RefCell::new(false));
+// Digital face shows HH:MM:SS instead of HH:MM
+static CLOCK_SECONDS: Mutex<RefCell<bool>> = Mutex::new(RefCell::new(false));
+// 12-hour clock with AM/PM instead of 24-hour
+static CLOCK_12H: Mutex<RefCell<bool>> = Mutex::new(RefCell::new(false));
+static PIXEL_SHIFT: Mutex<RefCell<(i32, i32)>> = Mutex::new(RefCell::new((0, 0)));
+static PIXEL_SHIFT_DIRTY: Mutex<RefCell<bool>> = Mutex::new(RefCell::new(false));
+// Offset changed since the last redraw (only the uncovered strips need clearing)
+static PIXEL_SHIFT_MOVED: Mutex<RefCell<bool>> = Mutex::new(RefCell::new(false));
+// Where `try_draw_image_bytes` last put an item (x, y, w, h), so a shift move can clear
+// what the item leaves behind
+static SHIFTED_ITEM: Mutex<RefCell<Option<(i32, i32, u32, u32)>>> = Mutex::new(RefCell::new(None));
+static ENCODER_DIRECTION: Mutex<RefCell<EncoderDirection>> =
+    Mutex::new(RefCell::new(EncoderDirection::Normal));
+static WRIST: Mutex<RefCell<Wrist>> = Mutex::new(RefCell::new(Wrist::Left));
+static ALWAYS_ON: Mutex<RefCell<bool>> = Mutex::new(RefCell::new(false));
+static AUTO_ROTATE: Mutex<RefCell<bool>> = Mutex::new(RefCell::new(false));
+static BRIGHTNESS_PERCEPTUAL: Mutex<RefCell<bool>> = Mutex::new(RefCell::new(true));
+static SCREEN_TIMEOUT_SECS: Mutex<RefCell<u8>> = Mutex::new(RefCell::new(0));
+static BATTERY_PCT: Mutex<RefCell<Option<u8>>> = Mutex::new(RefCell::new(None));
+static BATTERY_FILTER: Mutex<RefCell<BatteryFilter>> =
+    Mutex::new(RefCell::new(BatteryFilter::new()));
+static BATTERY_THRESHOLDS: Mutex<RefCell<(u8, u8)>> = Mutex::new(RefCell::new((20, 5)));
+static TAP_CADENCE: Mutex<RefCell<TapCadence>> = Mutex::new(RefCell::new(TapCadence::Off));
+static WAKE_SOURCES: Mutex<RefCell<WakeSources>> = Mutex::new(RefCell::new(WakeSources::BUTTON));
+// Time of day (seconds since midnight) the alarm wake source fires at, None = no alarm
+static WAKE_ALARM_AT: Mutex<RefCell<Option<u32>>> = Mutex::new(RefCell::new(None));
+static DATE_STYLE: Mutex<RefCell<DateStyle>> = Mutex::new(RefCell::new(DateStyle::Iso));
+// (day number, style) of the date line currently on the digital face
+static DATE_LINE_SHOWN: Mutex<RefCell<Option<(u32, DateStyle)>>> = Mutex::new(RefCell::new(None));
+// "Set clock" badge is on screen (cleared whenever the face is repainted under it)
+static CLOCK_BADGE_SHOWN: Mutex<RefCell<bool>> = Mutex::new(RefCell::new(false));
+static DIGIT_ROLL: Mutex<RefCell<DigitRoll>> = Mutex::new(RefCell::new(DigitRoll::new()));
+static STATUS_FLAGS: Mutex<RefCell<u8>> = Mutex::new(RefCell::new(0));
+static STATUS_DIRTY: Mutex<RefCell<bool>> = Mutex::new(RefCell::new(false));
 
 // uses a simple stack for navigation history
 fn nav_push(p: Page) {
@@ -173,10 +247,82 @@ pub struct UiState {
     pub dialog: Option<Dialog>,
 }
 
+// Page the watch boots to. Change it to e.g. `Page::Watch(WatchAppState::Analog)` for a
+// build that starts on the clock face; `set_default_page` overrides it at runtime.
+pub const DEFAULT_PAGE: Page = Page::Main(MainMenuState::Home);
+
+static DEFAULT_START_PAGE: Mutex<Cell<Page>> = Mutex::new(Cell::new(DEFAULT_PAGE));
+
+pub fn default_page() -> Page {
+    critical_section::with(|cs| DEFAULT_START_PAGE.borrow(cs).get())
+}
+
+pub fn set_default_page(page: Page) {
+    critical_section::with(|cs| DEFAULT_START_PAGE.borrow(cs).set(page));
+}
+
+// State to boot into (also the fallback when no saved state exists): the default page,
+// no dialog
+pub fn default_start_state() -> UiState {
+    UiState {
+        page: default_page(),
+        dialog: None,
+    }
+}
+
+// Global UI state, owned here so main, serial commands, etc. all navigate the same store
+static UI_STATE: Mutex<Cell<UiState>> = Mutex::new(Cell::new(UiState {
+    page: DEFAULT_PAGE,
+    dialog: None,
+}));
+
+pub fn current_state() -> UiState {
+    critical_section::with(|cs| UI_STATE.borrow(cs).get())
+}
+
+pub fn set_state(state: UiState) {
+    critical_section::with(|cs| UI_STATE.borrow(cs).set(state));
+}
+
+// Apply a navigation step (e.g. `UiState::back`) atomically, return the new state
+pub fn update_state(f: impl FnOnce(UiState) -> UiState) -> UiState {
+    critical_section::with(|cs| {
+        let next = f(UI_STATE.borrow(cs).get());
+        UI_STATE.borrow(cs).set(next);
+        next
+    })
+}
+
+pub fn current_page() -> Page {
+    current_state().page
+}
+
+// Jump straight to a page, closing any open dialog.
+// History is dropped, so Back from the new page returns Home.
+pub fn set_page(page: Page) {
+    critical_section::with(|cs| {
+        NAV_HISTORY.borrow(cs).borrow_mut().clear();
+        UI_STATE.borrow(cs).set(UiState { page, dialog: None });
+    });
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 struct ClockEditState {
-    digits: [u8; 4], // HHMM digits
-    idx: u8,         // active digit 0-3
+    digits: [u8; 4],  // HHMM digits (hours 01-12 when `pm` is set)
+    idx: u8,          // active field: 0-3 time digits, 4 = AM/PM, 5 = year, 6 = month, 7 = day
+    pm: Option<bool>, // Some in 12-hour mode
+    year: u16,
+    month: u8, // 1-12
+    day: u8,   // 1 to the length of `month`
+}
+
+// Years the clock editor offers; the software clock counts u32 Unix seconds (to early 2106)
+const EDIT_YEAR_MIN: u16 = 2020;
+const EDIT_YEAR_MAX: u16 = 2105;
+
+// Wrap `v` into min..=max, for encoder steps of any size
+fn wrap_field(v: i32, min: i32, max: i32) -> i32 {
+    min + (v - min).rem_euclid(max - min + 1)
 }
 
 #[derive(Copy, Clone, Default)]
@@ -184,6 +330,8 @@ struct HandCache {
     sec: Option<Point>,
     min: Option<Point>,
     hour: Option<Point>,
+    // Day number shown in the analog date window
+    date_day: Option<u32>,
 }
 
 impl HandCache {
@@ -192,6 +340,7 @@ impl HandCache {
             sec: None,
             min: None,
             hour: None,
+            date_day: None,
         }
     }
 }
@@ -204,20 +353,63 @@ pub enum Page {
     Settings(SettingsMenuState),
     Omnitrix(OmnitrixState),
     EasterEgg,
+    Temperature,
+    Flashlight,
+    SmashCalibration,
+    // Hidden diagnostics page, opened by holding Button 1 and pressing Button 3
+    Service,
+    // Quick launcher, highlighting entry N of `Page::ALL`
+    Launcher(u8),
+}
+
+impl Page {
+    // Top-level destinations, in launcher order
+    pub const ALL: [Page; 8] = [
+        Page::Watch(WatchAppState::Analog),
+        Page::Watch(WatchAppState::Digital),
+        Page::Omnitrix(OmnitrixState::Alien1),
+        Page::Temperature,
+        Page::Flashlight,
+        Page::Settings(SettingsMenuState::BrightnessPrompt),
+        Page::Service,
+        Page::EasterEgg,
+    ];
+
+    // Human-readable name, as shown in the launcher
+    pub fn title(&self) -> &'static str {
+        match self {
+            Page::Main(_) => "Home",
+            Page::Watch(WatchAppState::Analog) => "Analog Watch",
+            Page::Watch(WatchAppState::Digital) => "Digital Watch",
+            Page::Settings(_) => "Settings",
+            Page::Omnitrix(_) => "Omnitrix",
+            Page::EasterEgg => "Info",
+            Page::Temperature => "Temperature",
+            Page::Flashlight => "Flashlight",
+            Page::SmashCalibration => "Smash Calibration",
+            Page::Service => "Diagnostics",
+            Page::Launcher(_) => "Launcher",
+        }
+    }
 }
 
 // Dialogs that can overlay on top of pages
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum Dialog {
     TransformPage,
+    // The watch was dropped (IMU freefall); dismissed like any dialog
+    DropWarning,
 }
 
 // States for Main Menu
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum MainMenuState {
-    Home,        // just show home
-    WatchApp,    // enter watch app (analog/digital)
-    SettingsApp, // enter Settings
+    Home,          // just show home
+    WatchApp,      // enter watch app (analog/digital)
+    TempApp,       // enter temperature page
+    FlashlightApp, // enter full-white torch page
+    LauncherApp,   // enter the quick launcher (list of every page)
+    SettingsApp,   // enter Settings
 }
 
 // States for Watch App
@@ -228,18 +420,106 @@ pub enum WatchAppState {
 }
 
 // Simple software clock: base seconds and ticks when set.
-static CLOCK_BASE_SECS: Mutex<RefCell<u64>> = Mutex::new(RefCell::new(0));
-static CLOCK_BASE_TICKS: Mutex<RefCell<u64>> = Mutex::new(RefCell::new(0));
+// Stored as a seqlock over 32-bit atomics (Xtensa has no native 64-bit atomics), so the
+// read path used every frame never takes a critical section or blocks the input ISR.
+static CLOCK_SEQ: AtomicU32 = AtomicU32::new(0);
+static CLOCK_BASE_SECS_LO: AtomicU32 = AtomicU32::new(0);
+static CLOCK_BASE_SECS_HI: AtomicU32 = AtomicU32::new(0);
+static CLOCK_BASE_TICKS_LO: AtomicU32 = AtomicU32::new(0);
+static CLOCK_BASE_TICKS_HI: AtomicU32 = AtomicU32::new(0);
+
+// Store a new clock base. Writers are serialized by the caller's critical section.
+fn clock_base_store(secs: u64, ticks: u64) {
+    let seq = CLOCK_SEQ.load(Ordering::Relaxed);
+    CLOCK_SEQ.store(seq.wrapping_add(1), Ordering::Relaxed); // odd: write in progress
+    fence(Ordering::Release);
+    CLOCK_BASE_SECS_LO.store(secs as u32, Ordering::Relaxed);
+    CLOCK_BASE_SECS_HI.store((secs >> 32) as u32, Ordering::Relaxed);
+    CLOCK_BASE_TICKS_LO.store(ticks as u32, Ordering::Relaxed);
+    CLOCK_BASE_TICKS_HI.store((ticks >> 32) as u32, Ordering::Relaxed);
+    CLOCK_SEQ.store(seq.wrapping_add(2), Ordering::Release);
+}
+
+// Lock-free read of (base_secs, base_ticks), retrying if a write raced the read.
+fn clock_base() -> (u64, u64) {
+    loop {
+        let seq0 = CLOCK_SEQ.load(Ordering::Acquire);
+        if seq0 & 1 != 0 {
+            core::hint::spin_loop();
+            continue;
+        }
+        let secs = ((CLOCK_BASE_SECS_HI.load(Ordering::Relaxed) as u64) << 32)
+            | CLOCK_BASE_SECS_LO.load(Ordering::Relaxed) as u64;
+        let ticks = ((CLOCK_BASE_TICKS_HI.load(Ordering::Relaxed) as u64) << 32)
+            | CLOCK_BASE_TICKS_LO.load(Ordering::Relaxed) as u64;
+        fence(Ordering::Acquire);
+        if CLOCK_SEQ.load(Ordering::Relaxed) == seq0 {
+            return (secs, ticks);
+        }
+    }
+}
 
+// Set the software clock to the specified seconds since epoch, but never backwards: a
+// stale source (an RTC read racing the deep-sleep restore, say) can't rewind the time.
 pub fn set_clock_seconds(seconds: u32) {
-    // Set the software clock to the specified seconds since epoch
+    set_clock_seconds_checked(seconds, false);
+}
+
+// Set the clock, refusing to move it backwards unless `force` is set (user edits only).
+// Returns whether the clock was changed.
+pub fn set_clock_seconds_checked(seconds: u32, force: bool) -> bool {
     let now = SystemTimer::unit_value(Unit::Unit0);
     critical_section::with(|cs| {
-        *CLOCK_BASE_SECS.borrow(cs).borrow_mut() = seconds as u64;
-        *CLOCK_BASE_TICKS.borrow(cs).borrow_mut() = now;
+        if !force && (seconds as u64) < clock_now_seconds() {
+            return false;
+        }
+        clock_base_store(seconds as u64, now);
         *HAND_CACHE.borrow(cs).borrow_mut() = HandCache::new();
         *WATCH_FACE_DIRTY.borrow(cs).borrow_mut() = true;
-    });
+        true
+    })
+}
+
+// RTC resync: the PCF85063 only reports whole seconds, so errors under this are noise
+const RESYNC_DEADBAND_MS: i64 = 500;
+// Largest correction applied per call, small enough that the seconds never visibly skip
+const RESYNC_SLEW_MS: i64 = 100;
+// Beyond this the RTC was changed under us (or we were way off); just take its time
+const RESYNC_STEP_MS: i64 = 5_000;
+
+// Nudge the software clock toward the crystal-backed RTC, correcting SystemTimer drift
+// without a visible jump. `rtc_secs` is a fresh RTC read, assumed to be mid-second.
+// Backwards is limited like `set_clock_seconds`: a step back is refused (a stale RTC can't
+// rewind the time), and a slew back only eats into the current second, so the seconds
+// reading never repeats. Returns the correction applied in ms (positive = forward).
+pub fn resync_from_rtc(rtc_secs: u32) -> i64 {
+    let tps = SystemTimer::ticks_per_second();
+    let now = SystemTimer::unit_value(Unit::Unit0);
+    critical_section::with(|cs| {
+        let (base_secs, base_ticks) = clock_base();
+        let clock_ticks = base_secs * tps + now.saturating_sub(base_ticks);
+        let rtc_ticks = rtc_secs as u64 * tps + tps / 2;
+        let err_ms = (rtc_ticks as i64 - clock_ticks as i64) * 1000 / tps as i64;
+
+        let step_ms = if err_ms >= RESYNC_STEP_MS {
+            *HAND_CACHE.borrow(cs).borrow_mut() = HandCache::new();
+            *WATCH_FACE_DIRTY.borrow(cs).borrow_mut() = true;
+            err_ms
+        } else if err_ms.abs() > RESYNC_DEADBAND_MS && err_ms > -RESYNC_STEP_MS {
+            err_ms.clamp(-RESYNC_SLEW_MS, RESYNC_SLEW_MS)
+        } else {
+            return 0;
+        };
+
+        // Rebase so that "now" reads as the corrected time, not before this second's start
+        let target = (clock_ticks as i64 + step_ms * tps as i64 / 1000).max(0) as u64;
+        let target = target.max(clock_ticks - clock_ticks % tps);
+        if target == clock_ticks {
+            return 0;
+        }
+        clock_base_store(target / tps, now.saturating_sub(target % tps));
+        (target as i64 - clock_ticks as i64) * 1000 / tps as i64
+    })
 }
 
 pub fn watch_edit_active() -> bool {
@@ -253,11 +533,27 @@ pub fn watch_edit_start() {
     let total_mins = now / 60;
     let h = ((total_mins / 60) % 24) as u8;
     let m = (total_mins % 60) as u8;
+    let (h, pm) = if clock_12h() {
+        let (h12, pm) = hour_12(h as u32);
+        (h12 as u8, Some(pm))
+    } else {
+        (h, None)
+    };
     let digits = [h / 10, h % 10, m / 10, m % 10];
+    // An unset clock reads 1970; start the year at the bottom of the range instead
+    let dt = unix_to_datetime(now.min(u32::MAX as u64) as u32);
+    let year = dt.year.clamp(EDIT_YEAR_MIN, EDIT_YEAR_MAX);
 
     // Set edit state
     critical_section::with(|cs| {
-        *CLOCK_EDIT.borrow(cs).borrow_mut() = Some(ClockEditState { digits, idx: 0 });
+        *CLOCK_EDIT.borrow(cs).borrow_mut() = Some(ClockEditState {
+            digits,
+            idx: 0,
+            pm,
+            year,
+            month: dt.month,
+            day: dt.day.min(days_in_month(year, dt.month)),
+        });
     });
 }
 
@@ -269,25 +565,68 @@ pub fn watch_edit_cancel() {
 }
 
 pub fn watch_edit_advance() {
-    // Move to next digit or commit changes if on last digit
-    critical_section::with(|cs| {
+    // Move to next field or commit changes if on the day
+    let committed = critical_section::with(|cs| {
         let mut guard = CLOCK_EDIT.borrow(cs).borrow_mut();
         if let Some(mut ed) = *guard {
-            if ed.idx < 3 {
-                ed.idx += 1;
+            if ed.idx < 7 {
+                // The AM/PM field only exists in 12-hour mode
+                ed.idx = if ed.idx == 3 && ed.pm.is_none() {
+                    5
+                } else {
+                    ed.idx + 1
+                };
                 *guard = Some(ed);
             } else {
                 // Commit
-                let hours = (ed.digits[0] as u32) * 10 + (ed.digits[1] as u32);
-                let mins = (ed.digits[2] as u32) * 10 + (ed.digits[3] as u32);
-                let secs = (hours * 60 + mins) * 60;
-                set_clock_seconds(secs);
+                let mut hours = (ed.digits[0] as u32) * 10 + (ed.digits[1] as u32);
+                if let Some(pm) = ed.pm {
+                    hours = hours % 12 + if pm { 12 } else { 0 };
+                }
+                let mins = ed.digits[2] * 10 + ed.digits[3];
+                let secs = datetime_to_unix(&DateTime {
+                    year: ed.year,
+                    month: ed.month,
+                    day: ed.day,
+                    hour: hours as u8,
+                    minute: mins,
+                    second: 0,
+                });
+                set_clock_seconds_checked(secs, true);
                 *HAND_CACHE.borrow(cs).borrow_mut() = HandCache::new();
                 *WATCH_FACE_DIRTY.borrow(cs).borrow_mut() = true;
                 *guard = None;
+                return true;
             }
         }
+        false
+    });
+    // The user just set the time by hand, so stop asking for it
+    if committed {
+        set_clock_reliable(true);
+    }
+}
+
+// Whether the clock holds a real time (false after an RTC power loss until the user sets it)
+pub fn clock_reliable() -> bool {
+    critical_section::with(|cs| *CLOCK_RELIABLE.borrow(cs).borrow())
+}
+
+// Mark the clock as trusted or not; drives the Home status icon and the watch "Set clock" badge
+pub fn set_clock_reliable(reliable: bool) {
+    let changed = critical_section::with(|cs| {
+        let mut r = CLOCK_RELIABLE.borrow(cs).borrow_mut();
+        let changed = *r != reliable;
+        *r = reliable;
+        if changed {
+            // repaint the watch face so the badge appears/disappears
+            *WATCH_FACE_DIRTY.borrow(cs).borrow_mut() = true;
+        }
+        changed
     });
+    if changed {
+        status_icon_set(StatusIcon::ClockWarning, !reliable);
+    }
 }
 
 pub fn watch_edit_adjust(delta: i32) {
@@ -299,19 +638,52 @@ pub fn watch_edit_adjust(delta: i32) {
         let mut guard = CLOCK_EDIT.borrow(cs).borrow_mut();
         // Adjust active digit
         if let Some(mut ed) = *guard {
+            // AM/PM field: any turn flips it
+            if let (4, Some(pm)) = (ed.idx, ed.pm) {
+                ed.pm = Some(!pm);
+                *guard = Some(ed);
+                return;
+            }
+            // Date fields step whole values; the day is kept within the month
+            if ed.idx >= 5 {
+                match ed.idx {
+                    5 => {
+                        let (lo, hi) = (EDIT_YEAR_MIN as i32, EDIT_YEAR_MAX as i32);
+                        ed.year = wrap_field(ed.year as i32 + delta, lo, hi) as u16;
+                    }
+                    6 => ed.month = wrap_field(ed.month as i32 + delta, 1, 12) as u8,
+                    _ => {
+                        let dim = days_in_month(ed.year, ed.month) as i32;
+                        ed.day = wrap_field(ed.day as i32 + delta, 1, dim) as u8;
+                    }
+                }
+                ed.day = ed.day.min(days_in_month(ed.year, ed.month));
+                *guard = Some(ed);
+                return;
+            }
             let idx = ed.idx as usize;
+            // Single digits step by one even on a fast spin
+            let delta = delta.signum();
             let mut digit = ed.digits[idx] as i32;
-            // Determine min/max for digit
-            let (min_d, max_d) = match idx {
-                0 => (0, 2),
-                1 => {
+            // Determine min/max for digit (hours 01-12 in 12-hour mode)
+            let (min_d, max_d) = match (idx, ed.pm.is_some()) {
+                (0, false) => (0, 2),
+                (0, true) => (0, 1),
+                (1, false) => {
                     if ed.digits[0] == 2 {
                         (0, 3)
                     } else {
                         (0, 9)
                     }
                 }
-                2 => (0, 5),
+                (1, true) => {
+                    if ed.digits[0] == 1 {
+                        (0, 2)
+                    } else {
+                        (1, 9)
+                    }
+                }
+                (2, _) => (0, 5),
                 _ => (0, 9),
             };
             // Adjust digit
@@ -326,6 +698,13 @@ pub fn watch_edit_adjust(delta: i32) {
 
             // Update digit
             ed.digits[idx] = digit as u8;
+            // Keep the 12-hour hour valid when its tens digit changes (10-12, 01-09)
+            if idx == 0 && ed.pm.is_some() {
+                ed.digits[1] = match ed.digits[0] {
+                    1 => ed.digits[1].min(2),
+                    _ => ed.digits[1].max(1),
+                };
+            }
             *guard = Some(ed);
         }
     });
@@ -336,28 +715,62 @@ pub fn brightness_pct() -> u8 {
 }
 
 pub fn brightness_set_pct(new_pct: i32) -> u8 {
-    let clamped = new_pct.clamp(0, 100) as u8;
     critical_section::with(|cs| {
+        let (min, max) = *BRIGHTNESS_LIMITS.borrow(cs).borrow();
+        let clamped = new_pct.clamp(min as i32, max as i32) as u8;
         *BRIGHTNESS_PCT.borrow(cs).borrow_mut() = clamped;
         *BRIGHTNESS_DIRTY.borrow(cs).borrow_mut() = true;
-    });
-    clamped
+        clamped
+    })
+}
+
+pub fn brightness_limits() -> (u8, u8) {
+    critical_section::with(|cs| *BRIGHTNESS_LIMITS.borrow(cs).borrow())
+}
+
+// Set the (min, max) brightness bounds, both clamped to 0..=100 with min <= max.
+// The current brightness is pulled inside the new bounds (marking it dirty if it moved).
+// Returns the brightness after the change so the caller can re-apply it to the panel.
+pub fn brightness_set_limits(min_pct: u8, max_pct: u8) -> u8 {
+    let max = max_pct.min(100);
+    let min = min_pct.min(max);
+    critical_section::with(|cs| {
+        *BRIGHTNESS_LIMITS.borrow(cs).borrow_mut() = (min, max);
+        let mut pct = BRIGHTNESS_PCT.borrow(cs).borrow_mut();
+        let clamped = (*pct).clamp(min, max);
+        if clamped != *pct {
+            *pct = clamped;
+            *BRIGHTNESS_DIRTY.borrow(cs).borrow_mut() = true;
+        }
+        clamped
+    })
+}
+
+// (min, max) brightness ranges offered in settings; the first is the full range
+pub const BRIGHTNESS_LIMIT_PRESETS: [(u8, u8); 5] =
+    [(0, 100), (10, 100), (20, 100), (0, 70), (10, 50)];
+
+// Step to the next preset range, return it
+pub fn brightness_limits_cycle() -> (u8, u8) {
+    let cur = brightness_limits();
+    let idx = BRIGHTNESS_LIMIT_PRESETS
+        .iter()
+        .position(|&p| p == cur)
+        .unwrap_or(0);
+    let next = BRIGHTNESS_LIMIT_PRESETS[(idx + 1) % BRIGHTNESS_LIMIT_PRESETS.len()];
+    brightness_set_limits(next.0, next.1);
+    next
 }
 
-// Adjust brightness by delta, return new percentage
+// Adjust brightness by delta within the configured limits, return new percentage
 pub fn brightness_adjust(delta: i32) -> u8 {
     if delta == 0 {
         return brightness_pct();
     }
     critical_section::with(|cs| {
-        let mut pct = *BRIGHTNESS_PCT.borrow(cs).borrow();
-        let mut v = pct as i32 + delta;
-        if v < 0 {
-            v = 0;
-        } else if v > 100 {
-            v = 100;
-        }
-        pct = v as u8;
+        let (min, max) = *BRIGHTNESS_LIMITS.borrow(cs).borrow();
+        let cur = *BRIGHTNESS_PCT.borrow(cs).borrow() as i32;
+        let pct = (cur + delta).clamp(min as i32, max as i32) as u8;
         // Mark dirty if changed
         if pct != *BRIGHTNESS_PCT.borrow(cs).borrow() {
             *BRIGHTNESS_PCT.borrow(cs).borrow_mut() = pct;
@@ -387,1694 +800,5083 @@ pub fn brightness_take_dirty() -> bool {
     })
 }
 
-// Get the current clock time in seconds since epoch (for saving before deep sleep)
-pub fn get_clock_seconds() -> u64 {
-    clock_now_seconds()
+// Brightness curve: perceptual (gamma 2.2 table) by default; off keeps the old linear
+// pct*255/100 mapping. main.rs reads it whenever it applies a brightness level.
+pub fn brightness_perceptual() -> bool {
+    critical_section::with(|cs| *BRIGHTNESS_PERCEPTUAL.borrow(cs).borrow())
 }
 
-// Clear all cached assets and state (call after waking from deep sleep)
-pub fn clear_all_caches() {
-    critical_section::with(|cs| {
-        // Clear asset cache
-        let mut assets = ASSETS.borrow(cs).borrow_mut();
-        for slot in assets.iter_mut() {
-            slot.data = None;
-            slot.w = 0;
-            slot.h = 0;
-        }
+pub fn brightness_perceptual_set(on: bool) {
+    critical_section::with(|cs| *BRIGHTNESS_PERCEPTUAL.borrow(cs).borrow_mut() = on);
+}
 
-        // Clear page tracking
-        *LAST_PAGE_KIND.borrow(cs).borrow_mut() = None;
-        *LAST_OMNI_TRANSFORM_ACTIVE.borrow(cs).borrow_mut() = false;
-        *NAV_HISTORY.borrow(cs).borrow_mut() = Vec::new();
-        *LAST_WATCH_STATE.borrow(cs).borrow_mut() = None;
-        *CLOCK_EDIT.borrow(cs).borrow_mut() = None;
-        *LAST_WATCH_EDIT_ACTIVE.borrow(cs).borrow_mut() = false;
-        *HAND_CACHE.borrow(cs).borrow_mut() = HandCache::new();
-        *WATCH_BG.borrow(cs).borrow_mut() = None;
-        *WATCH_FACE_DIRTY.borrow(cs).borrow_mut() = false;
-        *LAST_TRANSFORM_ACTIVE.borrow(cs).borrow_mut() = false;
-        *BRIGHTNESS_LAST.borrow(cs).borrow_mut() = None;
-        *LAST_SETTINGS_STATE.borrow(cs).borrow_mut() = None;
-        *BRIGHTNESS_DIRTY.borrow(cs).borrow_mut() = false;
-    });
+// Temperature display unit
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TempUnit {
+    Celsius,
+    Fahrenheit,
 }
 
-fn clock_now_seconds() -> u64 {
-    // Get current software clock time in seconds since epoch
-    critical_section::with(|cs| {
-        let base_secs = *CLOCK_BASE_SECS.borrow(cs).borrow();
-        let base_ticks = *CLOCK_BASE_TICKS.borrow(cs).borrow();
-        let now = SystemTimer::unit_value(Unit::Unit0);
-        let tps = SystemTimer::ticks_per_second();
-        let elapsed = now.saturating_sub(base_ticks) / tps;
-        base_secs.saturating_add(elapsed)
-    })
+// Date line order, all prefixed with the weekday: "Tue 2024-05-14" / "Tue 05/14/2024" /
+// "Tue 14/05/2024"
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DateStyle {
+    Iso,
+    Us,
+    Eu,
 }
 
-pub fn clock_now_seconds_u32() -> u32 {
-    clock_now_seconds() as u32
+pub fn date_style() -> DateStyle {
+    critical_section::with(|cs| *DATE_STYLE.borrow(cs).borrow())
 }
 
-fn clock_now_seconds_f32() -> f32 {
-    // Get current software clock time in seconds since epoch as f32
-    critical_section::with(|cs| {
-        let base_secs = *CLOCK_BASE_SECS.borrow(cs).borrow();
-        let base_ticks = *CLOCK_BASE_TICKS.borrow(cs).borrow();
-        let now = SystemTimer::unit_value(Unit::Unit0);
-        let tps = SystemTimer::ticks_per_second() as u64;
-        let elapsed_ticks = now.saturating_sub(base_ticks);
-        let whole = elapsed_ticks / tps;
-        let frac = (elapsed_ticks % tps) as f32 / tps as f32;
-        // Work modulo 24h to preserve sub-second precision even with large epoch seconds.
-        let total = base_secs + whole;
-        let within_day = (total % 86_400) as f32;
-        within_day + frac
-    })
+pub fn date_style_set(style: DateStyle) {
+    critical_section::with(|cs| *DATE_STYLE.borrow(cs).borrow_mut() = style);
 }
 
-// Return hours, minutes, seconds as f32 with good precision by working modulo 12h.
-fn clock_now_hms_f32() -> (f32, f32, f32) {
-    critical_section::with(|cs| {
-        let base_secs = *CLOCK_BASE_SECS.borrow(cs).borrow();
-        let base_ticks = *CLOCK_BASE_TICKS.borrow(cs).borrow();
-        let now = SystemTimer::unit_value(Unit::Unit0);
-        let tps = SystemTimer::ticks_per_second() as u64;
-        let elapsed_ticks = now.saturating_sub(base_ticks);
-        let whole = elapsed_ticks / tps;
-        let frac = (elapsed_ticks % tps) as f32 / tps as f32;
-        let total = base_secs + whole;
-        let s = (total % 60) as f32 + frac;
-        let m_total = total / 60;
-        let m = (m_total % 60) as f32 + s / 60.0;
-        let h_total = m_total / 60;
-        let h = (h_total % 12) as f32 + m / 60.0;
-        (h, m, s)
-    })
+// Step ISO -> US -> EU -> ISO, return the new style
+pub fn date_style_cycle() -> DateStyle {
+    let next = match date_style() {
+        DateStyle::Iso => DateStyle::Us,
+        DateStyle::Us => DateStyle::Eu,
+        DateStyle::Eu => DateStyle::Iso,
+    };
+    date_style_set(next);
+    next
 }
 
-// States for Settings Menu
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
-pub enum SettingsMenuState {
-    BrightnessPrompt,
-    BrightnessAdjust,
-    EasterEgg,
+#[cfg(feature = "esp32s3-disp143Oled")]
+const WEEKDAY_ABBR: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+
+// Format `dt` as "Www " + date in `style` into the provided buffer and return it as &str
+#[cfg(feature = "esp32s3-disp143Oled")]
+pub fn format_date(dt: &DateTime, style: DateStyle, buf: &mut [u8; 14]) -> &str {
+    fn put2(out: &mut [u8], v: u8) {
+        out[0] = b'0' + (v / 10) % 10;
+        out[1] = b'0' + v % 10;
+    }
+    fn put4(out: &mut [u8], v: u16) {
+        put2(&mut out[0..2], (v / 100 % 100) as u8);
+        put2(&mut out[2..4], (v % 100) as u8);
+    }
+
+    let wd = WEEKDAY_ABBR[dt.weekday() as usize % 7];
+    buf[..3].copy_from_slice(wd.as_bytes());
+    buf[3] = b' ';
+    let date = &mut buf[4..];
+    match style {
+        DateStyle::Iso => {
+            put4(&mut date[0..4], dt.year);
+            date[4] = b'-';
+            put2(&mut date[5..7], dt.month);
+            date[7] = b'-';
+            put2(&mut date[8..10], dt.day);
+        }
+        DateStyle::Us | DateStyle::Eu => {
+            let (a, b) = if style == DateStyle::Us {
+                (dt.month, dt.day)
+            } else {
+                (dt.day, dt.month)
+            };
+            put2(&mut date[0..2], a);
+            date[2] = b'/';
+            put2(&mut date[3..5], b);
+            date[5] = b'/';
+            put4(&mut date[6..10], dt.year);
+        }
+    }
+    core::str::from_utf8(buf).unwrap_or("??? ????-??-??")
 }
 
-// States for Omnitrix Menu
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
-pub enum OmnitrixState {
-    Alien1,
-    Alien2,
-    Alien3,
-    Alien4,
-    Alien5,
-    Alien6,
-    Alien7,
-    Alien8,
-    Alien9,
-    Alien10,
+// Last reading plus min/max since boot (or since the last reset), all in Celsius
+#[derive(Copy, Clone)]
+struct TempStats {
+    last: Option<f32>,
+    min: f32,
+    max: f32,
 }
 
-impl UiState {
-    // Move to the next item/state in the current layer (rotary CW)
-    pub fn next_item(self) -> Self {
-        if self.dialog.is_some() {
-            return self;
-        }
-        let next_page = match self.page {
-            Page::Main(state) => {
-                let next = match state {
-                    MainMenuState::Home => MainMenuState::WatchApp,
-                    MainMenuState::WatchApp => MainMenuState::SettingsApp,
-                    MainMenuState::SettingsApp => MainMenuState::Home,
-                };
-                Page::Main(next)
-            }
-            Page::Watch(state) => {
-                let next = match state {
-                    WatchAppState::Analog => WatchAppState::Digital,
-                    WatchAppState::Digital => WatchAppState::Analog,
-                };
-                Page::Watch(next)
-            }
-            Page::Settings(state) => {
-                let next = match state {
-                    SettingsMenuState::BrightnessPrompt => SettingsMenuState::EasterEgg,
-                    SettingsMenuState::EasterEgg => SettingsMenuState::BrightnessPrompt,
-                    SettingsMenuState::BrightnessAdjust => SettingsMenuState::BrightnessAdjust,
-                };
-                Page::Settings(next)
-            }
-            Page::Omnitrix(state) => {
-                let next = match state {
-                    OmnitrixState::Alien1 => OmnitrixState::Alien2,
-                    OmnitrixState::Alien2 => OmnitrixState::Alien3,
-                    OmnitrixState::Alien3 => OmnitrixState::Alien4,
-                    OmnitrixState::Alien4 => OmnitrixState::Alien5,
-                    OmnitrixState::Alien5 => OmnitrixState::Alien6,
-                    OmnitrixState::Alien6 => OmnitrixState::Alien7,
-                    OmnitrixState::Alien7 => OmnitrixState::Alien8,
-                    OmnitrixState::Alien8 => OmnitrixState::Alien9,
-                    OmnitrixState::Alien9 => OmnitrixState::Alien10,
-                    OmnitrixState::Alien10 => OmnitrixState::Alien1,
-                };
-                Page::Omnitrix(next)
-            }
-            Page::EasterEgg => Page::EasterEgg,
-        };
+impl TempStats {
+    const fn new() -> Self {
         Self {
-            page: next_page,
-            dialog: None,
+            last: None,
+            min: f32::MAX,
+            max: f32::MIN,
         }
     }
+}
 
-    // Move to the previous item/state (rotary CCW)
-    pub fn prev_item(self) -> Self {
-        if self.dialog.is_some() {
-            return self;
-        }
-        let prev_page = match self.page {
-            Page::Main(state) => {
-                let prev = match state {
-                    MainMenuState::Home => MainMenuState::SettingsApp,
-                    MainMenuState::WatchApp => MainMenuState::Home,
-                    MainMenuState::SettingsApp => MainMenuState::WatchApp,
-                };
-                Page::Main(prev)
-            }
-            Page::Watch(state) => {
-                let prev = match state {
-                    WatchAppState::Analog => WatchAppState::Digital,
-                    WatchAppState::Digital => WatchAppState::Analog,
-                };
-                Page::Watch(prev)
-            }
-            Page::Settings(state) => {
-                let prev = match state {
-                    SettingsMenuState::BrightnessPrompt => SettingsMenuState::EasterEgg,
-                    SettingsMenuState::EasterEgg => SettingsMenuState::BrightnessPrompt,
-                    SettingsMenuState::BrightnessAdjust => SettingsMenuState::BrightnessAdjust,
-                };
-                Page::Settings(prev)
-            }
-            Page::Omnitrix(state) => {
-                let prev = match state {
-                    OmnitrixState::Alien1 => OmnitrixState::Alien10,
-                    OmnitrixState::Alien2 => OmnitrixState::Alien1,
-                    OmnitrixState::Alien3 => OmnitrixState::Alien2,
-                    OmnitrixState::Alien4 => OmnitrixState::Alien3,
-                    OmnitrixState::Alien5 => OmnitrixState::Alien4,
-                    OmnitrixState::Alien6 => OmnitrixState::Alien5,
-                    OmnitrixState::Alien7 => OmnitrixState::Alien6,
-                    OmnitrixState::Alien8 => OmnitrixState::Alien7,
-                    OmnitrixState::Alien9 => OmnitrixState::Alien8,
-                    OmnitrixState::Alien10 => OmnitrixState::Alien9,
-                };
-                Page::Omnitrix(prev)
-            }
-            Page::EasterEgg => Page::EasterEgg,
-        };
-        Self {
-            page: prev_page,
-            dialog: None,
-        }
-    }
+pub fn temp_unit() -> TempUnit {
+    critical_section::with(|cs| *TEMP_UNIT.borrow(cs).borrow())
+}
 
-    // Go back (Button 1)
-    pub fn back(self) -> Self {
-        if self.dialog.is_some() {
-            return Self {
-                page: self.page,
-                dialog: None,
-            };
-        }
-        // If in Settings adjust view, pop back to prompt (also pop nav once).
-        if matches!(
-            self.page,
-            Page::Settings(SettingsMenuState::BrightnessAdjust)
-        ) {
-            let _ = nav_pop();
-            return Self {
-                page: Page::Settings(SettingsMenuState::BrightnessPrompt),
-                dialog: None,
-            };
-        }
-        if matches!(self.page, Page::EasterEgg) {
-            let _ = nav_pop(); // drop the settings->easter egg push
-            return Self {
-                page: Page::Settings(SettingsMenuState::EasterEgg),
-                dialog: None,
-            };
-        }
+pub fn temp_unit_set(unit: TempUnit) {
+    critical_section::with(|cs| *TEMP_UNIT.borrow(cs).borrow_mut() = unit);
+}
 
-        // Otherwise, try navigation history first.
-        if let Some(prev) = nav_pop() {
-            return Self {
-                page: prev,
-                dialog: None,
-            };
-        }
-        // Fallback if no history
-        Self {
-            page: Page::Main(MainMenuState::Home),
-            dialog: None,
+// Flip between Celsius and Fahrenheit, return the new unit
+pub fn temp_unit_toggle() -> TempUnit {
+    let next = match temp_unit() {
+        TempUnit::Celsius => TempUnit::Fahrenheit,
+        TempUnit::Fahrenheit => TempUnit::Celsius,
+    };
+    temp_unit_set(next);
+    next
+}
+
+// Omnitrix carousel: auto-advance interval presets in seconds (0 = off)
+pub const CAROUSEL_PRESETS: [u8; 4] = [0, 3, 5, 10];
+
+pub fn carousel_secs() -> u8 {
+    critical_section::with(|cs| *CAROUSEL_SECS.borrow(cs).borrow())
+}
+
+pub fn carousel_set_secs(secs: u8) {
+    critical_section::with(|cs| *CAROUSEL_SECS.borrow(cs).borrow_mut() = secs);
+}
+
+// Step to the next preset (wrapping back to off), return the new interval
+pub fn carousel_cycle() -> u8 {
+    let cur = carousel_secs();
+    let idx = CAROUSEL_PRESETS.iter().position(|&p| p == cur).unwrap_or(0);
+    let next = CAROUSEL_PRESETS[(idx + 1) % CAROUSEL_PRESETS.len()];
+    carousel_set_secs(next);
+    next
+}
+
+// Optional UI animations (digit roll etc.), can be turned off to save power
+pub fn animations_enabled() -> bool {
+    critical_section::with(|cs| *ANIMATIONS_ENABLED.borrow(cs).borrow())
+}
+
+pub fn animations_set(on: bool) {
+    critical_section::with(|cs| *ANIMATIONS_ENABLED.borrow(cs).borrow_mut() = on);
+}
+
+// Blink the digital clock's colon once per second (off by default)
+pub fn colon_blink_enabled() -> bool {
+    critical_section::with(|cs| *COLON_BLINK.borrow(cs).borrow())
+}
+
+pub fn colon_blink_set(on: bool) {
+    critical_section::with(|cs| *COLON_BLINK.borrow(cs).borrow_mut() = on);
+}
+
+// Seconds on the digital face (off by default)
+pub fn clock_seconds_enabled() -> bool {
+    critical_section::with(|cs| *CLOCK_SECONDS.borrow(cs).borrow())
+}
+
+pub fn clock_seconds_set(on: bool) {
+    critical_section::with(|cs| *CLOCK_SECONDS.borrow(cs).borrow_mut() = on);
+}
+
+// 12-hour "hh:MM AM" clock instead of 24-hour "HH:MM" (off by default)
+pub fn clock_12h() -> bool {
+    critical_section::with(|cs| *CLOCK_12H.borrow(cs).borrow())
+}
+
+pub fn clock_12h_set(on: bool) {
+    critical_section::with(|cs| *CLOCK_12H.borrow(cs).borrow_mut() = on);
+}
+
+// Burn-in mitigation: a small global offset added to image and analog-face positions.
+// Kept within +/-PIXEL_SHIFT_MAX; full-screen images lose at most that much off one edge.
+pub const PIXEL_SHIFT_MAX: i32 = 4;
+
+// Set the render offset (clamped), returns true if it changed. The next redraw repaints
+// the page at the new position.
+pub fn set_pixel_shift(dx: i32, dy: i32) -> bool {
+    let next = (
+        dx.clamp(-PIXEL_SHIFT_MAX, PIXEL_SHIFT_MAX),
+        dy.clamp(-PIXEL_SHIFT_MAX, PIXEL_SHIFT_MAX),
+    );
+    critical_section::with(|cs| {
+        let mut cur = PIXEL_SHIFT.borrow(cs).borrow_mut();
+        if *cur == next {
+            return false;
         }
-    }
+        *cur = next;
+        *PIXEL_SHIFT_MOVED.borrow(cs).borrow_mut() = true;
+        true
+    })
+}
 
-    // Select/enter (Button 2)
-    pub fn select(self) -> Self {
-        if let Some(_) = self.dialog {
-            return Self {
-                page: self.page,
-                dialog: None,
-            };
+// Wipe the panel and repaint everything on the next redraw, e.g. after the display's
+// gamma changed under content already on screen
+pub fn force_full_repaint() {
+    critical_section::with(|cs| *PIXEL_SHIFT_DIRTY.borrow(cs).borrow_mut() = true);
+}
+
+pub fn pixel_shift() -> (i32, i32) {
+    critical_section::with(|cs| *PIXEL_SHIFT.borrow(cs).borrow())
+}
+
+// Top-left for a centered w x h item with the pixel shift applied. An item smaller than
+// the panel is clamped so it stays fully on screen; a full-screen image moves by the whole
+// shift, clipped on one side and leaving a black strip on the other.
+fn shifted_origin(w: u32, h: u32) -> (i32, i32) {
+    let (dx, dy) = pixel_shift();
+    let free_x = RESOLUTION.saturating_sub(w) as i32;
+    let free_y = RESOLUTION.saturating_sub(h) as i32;
+    // Not mirrored with the wrist, so images move together with the analog face
+    let fit = |pos: i32, free: i32| if free > 0 { pos.clamp(0, free) } else { pos };
+    (
+        fit(layout_x(free_x / 2, w as i32) + dx, free_x),
+        fit(free_y / 2 + dy, free_y),
+    )
+}
+
+// After a pixel shift move, black out the part of the last shifted item's old footprint
+// that it no longer covers at the new offset (for a full-screen image: the exposed edge
+// strips). The page redraw repaints everything else.
+fn clear_shift_strips(disp: &mut impl PanelRgb565) {
+    let Some((x, y, w, h)) = critical_section::with(|cs| *SHIFTED_ITEM.borrow(cs).borrow()) else {
+        return;
+    };
+    let (nx, ny) = shifted_origin(w, h);
+    let (w, h) = (w as i32, h as i32);
+    let res = RESOLUTION as i32;
+    // Old [start, end) on one axis minus the new one
+    let uncovered = |old: i32, new: i32, len: i32| {
+        if new > old {
+            (old, new.min(old + len))
+        } else {
+            ((new + len).max(old), old + len)
         }
-        match self.page {
-            Page::Main(state) => {
-                nav_push(Page::Main(state));
-                let page = match state {
-                    MainMenuState::Home => Page::Omnitrix(OmnitrixState::Alien1),
-                    MainMenuState::WatchApp => Page::Watch(WatchAppState::Analog),
-                    MainMenuState::SettingsApp => {
-                        Page::Settings(SettingsMenuState::BrightnessPrompt)
-                    }
-                };
-                Self { page, dialog: None }
-            }
-            Page::Watch(_) => Self {
-                page: self.page,
-                dialog: None,
-            },
-            Page::Settings(s) => {
-                let page = match s {
-                    SettingsMenuState::BrightnessPrompt => {
-                        nav_push(Page::Settings(s));
-                        Page::Settings(SettingsMenuState::BrightnessAdjust)
-                    }
-                    SettingsMenuState::EasterEgg => {
-                        nav_push(Page::Settings(s));
-                        Page::EasterEgg
-                    }
-                    _ => self.page,
-                };
-                Self { page, dialog: None }
-            }
-            Page::Omnitrix(_) => Self {
-                page: self.page,
-                dialog: None,
-            }, // changed
-            Page::EasterEgg => Self {
-                page: self.page,
-                dialog: None,
-            },
+    };
+    let (cx0, cx1) = uncovered(x, nx, w);
+    let (ry0, ry1) = uncovered(y, ny, h);
+    // A column strip and a row strip, (x0, y0, x1, y1) with exclusive ends
+    for (x0, y0, x1, y1) in [(cx0, y, cx1, y + h), (x, ry0, x + w, ry1)] {
+        let (x0, y0, x1, y1) = (x0.max(0), y0.max(0), x1.min(res), y1.min(res));
+        if x0 >= x1 || y0 >= y1 {
+            continue;
         }
-    }
-
-    // Omnitrix transform (Button 3)
-    pub fn transform(self) -> Self {
-        // Only if on Omnitrix and no dialog already
-        if matches!(self.page, Page::Omnitrix(_)) && self.dialog.is_none() {
-            Self {
-                page: self.page,
-                dialog: Some(Dialog::TransformPage),
-            }
+        if let Some(co) = disp.fast_panel() {
+            co.fill_rect_fb(x0, y0, x1 - 1, y1 - 1, Rgb565::BLACK);
+            let _ = co.flush_rect_even(x0 as u16, y0 as u16, (x1 - 1) as u16, (y1 - 1) as u16);
         } else {
-            self
+            let _ = Rectangle::new(
+                Point::new(x0, y0),
+                Size::new((x1 - x0) as u32, (y1 - y0) as u32),
+            )
+            .into_styled(PrimitiveStyle::with_fill(Rgb565::BLACK))
+            .draw(disp);
         }
     }
 }
 
-// helper function to draw centered text
-fn draw_text(
-    disp: &mut impl PanelRgb565,
-    text: &str,
-    fg: Rgb565,
-    bg: Option<Rgb565>,
-    x_point: i32,
-    y_point: i32,
-    clear: bool,
-    update_fb: bool,
-    font: Option<&'static MonoFont<'static>>,
-) {
-    if clear {
-        // Prefer no-FB clear if available and requested
-        if !update_fb {
-            if let Some(co) =
-                (disp as &mut dyn Any).downcast_mut::<crate::display::DisplayType<'static>>()
-            {
-                let _ = co.fill_rect_solid_no_fb(
-                    0,
-                    0,
-                    RESOLUTION as u16,
-                    RESOLUTION as u16,
-                    Rgb565::BLACK,
-                );
-            } else {
-                let _ = disp.clear(Rgb565::BLACK);
-            }
-        } else {
-            let _ = disp.clear(Rgb565::BLACK);
-        }
-    }
-    let font = font.unwrap_or(&FONT_10X20);
-    let mut builder = MonoTextStyleBuilder::new().font(font).text_color(fg);
-    if let Some(b) = bg {
-        builder = builder.background_color(b);
-    }
-    let style = builder.build();
-    Text::with_alignment(text, Point::new(x_point, y_point), style, Alignment::Center)
-        .draw(disp)
-        .ok();
+// Which way the encoder turns count as "forward" (next item / increase)
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum EncoderDirection {
+    Normal,
+    Reversed,
 }
 
-// Format current clock as HH:MM into the provided 5-byte buffer and return it as &str.
-fn format_clock_hm(buf: &mut [u8; 5]) -> &str {
-    let total_secs = clock_now_seconds();
-    let total_mins = total_secs / 60;
-    let h = (total_mins / 60) % 24;
-    let m = total_mins % 60;
+pub fn encoder_direction() -> EncoderDirection {
+    critical_section::with(|cs| *ENCODER_DIRECTION.borrow(cs).borrow())
+}
 
-    buf[0] = b'0' + (h / 10) as u8;
-    buf[1] = b'0' + (h % 10) as u8;
-    buf[2] = b':';
-    buf[3] = b'0' + (m / 10) as u8;
-    buf[4] = b'0' + (m % 10) as u8;
+pub fn encoder_direction_set(dir: EncoderDirection) {
+    critical_section::with(|cs| *ENCODER_DIRECTION.borrow(cs).borrow_mut() = dir);
+}
 
-    core::str::from_utf8(buf).unwrap_or("??:??")
+pub fn encoder_direction_toggle() -> EncoderDirection {
+    let next = match encoder_direction() {
+        EncoderDirection::Normal => EncoderDirection::Reversed,
+        EncoderDirection::Reversed => EncoderDirection::Normal,
+    };
+    encoder_direction_set(next);
+    next
 }
 
-fn rgb565_from_888(r: u8, g: u8, b: u8) -> Rgb565 {
-    Rgb565::new((r >> 3) as u8, (g >> 2) as u8, (b >> 3) as u8)
+// Always-on mode: the face dims when the wrist is lowered and brightens on raise, instead of
+// relying on deep sleep (main.rs runs the dim/bright state machine)
+pub fn always_on_enabled() -> bool {
+    critical_section::with(|cs| *ALWAYS_ON.borrow(cs).borrow())
 }
 
-fn hand_end(cx: i32, cy: i32, angle_deg: f32, length: i32) -> Point {
-    let ang = angle_deg.to_radians();
-    let dx = (cosf(ang) * length as f32) as i32;
-    let dy = (sinf(ang) * length as f32) as i32;
-    Point::new(cx + dx, cy + dy)
+pub fn always_on_set(on: bool) {
+    critical_section::with(|cs| *ALWAYS_ON.borrow(cs).borrow_mut() = on);
 }
 
-fn draw_hand_line(
-    disp: &mut impl PanelRgb565,
-    cx: i32,
-    cy: i32,
-    end: Point,
-    color: Rgb565,
-    stroke: u8,
-) {
-    let style = PrimitiveStyle::with_stroke(color, stroke.into());
-    let _ = Line::new(Point::new(cx, cy), end)
-        .into_styled(style)
-        .draw(disp);
+// Auto-rotate: main.rs turns the panel to follow gravity while this is on; turning it off
+// keeps whatever orientation is current
+pub fn auto_rotate_enabled() -> bool {
+    critical_section::with(|cs| *AUTO_ROTATE.borrow(cs).borrow())
 }
 
-fn draw_analog_clock(disp: &mut impl PanelRgb565) {
-    let center = (RESOLUTION as i32 / 2, RESOLUTION as i32 / 2);
-    let cx = center.0;
-    let cy = center.1;
+pub fn auto_rotate_set(on: bool) {
+    critical_section::with(|cs| *AUTO_ROTATE.borrow(cs).borrow_mut() = on);
+}
 
-    // Current time in fractional hours, minutes, seconds
-    let (h, m, s) = clock_now_hms_f32();
+// Screen timeout: panel off (CPU keeps running) after this many idle seconds (0 = never).
+// Lighter than the hold-to-sleep deep sleep, and the clock never stops.
+pub const SCREEN_TIMEOUT_PRESETS: [u8; 4] = [0, 10, 30, 60];
 
-    // Angles: 0 deg at 12 o'clock, increasing clockwise
-    let sec_ang = (s / 60.0) * 360.0 - 90.0;
-    let min_ang = (m / 60.0) * 360.0 - 90.0;
-    let hour_ang = (h / 12.0) * 360.0 - 90.0;
+pub fn screen_timeout_secs() -> u8 {
+    critical_section::with(|cs| *SCREEN_TIMEOUT_SECS.borrow(cs).borrow())
+}
 
-    // Hand lengths
-    let radius = RESOLUTION as i32 / 2 - 10;
-    let sec_len = radius - 10;
-    let min_len = radius - 25;
-    let hour_len = radius - 50;
+pub fn screen_timeout_set_secs(secs: u8) {
+    critical_section::with(|cs| *SCREEN_TIMEOUT_SECS.borrow(cs).borrow_mut() = secs);
+}
 
-    // Compute new endpoints
-    let sec_end = hand_end(cx, cy, sec_ang, sec_len);
-    let min_end = hand_end(cx, cy, min_ang, min_len);
-    let hour_end = hand_end(cx, cy, hour_ang, hour_len);
+// Step to the next preset (wrapping back to never), return the new timeout
+pub fn screen_timeout_cycle() -> u8 {
+    let cur = screen_timeout_secs();
+    let idx = SCREEN_TIMEOUT_PRESETS
+        .iter()
+        .position(|&p| p == cur)
+        .unwrap_or(0);
+    let next = SCREEN_TIMEOUT_PRESETS[(idx + 1) % SCREEN_TIMEOUT_PRESETS.len()];
+    screen_timeout_set_secs(next);
+    next
+}
 
-    // Fast path: draw into FB only and flush once.
-    if let Some(co) = (disp as &mut dyn Any).downcast_mut::<crate::display::DisplayType<'static>>()
-    {
-        let (bbox, _) = critical_section::with(|cs| {
-            let mut cache = HAND_CACHE.borrow(cs).borrow_mut();
-            let bg_ref = WATCH_BG.borrow(cs).borrow();
-            let bgdata = bg_ref.as_ref();
+// Battery state of charge in percent, fed by whatever measures it (None until the first
+// reading, so boards without a gauge never trigger the low-battery actions)
+pub fn battery_record(pct: u8) {
+    critical_section::with(|cs| *BATTERY_PCT.borrow(cs).borrow_mut() = Some(pct.min(100)));
+}
 
-            // Bounding box of old + new hands with padding
-            let mut minx = cx;
-            let mut miny = cy;
-            let mut maxx = cx;
-            let mut maxy = cy;
-            let mut add_pt = |p: Point, pad: i32| {
-                minx = minx.min(p.x - pad);
-                miny = miny.min(p.y - pad);
-                maxx = maxx.max(p.x + pad);
-                maxy = maxy.max(p.y + pad);
-            };
+pub fn battery_pct() -> Option<u8> {
+    critical_section::with(|cs| *BATTERY_PCT.borrow(cs).borrow())
+}
 
-            // Add previous hand endpoints
-            let sec_stroke = 4;
-            let min_stroke = 4;
-            let hour_stroke = 4;
-            let sec_pad = (sec_stroke * 2).max(6);
-            let min_pad = (min_stroke * 2).max(8);
-            let hour_pad = (hour_stroke * 2).max(10);
+// Battery voltage filter. ADC readings jitter by tens of mV and the cell sags under the
+// panel's draw, so each reading is first corrected for the estimated load (I * R), then
+// smoothed with an EMA, then mapped through a LiPo open-circuit curve.
+const BATTERY_EMA_ALPHA: f32 = 0.1;
+// Estimated current: board baseline plus the panel's share at full brightness
+const BATTERY_BASE_MA: f32 = 45.0;
+const BATTERY_PANEL_MA: f32 = 110.0;
+// Cell + protection + wiring resistance
+const BATTERY_INTERNAL_MOHM: f32 = 200.0;
+// (open-circuit mV, percent), ascending
+const LIPO_CURVE: [(f32, f32); 11] = [
+    (3300.0, 0.0),
+    (3500.0, 5.0),
+    (3600.0, 10.0),
+    (3700.0, 30.0),
+    (3750.0, 45.0),
+    (3800.0, 55.0),
+    (3850.0, 65.0),
+    (3900.0, 73.0),
+    (4000.0, 85.0),
+    (4100.0, 94.0),
+    (4200.0, 100.0),
+];
+
+struct BatteryFilter {
+    ema_mv: Option<f32>,
+    // Last published whole percent
+    shown: Option<u8>,
+}
 
-            // Previous points
-            if let Some(p) = cache.sec {
-                add_pt(p, sec_pad);
-            }
-            if let Some(p) = cache.min {
-                add_pt(p, min_pad);
-            }
-            if let Some(p) = cache.hour {
-                add_pt(p, hour_pad);
-            }
+impl BatteryFilter {
+    const fn new() -> Self {
+        Self {
+            ema_mv: None,
+            shown: None,
+        }
+    }
+}
 
-            // New points
-            add_pt(sec_end, sec_pad);
-            add_pt(min_end, min_pad);
-            add_pt(hour_end, hour_pad);
+fn lipo_pct(mv: f32) -> f32 {
+    let (first, last) = (LIPO_CURVE[0], LIPO_CURVE[LIPO_CURVE.len() - 1]);
+    if mv <= first.0 {
+        return first.1;
+    }
+    if mv >= last.0 {
+        return last.1;
+    }
+    for pair in LIPO_CURVE.windows(2) {
+        let ((v0, p0), (v1, p1)) = (pair[0], pair[1]);
+        if mv <= v1 {
+            return p0 + (p1 - p0) * (mv - v0) / (v1 - v0);
+        }
+    }
+    last.1
+}
 
-            // Center dot padding
-            let dot_pad = 22; // covers enlarged center gradient
-            add_pt(Point::new(cx, cy), dot_pad);
+// Feed one battery ADC reading (`mv` at the cell, after any divider) taken while the
+// panel ran at `load_pct` brightness. The published percent only moves once the filtered
+// value is a whole percent away from it, so jitter can't make the readout flicker between
+// two values. Returns true when it changed (it is then also passed to `battery_record`).
+pub fn battery_sample_mv(mv: u16, load_pct: u8) -> bool {
+    let load_ma = BATTERY_BASE_MA + BATTERY_PANEL_MA * load_pct.min(100) as f32 / 100.0;
+    let ocv_mv = mv as f32 + load_ma * BATTERY_INTERNAL_MOHM / 1000.0;
+    let published = critical_section::with(|cs| {
+        let mut f = BATTERY_FILTER.borrow(cs).borrow_mut();
+        let ema = match f.ema_mv {
+            Some(prev) => prev + BATTERY_EMA_ALPHA * (ocv_mv - prev),
+            None => ocv_mv,
+        };
+        f.ema_mv = Some(ema);
+        let pct = lipo_pct(ema);
+        let next = match f.shown {
+            Some(shown) if fabsf(pct - shown as f32) < 1.0 => return None,
+            _ => roundf(pct) as u8,
+        };
+        f.shown = Some(next);
+        Some(next)
+    });
+    match published {
+        Some(pct) => {
+            battery_record(pct);
+            true
+        }
+        None => false,
+    }
+}
 
-            // Clear region to background if available, else black
-            if let Some(bgdata) = bgdata {
-                let bx0 = minx.clamp(0, (RESOLUTION - 1) as i32) as usize;
-                let by0 = miny.clamp(0, (RESOLUTION - 1) as i32) as usize;
-                let bx1 = maxx.clamp(0, (RESOLUTION - 1) as i32) as usize;
-                let by1 = maxy.clamp(0, (RESOLUTION - 1) as i32) as usize;
-                let bw = RESOLUTION as usize;
-                let w = bx1 - bx0 + 1;
-                let h = by1 - by0 + 1;
-                let mut buf = alloc::vec::Vec::with_capacity(w * h * 2);
-                for row in by0..=by1 {
-                    let off = (row * bw + bx0) * 2;
-                    buf.extend_from_slice(&bgdata[off..off + w * 2]);
-                }
-                let _ = co.write_rect_fb(bx0 as u16, by0 as u16, w as u16, h as u16, &buf);
-            } else {
-                co.fill_rect_fb(minx, miny, maxx, maxy, Rgb565::BLACK);
-            }
+// Filtered, load-compensated battery percent (None before the first sample)
+pub fn battery_pct_filtered() -> Option<u8> {
+    critical_section::with(|cs| BATTERY_FILTER.borrow(cs).borrow().shown)
+}
 
-            // Draw all hands
-            // Hour hand
-            co.draw_line_fb(
-                cx,
-                cy,
-                hour_end.x,
-                hour_end.y,
-                Rgb565::WHITE,
-                hour_stroke as u8,
-            );
-            // Minute hand
-            co.draw_line_fb(
-                cx,
-                cy,
-                min_end.x,
-                min_end.y,
-                Rgb565::YELLOW,
-                min_stroke as u8,
-            );
-            // Second hand
-            co.draw_line_fb(cx, cy, sec_end.x, sec_end.y, Rgb565::CYAN, sec_stroke as u8);
-            // Center dot as solid circle
-            let r_outer: i32 = 8;
-            let r_outer2: i32 = r_outer * r_outer;
-            let c_solid = rgb565_from_888(0x52, 0xC6, 0x6B); // #52C66B
-            let x0 = cx - r_outer;
-            let y0 = cy - r_outer;
-            let x1 = cx + r_outer;
-            let y1 = cy + r_outer;
-            for yy in y0..=y1 {
-                for xx in x0..=x1 {
-                    let dx = xx - cx;
-                    let dy = yy - cy;
-                    let d2 = dx * dx + dy * dy;
-                    if d2 > r_outer2 {
-                        continue;
-                    }
-                    co.fill_rect_fb(xx, yy, xx, yy, c_solid);
-                }
-            }
+// Low-battery (warn, critical) percentages: warn dims and shows the icon, critical sleeps
+pub const BATTERY_THRESHOLD_PRESETS: [(u8, u8); 4] = [(20, 5), (30, 10), (15, 5), (10, 3)];
 
-            // Update cache
-            cache.sec = Some(sec_end);
-            cache.min = Some(min_end);
-            cache.hour = Some(hour_end);
-            (
-                (
-                    // Return clamped bbox
-                    minx.clamp(0, (RESOLUTION - 1) as i32),
-                    miny.clamp(0, (RESOLUTION - 1) as i32),
-                    maxx.clamp(0, (RESOLUTION - 1) as i32),
-                    maxy.clamp(0, (RESOLUTION - 1) as i32),
-                ),
-                (),
-            )
-        });
+pub fn battery_thresholds() -> (u8, u8) {
+    critical_section::with(|cs| *BATTERY_THRESHOLDS.borrow(cs).borrow())
+}
 
-        // Flush the affected region
-        let (minx, miny, maxx, maxy) = bbox;
-        let _ = co.flush_rect_even(minx as u16, miny as u16, maxx as u16, maxy as u16);
-        return;
-    }
+// Set (warn, critical), critical is kept at or below warn
+pub fn battery_set_thresholds(warn_pct: u8, critical_pct: u8) {
+    let warn = warn_pct.min(100);
+    let critical = critical_pct.min(warn);
+    critical_section::with(|cs| *BATTERY_THRESHOLDS.borrow(cs).borrow_mut() = (warn, critical));
+}
 
-    // Fallback: use embedded-graphics path (may flicker more).
-    draw_hand_line(disp, cx, cy, sec_end, Rgb565::RED, 2);
-    draw_hand_line(disp, cx, cy, min_end, Rgb565::GREEN, 3);
-    draw_hand_line(disp, cx, cy, hour_end, Rgb565::BLUE, 4);
+// Step to the next preset pair, return it
+pub fn battery_thresholds_cycle() -> (u8, u8) {
+    let cur = battery_thresholds();
+    let idx = BATTERY_THRESHOLD_PRESETS
+        .iter()
+        .position(|&p| p == cur)
+        .unwrap_or(0);
+    let next = BATTERY_THRESHOLD_PRESETS[(idx + 1) % BATTERY_THRESHOLD_PRESETS.len()];
+    battery_set_thresholds(next.0, next.1);
+    next
 }
 
-// Draw an annular arc directly to the panel (no framebuffer update, faster, even-aligned writes).
-fn fill_ring_arc_no_fb(
-    drv: &mut crate::display::DisplayType<'static>,
-    cx: i32,
-    cy: i32,
-    r_outer: i32,
-    r_inner: i32,
-    ang0_deg: f32,
-    ang1_deg: f32,
-    color: Rgb565,
-) -> Option<(i32, i32, i32, i32)> {
-    // Normalize angles so ang1 >= ang0 in [0, 360+)
-    let mut ang0 = ang0_deg;
-    let mut ang1 = ang1_deg;
-    while ang0 < 0.0 {
-        ang0 += 360.0;
-        ang1 += 360.0;
+// What can wake the watch from deep sleep, as a bit set (any enabled source wakes it)
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct WakeSources(u8);
+
+impl WakeSources {
+    // Button 2 (GPIO7)
+    pub const BUTTON: Self = Self(0x01);
+    // IMU wake-on-motion on INT1 (GPIO8)
+    pub const MOTION: Self = Self(0x02);
+    // Sleep timer set to the next `wake_alarm_at` time
+    pub const ALARM: Self = Self(0x04);
+    const ALL: u8 = 0x07;
+
+    pub const fn bits(self) -> u8 {
+        self.0
     }
-    while ang1 < ang0 {
-        ang1 += 360.0;
+
+    // Keeps only known bits, an empty set falls back to BUTTON so the watch can always wake
+    pub const fn from_bits(bits: u8) -> Self {
+        let bits = bits & Self::ALL;
+        if bits == 0 {
+            Self::BUTTON
+        } else {
+            Self(bits)
+        }
     }
-    if ang1 <= ang0 {
-        ang1 = ang0 + 360.0;
+
+    pub const fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
     }
 
-    // For small arcs, compute a tighter bounding box based on the arc endpoints
-    // This dramatically speeds up incremental updates
-    let arc_span = ang1 - ang0;
-    let (minx, miny, maxx, maxy) = if arc_span < 350.0 {
-        // Compute bbox from arc endpoints for BOTH inner and outer radii
-        let a0_rad = ang0.to_radians();
-        let a1_rad = ang1.to_radians();
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
 
-        let cos_a0 = cosf(a0_rad);
-        let sin_a0 = sinf(a0_rad);
-        let cos_a1 = cosf(a1_rad);
-        let sin_a1 = sinf(a1_rad);
+    pub fn label(self) -> &'static str {
+        match self.0 {
+            0x01 => "Button",
+            0x02 => "Motion",
+            0x03 => "Motion + Button",
+            0x04 => "Alarm",
+            0x05 => "Alarm + Button",
+            0x06 => "Motion + Alarm",
+            _ => "All",
+        }
+    }
+}
 
-        // Start with all 4 arc endpoints (inner/outer at start/end angles)
-        let outer_x0 = cos_a0 * r_outer as f32;
-        let outer_y0 = sin_a0 * r_outer as f32;
-        let outer_x1 = cos_a1 * r_outer as f32;
-        let outer_y1 = sin_a1 * r_outer as f32;
-        let inner_x0 = cos_a0 * r_inner as f32;
-        let inner_y0 = sin_a0 * r_inner as f32;
-        let inner_x1 = cos_a1 * r_inner as f32;
-        let inner_y1 = sin_a1 * r_inner as f32;
+pub fn wake_sources() -> WakeSources {
+    critical_section::with(|cs| *WAKE_SOURCES.borrow(cs).borrow())
+}
 
-        let mut x_min = outer_x0.min(outer_x1).min(inner_x0).min(inner_x1);
-        let mut x_max = outer_x0.max(outer_x1).max(inner_x0).max(inner_x1);
-        let mut y_min = outer_y0.min(outer_y1).min(inner_y0).min(inner_y1);
-        let mut y_max = outer_y0.max(outer_y1).max(inner_y0).max(inner_y1);
+pub fn wake_sources_set(sources: WakeSources) {
+    let sources = WakeSources::from_bits(sources.bits());
+    critical_section::with(|cs| *WAKE_SOURCES.borrow(cs).borrow_mut() = sources);
+}
 
-        // Check if arc crosses cardinal directions (0°, 90°, 180°, 270°)
-        // and extend bbox accordingly using OUTER radius
-        let check_angle = |target: f32, ang0: f32, ang1: f32| -> bool {
-            let t = if target < ang0 {
-                target + 360.0
-            } else {
-                target
-            };
-            t >= ang0 && t <= ang1
-        };
+// Step through every non-empty combination, return the new set
+pub fn wake_sources_cycle() -> WakeSources {
+    let next = WakeSources::from_bits(wake_sources().bits() % WakeSources::ALL + 1);
+    wake_sources_set(next);
+    next
+}
 
-        if check_angle(0.0, ang0, ang1) {
-            x_max = r_outer as f32;
-        } // right
-        if check_angle(90.0, ang0, ang1) {
-            y_max = r_outer as f32;
-        } // bottom
-        if check_angle(180.0, ang0, ang1) {
-            x_min = -(r_outer as f32);
-        } // left
-        if check_angle(270.0, ang0, ang1) {
-            y_min = -(r_outer as f32);
-        } // top
+pub fn wake_alarm_at() -> Option<u32> {
+    critical_section::with(|cs| *WAKE_ALARM_AT.borrow(cs).borrow())
+}
 
-        // Convert to screen coords with small padding for rounding errors
-        let pad = 2;
-        let minx = ((cx + x_min as i32 - pad).max(0)) & !1;
-        let maxx = ((cx + x_max as i32 + pad).min((RESOLUTION - 1) as i32)) | 1;
-        let miny = ((cy + y_min as i32 - pad).max(0)) & !1;
-        let maxy = ((cy + y_max as i32 + pad).min((RESOLUTION - 1) as i32)) | 1;
-        (minx, miny, maxx, maxy)
-    } else {
-        // Full ring - use full bbox
-        let minx = ((cx - r_outer).max(0)) & !1;
-        let maxx = ((cx + r_outer).min((RESOLUTION - 1) as i32)) | 1;
-        let miny = ((cy - r_outer).max(0)) & !1;
-        let maxy = ((cy + r_outer).min((RESOLUTION - 1) as i32)) | 1;
-        (minx, miny, maxx, maxy)
-    };
+// Set the alarm as seconds since midnight (wrapped into a day), or None to clear it
+pub fn wake_alarm_set(secs_of_day: Option<u32>) {
+    critical_section::with(|cs| {
+        *WAKE_ALARM_AT.borrow(cs).borrow_mut() = secs_of_day.map(|s| s % 86_400)
+    });
+}
 
-    let r2_outer = r_outer * r_outer;
-    let r2_inner = r_inner * r_inner;
+// Alarm times offered in Settings (seconds since midnight), None = off
+pub const WAKE_ALARM_PRESETS: [Option<u32>; 7] = [
+    None,
+    Some(6 * 3600),
+    Some(6 * 3600 + 1800),
+    Some(7 * 3600),
+    Some(7 * 3600 + 1800),
+    Some(8 * 3600),
+    Some(9 * 3600),
+];
+
+// Step to the next preset alarm time, return it
+pub fn wake_alarm_cycle() -> Option<u32> {
+    let cur = wake_alarm_at();
+    let idx = WAKE_ALARM_PRESETS
+        .iter()
+        .position(|&p| p == cur)
+        .unwrap_or(0);
+    let next = WAKE_ALARM_PRESETS[(idx + 1) % WAKE_ALARM_PRESETS.len()];
+    wake_alarm_set(next);
+    next
+}
 
-    let mut bb: Option<(i32, i32, i32, i32)> = None;
+// Tap navigation (knocks on the case instead of buttons) and how quickly the taps come.
+// Each cadence sets the double-tap window and how long a press must last to count as a hold.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TapCadence {
+    Off,
+    Fast,
+    Normal,
+    Slow,
+}
 
-    // Scan rows in 2-pixel bands to satisfy even-write requirement
-    for y0 in (miny..=maxy).step_by(2) {
-        let y_center = y0 + 1;
-        let dy = y_center - cy;
-        // Quick reject if outside outer radius
-        if dy * dy > r2_outer {
-            continue;
+impl TapCadence {
+    pub fn label(self) -> &'static str {
+        match self {
+            TapCadence::Off => "Off",
+            TapCadence::Fast => "Fast",
+            TapCadence::Normal => "Normal",
+            TapCadence::Slow => "Slow",
         }
-        let mut run_start: Option<i32> = None;
-        let mut run_end: i32 = 0;
-        for x0 in (minx..=maxx).step_by(2) {
-            let x_center = x0 + 1;
-            let dx = x_center - cx;
-            let d2 = dx * dx + dy * dy;
-            let inside_radial = d2 <= r2_outer && d2 >= r2_inner;
-            let inside_ang = if inside_radial {
-                let mut ang = atan2f(dy as f32, dx as f32).to_degrees();
-                if ang < 0.0 {
-                    ang += 360.0;
-                }
-                if ang < ang0 {
-                    ang += 360.0;
-                }
-                ang >= ang0 && ang <= ang1
-            } else {
-                false
-            };
+    }
 
-            if inside_ang {
-                if run_start.is_none() {
-                    run_start = Some(x0);
-                }
-                run_end = x0;
-            } else if let Some(rs) = run_start {
-                let width = (run_end - rs + 2) as u16;
-                let _ = drv.fill_rect_solid_no_fb(rs as u16, y0 as u16, width, 2, color);
-                bb = Some(match bb {
-                    None => (rs, y0, rs + width as i32 - 1, y0 + 1),
-                    Some((bx0, by0, bx1, by1)) => (
-                        bx0.min(rs),
-                        by0.min(y0),
-                        bx1.max(rs + width as i32 - 1),
-                        by1.max(y0 + 1),
-                    ),
-                });
-                run_start = None;
-            }
-        }
-        if let Some(rs) = run_start {
-            let width = (run_end - rs + 2) as u16;
-            let _ = drv.fill_rect_solid_no_fb(rs as u16, y0 as u16, width, 2, color);
-            bb = Some(match bb {
-                None => (rs, y0, rs + width as i32 - 1, y0 + 1),
-                Some((bx0, by0, bx1, by1)) => (
-                    bx0.min(rs),
-                    by0.min(y0),
-                    bx1.max(rs + width as i32 - 1),
-                    by1.max(y0 + 1),
-                ),
-            });
+    // (double-tap window, hold time) in ms, None when tap navigation is off
+    pub fn windows_ms(self) -> Option<(u32, u32)> {
+        match self {
+            TapCadence::Off => None,
+            TapCadence::Fast => Some((250, 400)),
+            TapCadence::Normal => Some((400, 600)),
+            TapCadence::Slow => Some((650, 900)),
         }
     }
-    bb
 }
 
-fn draw_ring_segment(
-    disp: &mut impl PanelRgb565,
-    cx: i32,
-    cy: i32,
-    radius: i32,
-    thickness: i32,
-    start_deg: f32,
-    end_deg: f32,
-    color: Rgb565,
-) {
-    // Draw radial lines at intervals to form ring segment
-    let step = 3.0_f32;
-    let r_inner = radius.saturating_sub(thickness.max(1) - 1);
+pub fn tap_cadence() -> TapCadence {
+    critical_section::with(|cs| *TAP_CADENCE.borrow(cs).borrow())
+}
 
-    // Fast path: draw into FB only and flush once.
-    if let Some(co) = (disp as &mut dyn Any).downcast_mut::<crate::display::DisplayType<'static>>()
-    {
-        let mut minx = i32::MAX;
-        let mut miny = i32::MAX;
-        let mut maxx = i32::MIN;
-        let mut maxy = i32::MIN;
+pub fn tap_cadence_set(c: TapCadence) {
+    critical_section::with(|cs| *TAP_CADENCE.borrow(cs).borrow_mut() = c);
+}
 
-        // Draw line and update bbox
-        let mut draw_line = |x0: i32, y0: i32, x1: i32, y1: i32| {
-            if let Some((ax0, ay0, ax1, ay1)) =
-                co.draw_line_fb(x0, y0, x1, y1, color, thickness as u8)
-            {
-                minx = minx.min(ax0 as i32);
-                miny = miny.min(ay0 as i32);
-                maxx = maxx.max(ax1 as i32);
-                maxy = maxy.max(ay1 as i32);
-            }
-        };
+pub fn tap_cadence_cycle() -> TapCadence {
+    let next = match tap_cadence() {
+        TapCadence::Off => TapCadence::Fast,
+        TapCadence::Fast => TapCadence::Normal,
+        TapCadence::Normal => TapCadence::Slow,
+        TapCadence::Slow => TapCadence::Off,
+    };
+    tap_cadence_set(next);
+    next
+}
 
-        // Draw all radial lines
-        let mut a = start_deg;
-        while a <= end_deg + 0.1 {
-            let ar = a.to_radians();
-            let ox = cx + (cosf(ar) * radius as f32) as i32;
-            let oy = cy + (sinf(ar) * radius as f32) as i32;
-            let ix = cx + (cosf(ar) * r_inner as f32) as i32;
-            let iy = cy + (sinf(ar) * r_inner as f32) as i32;
-            draw_line(ox, oy, ix, iy);
-            a += step;
-        }
+// Which wrist the watch is worn on. Right puts the encoder on the other side, so the
+// encoder direction flips and the layout is mirrored horizontally. Only positions are
+// mirrored (glyphs and images are drawn as-is), so text stays readable.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Wrist {
+    Left,
+    Right,
+}
 
-        // Flush affected region
-        if minx != i32::MAX {
-            let _ = co.flush_rect_even(
-                minx.clamp(0, (RESOLUTION - 1) as i32) as u16,
-                miny.clamp(0, (RESOLUTION - 1) as i32) as u16,
-                maxx.clamp(0, (RESOLUTION - 1) as i32) as u16,
-                maxy.clamp(0, (RESOLUTION - 1) as i32) as u16,
-            );
-        }
-    } else {
-        // Fallback: use embedded-graphics path (may flicker more).
-        let mut a = start_deg;
-        while a <= end_deg + 0.1 {
-            let ar = a.to_radians();
-            let ox = cx + (cosf(ar) * radius as f32) as i32;
-            let oy = cy + (sinf(ar) * radius as f32) as i32;
-            let ix = cx + (cosf(ar) * r_inner as f32) as i32;
-            let iy = cy + (sinf(ar) * r_inner as f32) as i32;
-            let _ = Line::new(Point::new(ox, oy), Point::new(ix, iy))
-                .into_styled(PrimitiveStyle::with_stroke(color, thickness.max(1) as u32))
-                .draw(disp);
-            a += step;
-        }
-    }
+pub fn wrist() -> Wrist {
+    critical_section::with(|cs| *WRIST.borrow(cs).borrow())
 }
 
-fn draw_brightness_ui(disp: &mut impl PanelRgb565) {
-    let pct = brightness_pct();
-    let radius = (RESOLUTION as i32 / 2) + 10;
-    let thickness_fg = 20;
-    let thickness_bg = thickness_fg + 12;
-    let radius_fg_outer = radius;
-    let radius_fg_inner = radius - thickness_fg;
-    let radius_bg_outer = radius + 2;
-    let radius_bg_inner = (radius - thickness_bg - 2).max(0);
-    let start = -90.0_f32;
-    let end_full = start + 360.0;
-    let end_pct = start + (pct as f32) * 3.6;
-    let bg_ring = Rgb565::BLACK;
-    let fg_ring = rgb565_from_888(0x9F, 0xFF, 0x4A);
+// Pick a wrist: also resets the encoder direction to match (it can still be changed after)
+pub fn wrist_set(w: Wrist) {
+    critical_section::with(|cs| *WRIST.borrow(cs).borrow_mut() = w);
+    encoder_direction_set(match w {
+        Wrist::Left => EncoderDirection::Normal,
+        Wrist::Right => EncoderDirection::Reversed,
+    });
+}
 
-    let pad = radius_bg_outer + 4;
-    let x0 = (CENTER - pad).clamp(0, (RESOLUTION - 1) as i32);
-    let x1 = (CENTER + pad).clamp(0, (RESOLUTION - 1) as i32);
-    let y0 = (CENTER - pad).clamp(0, (RESOLUTION - 1) as i32);
-    let y1 = (CENTER + pad).clamp(0, (RESOLUTION - 1) as i32);
-    // Tight text box so we don't wipe nearby graphics.
-    let text_box = (CENTER - 70, CENTER - 20, CENTER + 70, CENTER + 20);
+pub fn wrist_toggle() -> Wrist {
+    let next = match wrist() {
+        Wrist::Left => Wrist::Right,
+        Wrist::Right => Wrist::Left,
+    };
+    wrist_set(next);
+    next
+}
 
-    if let Some(co) = (disp as &mut dyn Any).downcast_mut::<crate::display::DisplayType<'static>>()
-    {
-        let prev_pct_opt = critical_section::with(|cs| *BRIGHTNESS_LAST.borrow(cs).borrow());
-        let do_full = prev_pct_opt.is_none();
-        let prev_pct = prev_pct_opt.unwrap_or(pct);
+// Left edge of a `w` wide item placed at `x`, mirrored across the panel when worn on the
+// right wrist. Pass w = 0 for a center point.
+fn layout_x(x: i32, w: i32) -> i32 {
+    match wrist() {
+        Wrist::Left => x,
+        Wrist::Right => RESOLUTION as i32 - x - w,
+    }
+}
 
-        let prev_ang = start + (prev_pct as f32) * 3.6;
-        let new_ang = start + (pct as f32) * 3.6;
+// Turn a raw detent delta into a direction-corrected one: positive means forward.
+// Every encoder consumer (menus, brightness, clock edit) should go through this.
+pub fn encoder_delta(raw_detents: i32) -> i32 {
+    match encoder_direction() {
+        EncoderDirection::Normal => -raw_detents,
+        EncoderDirection::Reversed => raw_detents,
+    }
+}
 
-        if do_full {
-            // Full redraw: background then foreground
-            let _ = fill_ring_arc_no_fb(
-                co,
-                CENTER,
-                CENTER,
-                radius_bg_outer,
-                radius_bg_inner,
-                start - 5.0,
-                end_full + 5.0,
-                bg_ring,
-            );
-            if pct > 0 {
-                let fg_end = if pct == 100 { end_full + 5.0 } else { new_ang };
-                let _ = fill_ring_arc_no_fb(
-                    co,
-                    CENTER,
-                    CENTER,
-                    radius_fg_outer,
-                    radius_fg_inner,
-                    start - 5.0,
-                    fg_end,
-                    fg_ring,
-                );
-            }
-        } else if pct != prev_pct {
-            // Incremental update - use SAME radii for both clear and paint
-            // Use the bg radii for everything to ensure consistent ring shape
-            let delta = (pct as i32) - (prev_pct as i32);
+// Motion sensor state for the Service and Info pages
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SensorHealth {
+    // Never opened (probe failed or bus down)
+    Missing,
+    Ok,
+    // Opened, but the built-in self-test response was out of bounds
+    SelfTestFailed,
+    // Opened earlier, no longer answers WHO_AM_I
+    NotResponding,
+}
 
-            if delta > 0 {
-                // GROWING: paint the new segment with fg radii
-                let fg_start = (prev_ang - 2.0).max(start - 5.0);
-                let fg_end = if pct == 100 {
-                    end_full + 5.0
-                } else {
-                    new_ang + 2.0
-                };
-                let _ = fill_ring_arc_no_fb(
-                    co,
-                    CENTER,
-                    CENTER,
-                    radius_fg_outer,
-                    radius_fg_inner,
-                    fg_start,
-                    fg_end,
-                    fg_ring,
-                );
-            } else {
-                // SHRINKING:
-                // 1. First clear the entire area from new_ang to prev_ang using bg radii
-                let clear_start = if pct == 0 { start - 5.0 } else { new_ang - 2.0 };
-                let clear_end = prev_ang + 5.0;
-                let _ = fill_ring_arc_no_fb(
-                    co,
-                    CENTER,
-                    CENTER,
-                    radius_bg_outer,
-                    radius_bg_inner,
-                    clear_start,
-                    clear_end,
-                    bg_ring,
-                );
-                // 2. Repaint the tip AND the outer/inner edges to restore clean boundary
-                if pct > 0 {
-                    // Repaint a small segment of the foreground to clean up the edge
-                    let _ = fill_ring_arc_no_fb(
-                        co,
-                        CENTER,
-                        CENTER,
-                        radius_fg_outer,
-                        radius_fg_inner,
-                        new_ang - 5.0,
-                        new_ang + 2.0,
-                        fg_ring,
-                    );
-                }
-            }
+impl SensorHealth {
+    pub fn label(self) -> &'static str {
+        match self {
+            SensorHealth::Missing => "--",
+            SensorHealth::Ok => "OK",
+            SensorHealth::SelfTestFailed => "FAIL self-test",
+            SensorHealth::NotResponding => "FAIL no reply",
         }
-
-        // Update text
-        let (tx0, ty0, tx1, ty1) = text_box;
-        co.fill_rect_fb(tx0, ty0, tx1, ty1, Rgb565::BLACK);
-        let pct_buf = alloc::format!("{}%", pct);
-        draw_text(
-            co,
-            &pct_buf,
-            fg_ring,
-            None,
-            CENTER,
-            CENTER,
-            false,
-            true,
-            Some(&FONT_10X20),
-        );
-
-        critical_section::with(|cs| {
-            *BRIGHTNESS_LAST.borrow(cs).borrow_mut() = Some(pct);
-        });
-
-        // Flush only text box
-        let fx0 = (tx0.clamp(0, (RESOLUTION - 1) as i32)) & !1;
-        let fy0 = (ty0.clamp(0, (RESOLUTION - 1) as i32)) & !1;
-        let fx1 = (tx1.clamp(0, (RESOLUTION - 1) as i32) | 1).min((RESOLUTION - 1) as i32);
-        let fy1 = (ty1.clamp(0, (RESOLUTION - 1) as i32) | 1).min((RESOLUTION - 1) as i32);
-        let _ = co.flush_rect_even(fx0 as u16, fy0 as u16, fx1 as u16, fy1 as u16);
-    } else {
-        // Fallback: small clear and redraw (non-panel path).
-        let _ = Rectangle::new(
-            Point::new(x0, y0),
-            Size::new((x1 - x0 + 1) as u32, (y1 - y0 + 1) as u32),
-        )
-        .into_styled(PrimitiveStyle::with_fill(Rgb565::BLACK))
-        .draw(disp);
-        draw_ring_segment(
-            disp,
-            CENTER,
-            CENTER,
-            radius,
-            thickness_bg,
-            start,
-            end_full,
-            bg_ring,
-        );
-        draw_ring_segment(
-            disp,
-            CENTER,
-            CENTER,
-            radius,
-            thickness_bg,
-            start,
-            end_pct,
-            fg_ring,
-        );
-        draw_ring_segment(
-            disp,
-            CENTER,
-            CENTER,
-            radius,
-            thickness_fg,
-            start,
-            end_pct,
-            fg_ring,
-        );
-        // Text: redraw center text in fallback mode
-        let pct_buf = alloc::format!("{}%", pct);
-        draw_text(
-            disp,
-            &pct_buf,
-            fg_ring,
-            None,
-            CENTER,
-            CENTER - 8,
-            false,
-            true,
-            Some(&FONT_10X20),
-        );
     }
 }
 
-fn draw_transform_overlay(disp: &mut impl PanelRgb565) {
-    // DNA-like helix animation with depth sorting for proper 3D illusion
-    let t = clock_now_seconds_f32() * 1.6; // slower rotation for better 3D illusion
-    let amp_max = (RESOLUTION as f32) * 0.26;
-    let step = 16; // slightly tighter spacing for smoother curve
-    let cx = CENTER;
-    let y_start = 12;
-    let y_end = RESOLUTION as i32 - 12;
-
-    // Front/back color pairs with more contrast for depth
-    let strand_a_front = rgb565_from_888(0xC0, 0xFF, 0x70); // brighter front
-    let strand_a_back = rgb565_from_888(0x40, 0x90, 0x10); // darker back
-    let strand_b_front = rgb565_from_888(0xA8, 0xFF, 0x50);
-    let strand_b_back = rgb565_from_888(0x38, 0x80, 0x08);
-    let rung_front = rgb565_from_888(0xB0, 0xFF, 0x60);
-    let rung_back = rgb565_from_888(0x50, 0x90, 0x18);
+static SENSOR_HEALTH: Mutex<RefCell<SensorHealth>> =
+    Mutex::new(RefCell::new(SensorHealth::Missing));
 
-    // Base thickness values - will be modulated by depth
-    let strand_thick_base = 6u8;
-    let rung_thick = 3u8;
+pub fn sensor_health() -> SensorHealth {
+    critical_section::with(|cs| *SENSOR_HEALTH.borrow(cs).borrow())
+}
 
-    // Bounding box for the helix drawing (reuse for clear/flush).
-    let pad = (amp_max as i32 + 20).min(CENTER);
-    let x0 = (cx - pad).clamp(0, (RESOLUTION - 1) as i32);
-    let x1 = (cx + pad).clamp(0, (RESOLUTION - 1) as i32);
-    let y0 = (y_start - 8).clamp(0, (RESOLUTION - 1) as i32);
-    let y1 = (y_end + 8).clamp(0, (RESOLUTION - 1) as i32);
+pub fn sensor_health_set(health: SensorHealth) {
+    critical_section::with(|cs| *SENSOR_HEALTH.borrow(cs).borrow_mut() = health);
+}
 
-    if let Some(co) = (disp as &mut dyn Any).downcast_mut::<crate::display::DisplayType<'static>>()
-    {
-        // Clear only the helix region in the framebuffer each frame.
-        co.fill_rect_fb(x0, y0, x1, y1, Rgb565::BLACK);
+// Record a new temperature reading (Celsius), updating min/max and the dirty flag
+pub fn temperature_record(value_c: f32) {
+    critical_section::with(|cs| {
+        let mut stats = TEMP_STATS.borrow(cs).borrow_mut();
+        // Only mark dirty when the displayed tenth changes
+        let changed = match stats.last {
+            Some(prev) => (prev * 10.0) as i32 != (value_c * 10.0) as i32,
+            None => true,
+        };
+        stats.last = Some(value_c);
+        stats.min = stats.min.min(value_c);
+        stats.max = stats.max.max(value_c);
+        if changed {
+            *TEMP_DIRTY.borrow(cs).borrow_mut() = true;
+        }
+    });
+}
 
-        // Collect strand segments for depth-sorted drawing
-        // (y_pos, depth, is_strand_a, prev_point, curr_point)
-        let mut segments: heapless::Vec<(i32, f32, bool, Point, Point), 64> = heapless::Vec::new();
+// Current, min and max temperature in Celsius, if any reading has been recorded
+pub fn temperature_stats() -> Option<(f32, f32, f32)> {
+    critical_section::with(|cs| {
+        let stats = *TEMP_STATS.borrow(cs).borrow();
+        stats.last.map(|v| (v, stats.min, stats.max))
+    })
+}
 
-        // Collect rungs with depth info for proper front/back coloring
-        // (y_pos, depth, point_a, point_b, is_front)
-        let mut rungs: heapless::Vec<(i32, f32, Point, Point, bool), 32> = heapless::Vec::new();
+// Restart min/max tracking from the last reading
+pub fn temperature_reset_minmax() {
+    critical_section::with(|cs| {
+        let mut stats = TEMP_STATS.borrow(cs).borrow_mut();
+        match stats.last {
+            Some(v) => {
+                stats.min = v;
+                stats.max = v;
+            }
+            None => *stats = TempStats::new(),
+        }
+        *TEMP_DIRTY.borrow(cs).borrow_mut() = true;
+    });
+}
 
-        let mut prev_a: Option<Point> = None;
-        let mut prev_b: Option<Point> = None;
+// Take and clear the temperature dirty flag
+pub fn temperature_take_dirty() -> bool {
+    critical_section::with(|cs| {
+        let mut d = TEMP_DIRTY.borrow(cs).borrow_mut();
+        let was = *d;
+        *d = false;
+        was
+    })
+}
 
-        // Generate strand points
-        for (i, y) in (y_start..=y_end).step_by(step).enumerate() {
-            let phase = t + (i as f32) * 0.32;
-            let amp = amp_max * 0.75;
+// Smash gravity calibration progress (0..=100) for the calibration page, fed by main.rs
+pub fn smash_cal_report(pct: u8) {
+    critical_section::with(|cs| {
+        let mut cur = SMASH_CAL_PROGRESS.borrow(cs).borrow_mut();
+        if *cur != Some(pct) {
+            *cur = Some(pct);
+            *SMASH_CAL_DIRTY.borrow(cs).borrow_mut() = true;
+        }
+    });
+}
 
-            let off_a = (sinf(phase) * amp) as i32;
-            let off_b = -off_a;
+// Take and clear the calibration dirty flag
+pub fn smash_cal_take_dirty() -> bool {
+    critical_section::with(|cs| {
+        let mut d = SMASH_CAL_DIRTY.borrow(cs).borrow_mut();
+        let was = *d;
+        *d = false;
+        was
+    })
+}
 
-            let xa = cx + off_a;
-            let xb = cx + off_b;
-            let pa = Point::new(xa, y);
-            let pb = Point::new(xb, y);
+// Glyphs in the status icon atlas, value is the cell index (row-major)
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum StatusIcon {
+    BatteryFull = 0,
+    BatteryHalf = 1,
+    BatteryLow = 2,
+    Bluetooth = 3,
+    Alarm = 4,
+    ClockWarning = 5,
+    Thermometer = 6,
+    Moon = 7,
+}
 
-            // Depth value: cosf gives z-depth (-1 = back, +1 = front)
-            let depth_a = cosf(phase);
-            // let depth_b = -depth_a;
+impl StatusIcon {
+    // Left-to-right order in the Home status row
+    const ALL: [StatusIcon; 8] = [
+        StatusIcon::ClockWarning,
+        StatusIcon::Alarm,
+        StatusIcon::Bluetooth,
+        StatusIcon::Thermometer,
+        StatusIcon::Moon,
+        StatusIcon::BatteryLow,
+        StatusIcon::BatteryHalf,
+        StatusIcon::BatteryFull,
+    ];
+}
 
-            if let (Some(pa_prev), Some(pb_prev)) = (prev_a, prev_b) {
-                let prev_phase = t + ((i - 1) as f32) * 0.32;
-                let avg_depth_a = (depth_a + cosf(prev_phase)) / 2.0;
-                let avg_depth_b = -avg_depth_a;
+// Show or hide an icon in the Home status row
+pub fn status_icon_set(icon: StatusIcon, on: bool) {
+    let bit = 1u8 << (icon as u8);
+    critical_section::with(|cs| {
+        let mut flags = STATUS_FLAGS.borrow(cs).borrow_mut();
+        let next = if on { *flags | bit } else { *flags & !bit };
+        if next != *flags {
+            *flags = next;
+            *STATUS_DIRTY.borrow(cs).borrow_mut() = true;
+        }
+    });
+}
 
-                let _ = segments.push((y, avg_depth_a, true, pa_prev, pa));
-                let _ = segments.push((y, avg_depth_b, false, pb_prev, pb));
-            }
+pub fn status_icon_active(icon: StatusIcon) -> bool {
+    critical_section::with(|cs| *STATUS_FLAGS.borrow(cs).borrow() & (1u8 << (icon as u8)) != 0)
+}
 
-            // Draw rungs at fixed Y intervals
-            if i % 3 == 1 {
-                // Rung visibility based on rotation: when strands are at edges (|sinf| high),
-                // the rung is facing us or away. When |sinf| is low, rung is on the side.
-                // Use cosf to determine if rung faces front or back
-                let rung_facing_front = cosf(phase).abs() < 0.7; // rung visible when strands near edges
-                let rung_depth = if rung_facing_front { 0.1 } else { -0.5 };
-                let _ = rungs.push((y, rung_depth, pa, pb, rung_facing_front));
-            }
+// Returns true once after the set of visible status icons changed
+pub fn status_take_dirty() -> bool {
+    critical_section::with(|cs| {
+        let mut d = STATUS_DIRTY.borrow(cs).borrow_mut();
+        let was = *d;
+        *d = false;
+        was
+    })
+}
 
-            prev_a = Some(pa);
-            prev_b = Some(pb);
-        }
+// Format a Celsius value as e.g. "-12.3°C" / "98.6°F" into the provided buffer.
+// The degree sign is UTF-8, so draw the result with a Latin-1 font.
+pub fn format_temperature(value_c: f32, unit: TempUnit, buf: &mut [u8; 10]) -> &str {
+    let (v, suffix) = match unit {
+        TempUnit::Celsius => (value_c, b'C'),
+        TempUnit::Fahrenheit => (value_c * 9.0 / 5.0 + 32.0, b'F'),
+    };
+    // Round to tenths and clamp to what fits in the buffer
+    let tenths = (v * 10.0 + if v >= 0.0 { 0.5 } else { -0.5 }) as i32;
+    let tenths = tenths.clamp(-9_999, 9_999);
+    let mag = tenths.unsigned_abs();
+    let whole = mag / 10;
+
+    let mut n = 0;
+    if tenths < 0 {
+        buf[n] = b'-';
+        n += 1;
+    }
+    if whole >= 100 {
+        buf[n] = b'0' + (whole / 100) as u8;
+        n += 1;
+    }
+    if whole >= 10 {
+        buf[n] = b'0' + ((whole / 10) % 10) as u8;
+        n += 1;
+    }
+    buf[n] = b'0' + (whole % 10) as u8;
+    buf[n + 1] = b'.';
+    buf[n + 2] = b'0' + (mag % 10) as u8;
+    buf[n + 3] = 0xC2; // U+00B0 degree sign
+    buf[n + 4] = 0xB0;
+    buf[n + 5] = suffix;
+    n += 6;
+
+    core::str::from_utf8(&buf[..n]).unwrap_or("--")
+}
 
-        // Sort strands by depth (back-to-front)
-        for i in 0..segments.len() {
-            for j in 0..segments.len().saturating_sub(1 + i) {
-                if segments[j].1 > segments[j + 1].1 {
-                    segments.swap(j, j + 1);
-                }
-            }
-        }
+// Get the current clock time in seconds since epoch (for saving before deep sleep)
+pub fn get_clock_seconds() -> u64 {
+    clock_now_seconds()
+}
 
-        // Sort rungs by depth too
-        for i in 0..rungs.len() {
-            for j in 0..rungs.len().saturating_sub(1 + i) {
-                if rungs[j].1 > rungs[j + 1].1 {
-                    rungs.swap(j, j + 1);
-                }
-            }
-        }
+// Forget where the analog hands were drawn and repaint the face on the next frame.
+// Call after drawing anything over the watch region (e.g. a notification) so no hand ghosts remain.
+pub fn invalidate_watch_hands() {
+    critical_section::with(|cs| {
+        *HAND_CACHE.borrow(cs).borrow_mut() = HandCache::new();
+        *WATCH_FACE_DIRTY.borrow(cs).borrow_mut() = true;
+    });
+}
 
-        // Interleave drawing: back rungs, back strands, front rungs, front strands
-        // Draw back rungs first
-        for &(_y, depth, pa, pb, is_front) in rungs.iter() {
-            if depth < 0.0 {
-                let col = if is_front { rung_front } else { rung_back };
-                let _ = co.draw_line_fb(pa.x, pa.y, pb.x, pb.y, col, rung_thick);
-            }
+// Clear all cached assets and state (call after waking from deep sleep)
+pub fn clear_all_caches() {
+    critical_section::with(|cs| {
+        // Clear asset cache
+        let mut assets = ASSETS.borrow(cs).borrow_mut();
+        for slot in assets.iter_mut() {
+            slot.data = None;
+            slot.w = 0;
+            slot.h = 0;
         }
 
-        // Draw sorted strand segments (back ones first due to sorting)
-        for &(_y, depth, is_a, p_prev, p_curr) in segments.iter() {
-            let depth_factor = (depth + 1.0) / 2.0;
-            let strand_thick = ((strand_thick_base as f32) * (0.5 + 0.7 * depth_factor)) as u8;
-            let strand_thick = strand_thick.max(3).min(9);
+        // Clear page tracking
+        *LAST_PAGE_KIND.borrow(cs).borrow_mut() = None;
+        *LAST_OMNI_TRANSFORM_ACTIVE.borrow(cs).borrow_mut() = false;
+        *NAV_HISTORY.borrow(cs).borrow_mut() = Vec::new();
+        *LAST_WATCH_STATE.borrow(cs).borrow_mut() = None;
+        *CLOCK_EDIT.borrow(cs).borrow_mut() = None;
+        *LAST_WATCH_EDIT_ACTIVE.borrow(cs).borrow_mut() = false;
+        *HAND_CACHE.borrow(cs).borrow_mut() = HandCache::new();
+        *DIGIT_ROLL.borrow(cs).borrow_mut() = DigitRoll::new();
+        *DATE_LINE_SHOWN.borrow(cs).borrow_mut() = None;
+        *CLOCK_BADGE_SHOWN.borrow(cs).borrow_mut() = false;
+        *WATCH_BG.borrow(cs).borrow_mut() = None;
+        *WATCH_FACE_DIRTY.borrow(cs).borrow_mut() = false;
+        *LAST_TRANSFORM_ACTIVE.borrow(cs).borrow_mut() = false;
+        *TRANSFORM_BG.borrow(cs).borrow_mut() = None;
+        *BRIGHTNESS_LAST.borrow(cs).borrow_mut() = None;
+        *LAST_SETTINGS_STATE.borrow(cs).borrow_mut() = None;
+        *BRIGHTNESS_DIRTY.borrow(cs).borrow_mut() = false;
+    });
+}
 
-            let front_side = depth >= 0.0;
+fn clock_now_seconds() -> u64 {
+    // Get current software clock time in seconds since epoch
+    let (base_secs, base_ticks) = clock_base();
+    let now = SystemTimer::unit_value(Unit::Unit0);
+    let tps = SystemTimer::ticks_per_second();
+    let elapsed = now.saturating_sub(base_ticks) / tps;
+    base_secs.saturating_add(elapsed)
+}
 
-            let (col_main, col_shadow) = if is_a {
-                if front_side {
-                    (strand_a_front, rgb565_from_888(0x70, 0xB0, 0x30))
-                } else {
-                    (strand_a_back, rgb565_from_888(0x28, 0x60, 0x08))
-                }
-            } else {
-                if front_side {
-                    (strand_b_front, rgb565_from_888(0x60, 0xA0, 0x28))
-                } else {
-                    (strand_b_back, rgb565_from_888(0x20, 0x50, 0x04))
-                }
-            };
+pub fn clock_now_seconds_u32() -> u32 {
+    clock_now_seconds() as u32
+}
 
-            let _ = co.draw_line_fb(
-                p_prev.x,
-                p_prev.y,
-                p_curr.x,
-                p_curr.y,
-                col_shadow,
-                strand_thick + 2,
-            );
-            let _ = co.draw_line_fb(
-                p_prev.x,
-                p_prev.y,
-                p_curr.x,
-                p_curr.y,
-                col_main,
-                strand_thick,
-            );
+fn clock_now_seconds_f32() -> f32 {
+    // Get current software clock time in seconds since epoch as f32
+    let (base_secs, base_ticks) = clock_base();
+    let now = SystemTimer::unit_value(Unit::Unit0);
+    let tps = SystemTimer::ticks_per_second() as u64;
+    let elapsed_ticks = now.saturating_sub(base_ticks);
+    let whole = elapsed_ticks / tps;
+    let frac = (elapsed_ticks % tps) as f32 / tps as f32;
+    // Work modulo 24h to preserve sub-second precision even with large epoch seconds.
+    let total = base_secs + whole;
+    let within_day = (total % 86_400) as f32;
+    within_day + frac
+}
+
+// Return hours, minutes, seconds as f32 with good precision by working modulo 12h.
+fn clock_now_hms_f32() -> (f32, f32, f32) {
+    let (base_secs, base_ticks) = clock_base();
+    let now = SystemTimer::unit_value(Unit::Unit0);
+    let tps = SystemTimer::ticks_per_second() as u64;
+    let elapsed_ticks = now.saturating_sub(base_ticks);
+    let whole = elapsed_ticks / tps;
+    let frac = (elapsed_ticks % tps) as f32 / tps as f32;
+    let total = base_secs + whole;
+    let s = (total % 60) as f32 + frac;
+    let m_total = total / 60;
+    let m = (m_total % 60) as f32 + s / 60.0;
+    let h_total = m_total / 60;
+    let h = (h_total % 12) as f32 + m / 60.0;
+    (h, m, s)
+}
+
+// Stopwatch on its own SystemTimer tick base, so changing the wall clock mid-run
+// (`set_clock_seconds`, serial SET, watch edit) doesn't affect it.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Stopwatch {
+    // Ticks accumulated by earlier start/stop runs
+    acc_ticks: u64,
+    // Tick of the current start while running
+    started_at: Option<u64>,
+}
+
+impl Stopwatch {
+    pub const fn new() -> Self {
+        Self {
+            acc_ticks: 0,
+            started_at: None,
         }
+    }
 
-        // Draw front rungs last (on top of strands)
-        for &(_y, depth, pa, pb, is_front) in rungs.iter() {
-            if depth >= 0.0 {
-                let col = if is_front { rung_front } else { rung_back };
-                let _ = co.draw_line_fb(pa.x, pa.y, pb.x, pb.y, col, rung_thick);
-            }
+    pub fn start(&mut self) {
+        if self.started_at.is_none() {
+            self.started_at = Some(SystemTimer::unit_value(Unit::Unit0));
         }
+    }
 
-        // Flush only the helix region to avoid needless panel churn.
-        let _ = co.flush_rect_even(x0 as u16, y0 as u16, x1 as u16, y1 as u16);
-    } else {
-        // Fallback path using embedded-graphics primitives.
-        let strand_thick = strand_thick_base; // use base thickness for fallback
-        let _ = Rectangle::new(
-            Point::new(x0, y0),
-            Size::new((x1 - x0 + 1) as u32, (y1 - y0 + 1) as u32),
-        )
-        .into_styled(PrimitiveStyle::with_fill(Rgb565::BLACK))
-        .draw(disp);
-        let mut prev_a: Option<Point> = None;
-        let mut prev_b: Option<Point> = None;
+    pub fn stop(&mut self) {
+        if let Some(t0) = self.started_at.take() {
+            let now = SystemTimer::unit_value(Unit::Unit0);
+            self.acc_ticks = self.acc_ticks.saturating_add(now.saturating_sub(t0));
+        }
+    }
 
-        // Draw helix strands
-        for (i, y) in (y_start..=y_end).step_by(step).enumerate() {
-            let phase = t + (i as f32) * 0.35;
-            let amp = amp_max * 0.75;
-            let off = (sinf(phase) * amp) as i32;
-            let xa = cx + off;
-            let xb = cx - off;
-            let pa = Point::new(xa, y);
-            let pb = Point::new(xb, y);
-            let front_side = sinf(phase) >= 0.0;
+    // Clear the elapsed time; a running stopwatch keeps running from zero
+    pub fn reset(&mut self) {
+        self.acc_ticks = 0;
+        if self.started_at.is_some() {
+            self.started_at = Some(SystemTimer::unit_value(Unit::Unit0));
+        }
+    }
 
-            // Choose colors based on front/back
-            let col_a = if front_side {
-                strand_a_front
-            } else {
-                strand_a_back
-            };
-            let col_b = if front_side {
-                strand_b_back
-            } else {
-                strand_b_front
-            };
-            let col_a_sh = rgb565_from_888(
-                (col_a.r().saturating_mul(3) / 4) as u8,
-                (col_a.g().saturating_mul(3) / 4) as u8,
-                (col_a.b().saturating_mul(3) / 4) as u8,
-            );
-            let col_b_sh = rgb565_from_888(
-                (col_b.r().saturating_mul(3) / 4) as u8,
-                (col_b.g().saturating_mul(3) / 4) as u8,
-                (col_b.b().saturating_mul(3) / 4) as u8,
-            );
+    pub fn is_running(&self) -> bool {
+        self.started_at.is_some()
+    }
 
-            // Connect strands smoothly
-            if let Some(p) = prev_a {
-                let _ = Line::new(p, pa)
-                    .into_styled(PrimitiveStyle::with_stroke(col_a_sh, strand_thick.into()))
-                    .draw(disp);
-                let _ = Line::new(p, pa)
-                    .into_styled(PrimitiveStyle::with_stroke(
-                        col_a,
-                        strand_thick.saturating_sub(2).into(),
-                    ))
-                    .draw(disp);
-            }
+    pub fn elapsed_ticks(&self) -> u64 {
+        let running = self
+            .started_at
+            .map(|t0| SystemTimer::unit_value(Unit::Unit0).saturating_sub(t0))
+            .unwrap_or(0);
+        self.acc_ticks.saturating_add(running)
+    }
 
-            // Connect strands smoothly
-            if let Some(p) = prev_b {
-                let _ = Line::new(p, pb)
-                    .into_styled(PrimitiveStyle::with_stroke(col_b_sh, strand_thick.into()))
-                    .draw(disp);
-                let _ = Line::new(p, pb)
-                    .into_styled(PrimitiveStyle::with_stroke(
-                        col_b,
-                        strand_thick.saturating_sub(2).into(),
-                    ))
-                    .draw(disp);
-            }
-
-            // Curved rung: bend slightly using a midpoint offset for a faux spin effect.
-            let mid_phase = phase + core::f32::consts::FRAC_PI_2;
-            let mid_bend = (sinf(mid_phase) * amp * 0.18) as i32;
-            let mid_x = cx + mid_bend;
-            let mid_y = y + step as i32 / 2;
-            let pm = Point::new(mid_x, mid_y);
-            let col_rung = if front_side { rung_front } else { rung_back };
-
-            // Draw two segments to form a bent rung
-            let _ = Line::new(pa, pm)
-                .into_styled(PrimitiveStyle::with_stroke(col_rung, rung_thick.into()))
-                .draw(disp);
-            let _ = Line::new(pm, pb)
-                .into_styled(PrimitiveStyle::with_stroke(col_rung, rung_thick.into()))
-                .draw(disp);
-
-            prev_a = Some(pa);
-            prev_b = Some(pb);
-        }
+    // Elapsed time in centiseconds (10 ms)
+    pub fn elapsed_cs(&self) -> u64 {
+        let tps = SystemTimer::ticks_per_second();
+        // split to avoid overflowing ticks * 100 on long runs
+        let ticks = self.elapsed_ticks();
+        (ticks / tps) * 100 + (ticks % tps) * 100 / tps
     }
 }
 
-fn draw_clock_edit(disp: &mut impl PanelRgb565, ed: ClockEditState) {
-    // Build HH:MM string from digits
-    let mut buf = [b'0'; 5];
-    buf[0] = b'0' + ed.digits[0];
-    buf[1] = b'0' + ed.digits[1];
-    buf[2] = b':';
-    buf[3] = b'0' + ed.digits[2];
-    buf[4] = b'0' + ed.digits[3];
-    let msg = core::str::from_utf8(&buf).unwrap_or("00:00");
-
-    let font = &FONT_10X20; // largest built-in mono ASCII font available
-
-    // Draw the time (use larger 10x20 font)
-    draw_text(
-        disp,
-        msg,
-        Rgb565::CYAN,
-        Some(Rgb565::BLACK),
-        CENTER,
-        CENTER,
-        false,
-        true,
-        Some(font),
-    );
-
-    // Underline the active digit only (skip the colon)
-    let char_w = font.character_size.width as i32;
-    let char_h = font.character_size.height as i32;
-    let chars_total = 5;
-    let box_w = char_w * chars_total;
-    let start_x = CENTER - box_w / 2;
-    let base_y = CENTER + char_h / 2 + 2;
-    let idx = ed.idx.min(3) as i32;
-    let visual_idx = if idx >= 2 { idx + 1 } else { idx }; // skip colon slot
-    let underline_x = start_x + visual_idx * char_w;
-
-    // Draw underline rectangle
-    let rect = Rectangle::new(Point::new(underline_x, base_y), Size::new(char_w as u32, 2));
-    rect.into_styled(PrimitiveStyle::with_fill(Rgb565::CYAN))
-        .draw(disp)
-        .ok();
+impl Default for Stopwatch {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
-fn ensure_watch_background_loaded() -> bool {
-    // Decompress watch background into PSRAM if not already done
-    critical_section::with(|cs| {
-        if WATCH_BG.borrow(cs).borrow().is_some() {
-            return true;
-        }
+// States for Settings Menu
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SettingsMenuState {
+    BrightnessPrompt,
+    BrightnessAdjust,
+    BrightnessLimits,
+    TempUnit,
+    Carousel,
+    Animations,
+    ColonBlink,
+    ClockFormat,
+    ClockSeconds,
+    DateFormat,
+    EncoderDir,
+    Wrist,
+    AutoRotate,
+    AlwaysOn,
+    ScreenTimeout,
+    LowBattery,
+    WakeSource,
+    WakeAlarm,
+    TapNav,
+    SmashCal,
+    EasterEgg,
+}
 
-        // Decompress now
-        if let Ok(decompressed) = decompress_to_vec_zlib_with_limit(
-            WATCH_BG_IMAGE,
-            (RESOLUTION * RESOLUTION * 2) as usize,
-        ) {
-            *WATCH_BG.borrow(cs).borrow_mut() = Some(decompressed);
-            true
-        } else {
-            false
-        }
-    })
+// States for Omnitrix Menu
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum OmnitrixState {
+    Alien1,
+    Alien2,
+    Alien3,
+    Alien4,
+    Alien5,
+    Alien6,
+    Alien7,
+    Alien8,
+    Alien9,
+    Alien10,
 }
 
-// Draw from already-decompressed bytes (used by cache on OLED)
-pub fn draw_image_bytes(
-    disp: &mut impl PanelRgb565,
-    bytes: &[u8],
-    w: u32,
-    h: u32,
-    clear: bool,
-    update_fb: bool,
-) {
-    // Clear background if requested
-    if clear {
-        if !update_fb {
-            if let Some(co) =
-                (disp as &mut dyn Any).downcast_mut::<crate::display::DisplayType<'static>>()
-            {
-                let _ = co.fill_rect_solid_no_fb(
-                    0,
-                    0,
-                    RESOLUTION as u16,
-                    RESOLUTION as u16,
-                    Rgb565::BLACK,
-                );
-            } else {
-                let _ = disp.clear(Rgb565::BLACK);
-            }
-        } else {
-            let _ = disp.clear(Rgb565::BLACK);
+impl UiState {
+    // Move to the next item/state in the current layer (rotary CW)
+    pub fn next_item(self) -> Self {
+        if self.dialog.is_some() {
+            return self;
         }
-    }
-    // Validate size
-    if bytes.len() != (w * h * 2) as usize {
-        return;
-    }
-    let x = (RESOLUTION.saturating_sub(w)) as i32 / 2;
-    let y = (RESOLUTION.saturating_sub(h)) as i32 / 2;
-
-    // Try fast raw blit if this really is the CO5300 driver (DMA or non-DMA alias).
-    // The display backend re-exports its concrete type as display::DisplayType.
-    if let Some(co) = (disp as &mut dyn Any).downcast_mut::<crate::display::DisplayType<'static>>()
-    {
-        let res = if update_fb {
-            co.blit_rect_be_fast(x as u16, y as u16, w as u16, h as u16, bytes)
-        } else {
-            co.blit_rect_be_fast_no_fb(x as u16, y as u16, w as u16, h as u16, bytes)
+        let next_page = match self.page {
+            Page::Main(state) => {
+                let next = match state {
+                    MainMenuState::Home => MainMenuState::WatchApp,
+                    MainMenuState::WatchApp => MainMenuState::TempApp,
+                    MainMenuState::TempApp => MainMenuState::FlashlightApp,
+                    MainMenuState::FlashlightApp => MainMenuState::LauncherApp,
+                    MainMenuState::LauncherApp => MainMenuState::SettingsApp,
+                    MainMenuState::SettingsApp => MainMenuState::Home,
+                };
+                Page::Main(next)
+            }
+            Page::Watch(state) => {
+                let next = match state {
+                    WatchAppState::Analog => WatchAppState::Digital,
+                    WatchAppState::Digital => WatchAppState::Analog,
+                };
+                Page::Watch(next)
+            }
+            Page::Settings(state) => {
+                let next = match state {
+                    SettingsMenuState::BrightnessPrompt => SettingsMenuState::BrightnessLimits,
+                    SettingsMenuState::BrightnessLimits => SettingsMenuState::TempUnit,
+                    SettingsMenuState::TempUnit => SettingsMenuState::Carousel,
+                    SettingsMenuState::Carousel => SettingsMenuState::Animations,
+                    SettingsMenuState::Animations => SettingsMenuState::ColonBlink,
+                    SettingsMenuState::ColonBlink => SettingsMenuState::ClockFormat,
+                    SettingsMenuState::ClockFormat => SettingsMenuState::ClockSeconds,
+                    SettingsMenuState::ClockSeconds => SettingsMenuState::DateFormat,
+                    SettingsMenuState::DateFormat => SettingsMenuState::EncoderDir,
+                    SettingsMenuState::EncoderDir => SettingsMenuState::Wrist,
+                    SettingsMenuState::Wrist => SettingsMenuState::AutoRotate,
+                    SettingsMenuState::AutoRotate => SettingsMenuState::AlwaysOn,
+                    SettingsMenuState::AlwaysOn => SettingsMenuState::ScreenTimeout,
+                    SettingsMenuState::ScreenTimeout => SettingsMenuState::LowBattery,
+                    SettingsMenuState::LowBattery => SettingsMenuState::WakeSource,
+                    SettingsMenuState::WakeSource => SettingsMenuState::WakeAlarm,
+                    SettingsMenuState::WakeAlarm => SettingsMenuState::TapNav,
+                    SettingsMenuState::TapNav => SettingsMenuState::SmashCal,
+                    SettingsMenuState::SmashCal => SettingsMenuState::EasterEgg,
+                    SettingsMenuState::EasterEgg => SettingsMenuState::BrightnessPrompt,
+                    SettingsMenuState::BrightnessAdjust => SettingsMenuState::BrightnessAdjust,
+                };
+                Page::Settings(next)
+            }
+            Page::Omnitrix(state) => {
+                let next = match state {
+                    OmnitrixState::Alien1 => OmnitrixState::Alien2,
+                    OmnitrixState::Alien2 => OmnitrixState::Alien3,
+                    OmnitrixState::Alien3 => OmnitrixState::Alien4,
+                    OmnitrixState::Alien4 => OmnitrixState::Alien5,
+                    OmnitrixState::Alien5 => OmnitrixState::Alien6,
+                    OmnitrixState::Alien6 => OmnitrixState::Alien7,
+                    OmnitrixState::Alien7 => OmnitrixState::Alien8,
+                    OmnitrixState::Alien8 => OmnitrixState::Alien9,
+                    OmnitrixState::Alien9 => OmnitrixState::Alien10,
+                    OmnitrixState::Alien10 => OmnitrixState::Alien1,
+                };
+                Page::Omnitrix(next)
+            }
+            Page::EasterEgg => Page::EasterEgg,
+            Page::Temperature => Page::Temperature,
+            Page::Flashlight => Page::Flashlight,
+            Page::SmashCalibration => Page::SmashCalibration,
+            Page::Service => Page::Service,
+            Page::Launcher(i) => Page::Launcher((i + 1) % Page::ALL.len() as u8),
         };
-        if let Err(e) = res {
-            esp_println::println!("fast blit failed: {:?}; fallback", e);
-            let raw = ImageRawBE::<Rgb565>::new(bytes, w);
-            let _ = Image::new(&raw, Point::new(x, y)).draw(disp);
+        Self {
+            page: next_page,
+            dialog: None,
         }
-    } else {
-        let raw = ImageRawBE::<Rgb565>::new(bytes, w);
-        let _ = Image::new(&raw, Point::new(x, y)).draw(disp);
-    }
-}
-
-// Map asset id to cache slot index, dimensions, and compressed blob
-fn asset_meta(id: AssetId) -> (usize, u32, u32, &'static [u8]) {
-    match id {
-        AssetId::Alien1 => (0, 308, 374, ALIEN1_IMAGE),
-        AssetId::Alien2 => (1, 308, 374, ALIEN2_IMAGE),
-        AssetId::Alien3 => (2, 308, 374, ALIEN3_IMAGE),
-        AssetId::Alien4 => (3, 308, 374, ALIEN4_IMAGE),
-        AssetId::Alien5 => (4, 308, 374, ALIEN5_IMAGE),
-        AssetId::Alien6 => (5, 308, 374, ALIEN6_IMAGE),
-        AssetId::Alien7 => (6, 308, 374, ALIEN7_IMAGE),
-        AssetId::Alien8 => (7, 308, 374, ALIEN8_IMAGE),
-        AssetId::Alien9 => (8, 308, 374, ALIEN9_IMAGE),
-        AssetId::Alien10 => (9, 308, 374, ALIEN10_IMAGE),
-        AssetId::Logo => (10, 466, 466, ALIEN_LOGO),
-        AssetId::InfoPage => (11, 466, 466, INFO_PAGE_IMAGE),
-        AssetId::SettingsImage => (12, 400, 344, SETTINGS_IMAGE),
-        AssetId::WatchIcon => (13, 316, 316, WATCH_ICON_IMAGE),
-    }
-}
-
-fn asset_id_for_state(s: OmnitrixState) -> AssetId {
-    match s {
-        OmnitrixState::Alien1 => AssetId::Alien1,
-        OmnitrixState::Alien2 => AssetId::Alien2,
-        OmnitrixState::Alien3 => AssetId::Alien3,
-        OmnitrixState::Alien4 => AssetId::Alien4,
-        OmnitrixState::Alien5 => AssetId::Alien5,
-        OmnitrixState::Alien6 => AssetId::Alien6,
-        OmnitrixState::Alien7 => AssetId::Alien7,
-        OmnitrixState::Alien8 => AssetId::Alien8,
-        OmnitrixState::Alien9 => AssetId::Alien9,
-        OmnitrixState::Alien10 => AssetId::Alien10,
     }
-}
 
-// Pre-cache a compressed asset into PSRAM
-pub fn precache_asset(id: AssetId) -> bool {
-    let (idx, w, h, blob) = asset_meta(id);
-    let need = (w * h * 2) as usize;
-    critical_section::with(|cs| {
-        if ASSETS.borrow(cs).borrow()[idx].data.is_some() {
-            return true;
+    // Move to the previous item/state (rotary CCW)
+    pub fn prev_item(self) -> Self {
+        if self.dialog.is_some() {
+            return self;
         }
-        if let Ok(tmp) = decompress_to_vec_zlib_with_limit(blob, need) {
-            if tmp.len() == need {
-                let leaked: &'static mut [u8] = alloc::boxed::Box::leak(tmp.into_boxed_slice());
-                ASSETS.borrow(cs).borrow_mut()[idx] = AssetSlot {
-                    data: Some(leaked as &'static [u8]),
-                    w,
-                    h,
+        let prev_page = match self.page {
+            Page::Main(state) => {
+                let prev = match state {
+                    MainMenuState::Home => MainMenuState::SettingsApp,
+                    MainMenuState::WatchApp => MainMenuState::Home,
+                    MainMenuState::TempApp => MainMenuState::WatchApp,
+                    MainMenuState::FlashlightApp => MainMenuState::TempApp,
+                    MainMenuState::LauncherApp => MainMenuState::FlashlightApp,
+                    MainMenuState::SettingsApp => MainMenuState::LauncherApp,
                 };
-                return true;
+                Page::Main(prev)
+            }
+            Page::Watch(state) => {
+                let prev = match state {
+                    WatchAppState::Analog => WatchAppState::Digital,
+                    WatchAppState::Digital => WatchAppState::Analog,
+                };
+                Page::Watch(prev)
             }
+            Page::Settings(state) => {
+                let prev = match state {
+                    SettingsMenuState::BrightnessPrompt => SettingsMenuState::EasterEgg,
+                    SettingsMenuState::BrightnessLimits => SettingsMenuState::BrightnessPrompt,
+                    SettingsMenuState::TempUnit => SettingsMenuState::BrightnessLimits,
+                    SettingsMenuState::Carousel => SettingsMenuState::TempUnit,
+                    SettingsMenuState::Animations => SettingsMenuState::Carousel,
+                    SettingsMenuState::ColonBlink => SettingsMenuState::Animations,
+                    SettingsMenuState::ClockFormat => SettingsMenuState::ColonBlink,
+                    SettingsMenuState::ClockSeconds => SettingsMenuState::ClockFormat,
+                    SettingsMenuState::DateFormat => SettingsMenuState::ClockSeconds,
+                    SettingsMenuState::EncoderDir => SettingsMenuState::DateFormat,
+                    SettingsMenuState::Wrist => SettingsMenuState::EncoderDir,
+                    SettingsMenuState::AlwaysOn => SettingsMenuState::AutoRotate,
+                    SettingsMenuState::AutoRotate => SettingsMenuState::Wrist,
+                    SettingsMenuState::ScreenTimeout => SettingsMenuState::AlwaysOn,
+                    SettingsMenuState::LowBattery => SettingsMenuState::ScreenTimeout,
+                    SettingsMenuState::WakeSource => SettingsMenuState::LowBattery,
+                    SettingsMenuState::WakeAlarm => SettingsMenuState::WakeSource,
+                    SettingsMenuState::TapNav => SettingsMenuState::WakeAlarm,
+                    SettingsMenuState::SmashCal => SettingsMenuState::TapNav,
+                    SettingsMenuState::EasterEgg => SettingsMenuState::SmashCal,
+                    SettingsMenuState::BrightnessAdjust => SettingsMenuState::BrightnessAdjust,
+                };
+                Page::Settings(prev)
+            }
+            Page::Omnitrix(state) => {
+                let prev = match state {
+                    OmnitrixState::Alien1 => OmnitrixState::Alien10,
+                    OmnitrixState::Alien2 => OmnitrixState::Alien1,
+                    OmnitrixState::Alien3 => OmnitrixState::Alien2,
+                    OmnitrixState::Alien4 => OmnitrixState::Alien3,
+                    OmnitrixState::Alien5 => OmnitrixState::Alien4,
+                    OmnitrixState::Alien6 => OmnitrixState::Alien5,
+                    OmnitrixState::Alien7 => OmnitrixState::Alien6,
+                    OmnitrixState::Alien8 => OmnitrixState::Alien7,
+                    OmnitrixState::Alien9 => OmnitrixState::Alien8,
+                    OmnitrixState::Alien10 => OmnitrixState::Alien9,
+                };
+                Page::Omnitrix(prev)
+            }
+            Page::EasterEgg => Page::EasterEgg,
+            Page::Temperature => Page::Temperature,
+            Page::Flashlight => Page::Flashlight,
+            Page::SmashCalibration => Page::SmashCalibration,
+            Page::Service => Page::Service,
+            Page::Launcher(i) => {
+                let n = Page::ALL.len() as u8;
+                Page::Launcher((i + n - 1) % n)
+            }
+        };
+        Self {
+            page: prev_page,
+            dialog: None,
         }
-        false
-    })
-}
+    }
 
-// Pre-cache all (call once at boot)
-pub fn precache_all() -> usize {
-    let mut ok = 0;
-    for id in [
-        AssetId::Alien1,
-        AssetId::Alien2,
-        AssetId::Alien3,
-        AssetId::Alien4,
-        AssetId::Alien5,
-        AssetId::Alien6,
-        AssetId::Alien7,
-        AssetId::Alien8,
-        AssetId::Alien9,
-        AssetId::Alien10,
-        AssetId::Logo,
-        AssetId::SettingsImage,
-        AssetId::WatchIcon,
-    ] {
-        if precache_asset(id) {
-            ok += 1;
+    // Go back (Button 1)
+    pub fn back(self) -> Self {
+        if self.dialog.is_some() {
+            return Self {
+                page: self.page,
+                dialog: None,
+            };
+        }
+        // If in Settings adjust view, pop back to prompt (also pop nav once).
+        if matches!(
+            self.page,
+            Page::Settings(SettingsMenuState::BrightnessAdjust)
+        ) {
+            let _ = nav_pop();
+            return Self {
+                page: Page::Settings(SettingsMenuState::BrightnessPrompt),
+                dialog: None,
+            };
+        }
+        if matches!(self.page, Page::EasterEgg) {
+            // drop the settings->easter egg push (the launcher can open it too)
+            let page = match nav_pop() {
+                Some(p @ Page::Launcher(_)) => p,
+                _ => Page::Settings(SettingsMenuState::EasterEgg),
+            };
+            return Self { page, dialog: None };
+        }
+
+        // Otherwise, try navigation history first.
+        if let Some(prev) = nav_pop() {
+            return Self {
+                page: prev,
+                dialog: None,
+            };
+        }
+        // Fallback if no history
+        Self {
+            page: Page::Main(MainMenuState::Home),
+            dialog: None,
+        }
+    }
+
+    // Select/enter (Button 2)
+    pub fn select(self) -> Self {
+        if let Some(_) = self.dialog {
+            return Self {
+                page: self.page,
+                dialog: None,
+            };
+        }
+        match self.page {
+            Page::Main(state) => {
+                nav_push(Page::Main(state));
+                let page = match state {
+                    MainMenuState::Home => Page::Omnitrix(OmnitrixState::Alien1),
+                    MainMenuState::WatchApp => Page::Watch(WatchAppState::Analog),
+                    MainMenuState::TempApp => Page::Temperature,
+                    MainMenuState::FlashlightApp => Page::Flashlight,
+                    MainMenuState::LauncherApp => Page::Launcher(0),
+                    MainMenuState::SettingsApp => {
+                        Page::Settings(SettingsMenuState::BrightnessPrompt)
+                    }
+                };
+                Self { page, dialog: None }
+            }
+            Page::Watch(_) => Self {
+                page: self.page,
+                dialog: None,
+            },
+            Page::Settings(s) => {
+                let page = match s {
+                    SettingsMenuState::BrightnessPrompt => {
+                        nav_push(Page::Settings(s));
+                        Page::Settings(SettingsMenuState::BrightnessAdjust)
+                    }
+                    SettingsMenuState::BrightnessLimits => {
+                        brightness_limits_cycle();
+                        self.page
+                    }
+                    SettingsMenuState::TempUnit => {
+                        temp_unit_toggle();
+                        self.page
+                    }
+                    SettingsMenuState::Carousel => {
+                        carousel_cycle();
+                        self.page
+                    }
+                    SettingsMenuState::Animations => {
+                        animations_set(!animations_enabled());
+                        self.page
+                    }
+                    SettingsMenuState::ColonBlink => {
+                        colon_blink_set(!colon_blink_enabled());
+                        self.page
+                    }
+                    SettingsMenuState::ClockFormat => {
+                        clock_12h_set(!clock_12h());
+                        self.page
+                    }
+                    SettingsMenuState::ClockSeconds => {
+                        clock_seconds_set(!clock_seconds_enabled());
+                        self.page
+                    }
+                    SettingsMenuState::DateFormat => {
+                        date_style_cycle();
+                        self.page
+                    }
+                    SettingsMenuState::EncoderDir => {
+                        encoder_direction_toggle();
+                        self.page
+                    }
+                    SettingsMenuState::Wrist => {
+                        wrist_toggle();
+                        self.page
+                    }
+                    SettingsMenuState::AutoRotate => {
+                        auto_rotate_set(!auto_rotate_enabled());
+                        self.page
+                    }
+                    SettingsMenuState::AlwaysOn => {
+                        always_on_set(!always_on_enabled());
+                        self.page
+                    }
+                    SettingsMenuState::ScreenTimeout => {
+                        screen_timeout_cycle();
+                        self.page
+                    }
+                    SettingsMenuState::LowBattery => {
+                        battery_thresholds_cycle();
+                        self.page
+                    }
+                    SettingsMenuState::WakeSource => {
+                        wake_sources_cycle();
+                        self.page
+                    }
+                    SettingsMenuState::WakeAlarm => {
+                        wake_alarm_cycle();
+                        self.page
+                    }
+                    SettingsMenuState::TapNav => {
+                        tap_cadence_cycle();
+                        self.page
+                    }
+                    SettingsMenuState::SmashCal => {
+                        nav_push(Page::Settings(s));
+                        critical_section::with(|cs| {
+                            *SMASH_CAL_PROGRESS.borrow(cs).borrow_mut() = None;
+                        });
+                        Page::SmashCalibration
+                    }
+                    SettingsMenuState::EasterEgg => {
+                        nav_push(Page::Settings(s));
+                        Page::EasterEgg
+                    }
+                    _ => self.page,
+                };
+                Self { page, dialog: None }
+            }
+            Page::Omnitrix(_) => Self {
+                page: self.page,
+                dialog: None,
+            }, // changed
+            Page::EasterEgg
+            | Page::Temperature
+            | Page::Flashlight
+            | Page::SmashCalibration
+            | Page::Service => Self {
+                page: self.page,
+                dialog: None,
+            },
+            Page::Launcher(i) => {
+                nav_push(self.page);
+                Self {
+                    page: Page::ALL[i as usize % Page::ALL.len()],
+                    dialog: None,
+                }
+            }
+        }
+    }
+
+    // Omnitrix transform (Button 3)
+    pub fn transform(self) -> Self {
+        // Only if on Omnitrix and no dialog already
+        if matches!(self.page, Page::Omnitrix(_)) && self.dialog.is_none() {
+            Self {
+                page: self.page,
+                dialog: Some(Dialog::TransformPage),
+            }
         } else {
-            break;
+            self
         }
     }
-    ok
-}
 
-// Get cached bytes and dims
-pub fn get_cached_asset(id: AssetId) -> Option<(&'static [u8], u32, u32)> {
-    let (idx, _, _, _) = asset_meta(id);
-    critical_section::with(|cs| {
-        let slot = ASSETS.borrow(cs).borrow()[idx];
-        slot.data.map(|d| (d, slot.w, slot.h))
-    })
+    // Drop warning over the current page; never replaces an open dialog
+    pub fn drop_warning(self) -> Self {
+        if self.dialog.is_none() {
+            Self {
+                page: self.page,
+                dialog: Some(Dialog::DropWarning),
+            }
+        } else {
+            self
+        }
+    }
 }
 
-// helper function to update the display based on UI_STATE
-pub fn update_ui(disp: &mut impl PanelRgb565, state: UiState, redraw: bool) {
-    // If caller does not want a redraw this cycle, bail out early.
-    if !redraw {
-        return;
+// helper function to draw centered text
+fn draw_text(
+    disp: &mut impl PanelRgb565,
+    text: &str,
+    fg: Rgb565,
+    bg: Option<Rgb565>,
+    x_point: i32,
+    y_point: i32,
+    clear: bool,
+    update_fb: bool,
+    font: Option<&'static MonoFont<'static>>,
+) {
+    if clear {
+        // Prefer no-FB clear if available and requested
+        if !update_fb {
+            if let Some(co) = disp.fast_panel() {
+                let _ = co.fill_rect_solid_no_fb(
+                    0,
+                    0,
+                    RESOLUTION as u16,
+                    RESOLUTION as u16,
+                    Rgb565::BLACK,
+                );
+            } else {
+                let _ = disp.clear(Rgb565::BLACK);
+            }
+        } else {
+            let _ = disp.clear(Rgb565::BLACK);
+        }
     }
-    // Clear when:
-    // - entering Omnitrix from another page, OR
-    // - exiting Transform dialog while staying in Omnitrix
-    let current_kind = match state.page {
-        Page::Main(_) => PageKind::Main,
-        Page::Settings(_) => PageKind::Settings,
-        Page::Omnitrix(_) => PageKind::Omnitrix,
-        Page::EasterEgg => PageKind::EasterEgg,
-        Page::Watch(_) => PageKind::Watch,
-    };
-    let current_transform_active = matches!(state.page, Page::Omnitrix(_))
-        && matches!(state.dialog, Some(Dialog::TransformPage));
+    let font = font.unwrap_or(&FONT_10X20);
+    let mut builder = MonoTextStyleBuilder::new().font(font).text_color(fg);
+    if let Some(b) = bg {
+        builder = builder.background_color(b);
+    }
+    let style = builder.build();
+    let x_point = layout_x(x_point, 0);
+    Text::with_alignment(text, Point::new(x_point, y_point), style, Alignment::Center)
+        .draw(disp)
+        .ok();
+}
 
-    let should_clear_no_fb = critical_section::with(|cs| {
-        let mut last_kind = LAST_PAGE_KIND.borrow(cs).borrow_mut();
-        let mut last_tx = LAST_OMNI_TRANSFORM_ACTIVE.borrow(cs).borrow_mut();
+// Centered, word-wrapped text: lines hold at most `max_w` pixels of glyphs, '\n' forces a
+// break, and words longer than a line are split. `y_point` is the first baseline (as in
+// `draw_text`); returns the baseline below the last line. No allocation, so it is safe on
+// error paths.
+pub fn draw_text_wrapped(
+    disp: &mut impl PanelRgb565,
+    text: &str,
+    fg: Rgb565,
+    bg: Option<Rgb565>,
+    x_point: i32,
+    y_point: i32,
+    max_w: u32,
+    font: Option<&'static MonoFont<'static>>,
+) -> i32 {
+    let font = font.unwrap_or(&FONT_10X20);
+    let mut builder = MonoTextStyleBuilder::new().font(font).text_color(fg);
+    if let Some(b) = bg {
+        builder = builder.background_color(b);
+    }
+    let style = builder.build();
+    let char_w = font.character_size.width + font.character_spacing;
+    let max_chars = (max_w / char_w.max(1)).max(1) as usize;
+    let line_h = font.character_size.height as i32 + 2;
+    let x_point = layout_x(x_point, 0);
+
+    let mut y = y_point;
+    for para in text.split('\n') {
+        let mut rest = para.trim();
+        loop {
+            let (line, next) = wrap_split(rest, max_chars);
+            Text::with_alignment(line, Point::new(x_point, y), style, Alignment::Center)
+                .draw(disp)
+                .ok();
+            y += line_h;
+            rest = next;
+            if rest.is_empty() {
+                break;
+            }
+        }
+    }
+    y
+}
 
-        let entering_omni =
-            current_kind == PageKind::Omnitrix && *last_kind != Some(PageKind::Omnitrix);
-        let exiting_transform =
-            (*last_tx) && current_kind == PageKind::Omnitrix && !current_transform_active;
+// Split off the first line of at most `max_chars` chars, preferring the last space
+fn wrap_split(s: &str, max_chars: usize) -> (&str, &str) {
+    let cut = match s.char_indices().nth(max_chars) {
+        Some((i, _)) => i,
+        None => return (s, ""),
+    };
+    if s[cut..].starts_with(' ') {
+        return (&s[..cut], s[cut..].trim_start());
+    }
+    match s[..cut].rfind(' ') {
+        Some(sp) if sp > 0 => (s[..sp].trim_end(), s[sp..].trim_start()),
+        _ => (&s[..cut], &s[cut..]),
+    }
+}
 
-        // update trackers for next frame
-        *last_kind = Some(current_kind);
-        *last_tx = current_transform_active;
+// What went wrong, for `draw_fault_screen`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FaultCode {
+    Display,
+    I2c,
+    Imu,
+    Rtc,
+    Battery,
+    Memory,
+    Panic,
+}
 
-        entering_omni || exiting_transform
-    });
+impl FaultCode {
+    // Short number users can read back over the phone / in an issue
+    pub fn code(self) -> u8 {
+        match self {
+            FaultCode::Display => 1,
+            FaultCode::I2c => 2,
+            FaultCode::Imu => 3,
+            FaultCode::Rtc => 4,
+            FaultCode::Battery => 5,
+            FaultCode::Memory => 6,
+            FaultCode::Panic => 9,
+        }
+    }
 
-    if should_clear_no_fb {
-        let _ = if let Some(co) =
-            (disp as &mut dyn Any).downcast_mut::<crate::display::DisplayType<'static>>()
-        {
-            co.fill_rect_solid_no_fb(0, 0, RESOLUTION as u16, RESOLUTION as u16, Rgb565::BLACK)
-                .ok();
-        } else {
-            disp.clear(Rgb565::BLACK).ok();
-        };
+    pub fn title(self) -> &'static str {
+        match self {
+            FaultCode::Display => "Display fault",
+            FaultCode::I2c => "I2C bus fault",
+            FaultCode::Imu => "Motion sensor fault",
+            FaultCode::Rtc => "Clock chip fault",
+            FaultCode::Battery => "Battery empty",
+            FaultCode::Memory => "Out of memory",
+            FaultCode::Panic => "System error",
+        }
     }
+}
 
-    if let Some(dialog) = state.dialog {
-        match dialog {
-            Dialog::TransformPage => {
-                // On first entry into Transform dialog, hard clear the whole screen.
-                let entering = critical_section::with(|cs| {
-                    let mut last = LAST_TRANSFORM_ACTIVE.borrow(cs).borrow_mut();
-                    let was = *last;
-                    *last = true;
-                    !was
+// Full-screen fault report: red banner with the code and title, `detail` wrapped below.
+// Only plain embedded-graphics draws (no caches, assets or heap), so it still works when
+// most of the system is broken.
+pub fn draw_fault_screen(disp: &mut impl PanelRgb565, code: FaultCode, detail: &str) {
+    let _ = disp.clear(Rgb565::BLACK);
+    let _ = Rectangle::new(Point::new(0, 120), Size::new(RESOLUTION, 76))
+        .into_styled(PrimitiveStyle::with_fill(Rgb565::RED))
+        .draw(disp);
+
+    // "E" + two digits, formatted by hand to stay allocation-free
+    let n = code.code();
+    let label = [b'E', b'0' + n / 10, b'0' + n % 10];
+    let label = core::str::from_utf8(&label).unwrap_or("E??");
+    draw_text(
+        disp,
+        label,
+        Rgb565::WHITE,
+        None,
+        CENTER,
+        148,
+        false,
+        true,
+        None,
+    );
+    draw_text(
+        disp,
+        code.title(),
+        Rgb565::WHITE,
+        None,
+        CENTER,
+        176,
+        false,
+        true,
+        None,
+    );
+    draw_text_wrapped(disp, detail, Rgb565::WHITE, None, CENTER, 236, 320, None);
+}
+
+// Drop warning dialog: red banner in the middle of a black screen
+fn draw_drop_warning(disp: &mut impl PanelRgb565) {
+    let _ = disp.clear(Rgb565::BLACK);
+    let _ = Rectangle::new(Point::new(0, 180), Size::new(RESOLUTION, 76))
+        .into_styled(PrimitiveStyle::with_fill(Rgb565::RED))
+        .draw(disp);
+    draw_text(
+        disp,
+        "Dropped!",
+        Rgb565::WHITE,
+        None,
+        CENTER,
+        218,
+        false,
+        true,
+        None,
+    );
+    draw_text(
+        disp,
+        "Check the watch, then press to dismiss",
+        Rgb565::WHITE,
+        None,
+        CENTER,
+        300,
+        false,
+        true,
+        Some(&FONT_6X10),
+    );
+}
+
+// 8-neighbour offsets used to build a 1px outline around 1-bit glyphs
+const OUTLINE_OFFSETS: [(i32, i32); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+// Centered text with no background box: a 1px `outline` halo under `fg`, so it stays
+// legible over photos. On the CO5300 the text area is composed off-screen over the
+// current FB contents and flushed once, so redrawing it doesn't flicker.
+fn draw_text_outlined(
+    disp: &mut impl PanelRgb565,
+    text: &str,
+    fg: Rgb565,
+    outline: Rgb565,
+    x_point: i32,
+    y_point: i32,
+    font: Option<&'static MonoFont<'static>>,
+) {
+    let font = font.unwrap_or(&FONT_10X20);
+    let fg_style = MonoTextStyleBuilder::new()
+        .font(font)
+        .text_color(fg)
+        .build();
+    let outline_style = MonoTextStyleBuilder::new()
+        .font(font)
+        .text_color(outline)
+        .build();
+    let at = Point::new(layout_x(x_point, 0), y_point);
+    let draw_all = |target: &mut dyn FnMut(Text<'_, MonoTextStyle<'static, Rgb565>>)| {
+        for (dx, dy) in OUTLINE_OFFSETS {
+            target(Text::with_alignment(
+                text,
+                at + Point::new(dx, dy),
+                outline_style,
+                Alignment::Center,
+            ));
+        }
+        target(Text::with_alignment(text, at, fg_style, Alignment::Center));
+    };
+
+    if let Some(co) = disp.fast_panel() {
+        let bb = Text::with_alignment(text, at, fg_style, Alignment::Center).bounding_box();
+        let max = (RESOLUTION - 1) as i32;
+        let x0 = (bb.top_left.x - 1).clamp(0, max);
+        let y0 = (bb.top_left.y - 1).clamp(0, max);
+        let x1 = (bb.top_left.x + bb.size.width as i32).clamp(0, max);
+        let y1 = (bb.top_left.y + bb.size.height as i32).clamp(0, max);
+
+        let mut canvas = FbRectCanvas {
+            data: Vec::new(),
+            origin: Point::new(x0, y0),
+            size: Size::new((x1 - x0 + 1) as u32, (y1 - y0 + 1) as u32),
+        };
+        co.save_fb_rect(x0 as u16, y0 as u16, x1 as u16, y1 as u16, &mut canvas.data);
+        draw_all(&mut |t| {
+            let _ = t.draw(&mut canvas);
+        });
+        let _ = co.restore_fb_rect(x0 as u16, y0 as u16, x1 as u16, y1 as u16, &canvas.data);
+        let _ = co.flush_rect_even(x0 as u16, y0 as u16, x1 as u16, y1 as u16);
+        return;
+    }
+    draw_all(&mut |t| {
+        let _ = t.draw(&mut *disp);
+    });
+}
+
+// A saved FB rectangle (raw FB order, see `save_fb_rect`) that can be drawn into off-screen
+struct FbRectCanvas {
+    data: Vec<u16>,
+    origin: Point,
+    size: Size,
+}
+
+impl OriginDimensions for FbRectCanvas {
+    fn size(&self) -> Size {
+        self.size
+    }
+}
+
+impl DrawTarget for FbRectCanvas {
+    type Color = Rgb565;
+    type Error = core::convert::Infallible;
+
+    // Takes screen coordinates; anything outside the saved rectangle is dropped
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = embedded_graphics::Pixel<Rgb565>>,
+    {
+        let (w, h) = (self.size.width as i32, self.size.height as i32);
+        for embedded_graphics::Pixel(p, c) in pixels {
+            let (x, y) = (p.x - self.origin.x, p.y - self.origin.y);
+            if x < 0 || y < 0 || x >= w || y >= h {
+                continue;
+            }
+            if let Some(px) = self.data.get_mut((y * w + x) as usize) {
+                *px = c.into_storage().to_be();
+            }
+        }
+        Ok(())
+    }
+}
+
+// Copy a rectangle of the watch background (if loaded) into `out` as RGB565 BE bytes.
+// Returns false when there is no background or the rect is off-screen.
+fn watch_bg_crop(x: i32, y: i32, w: usize, h: usize, out: &mut [u8]) -> bool {
+    let res = RESOLUTION as usize;
+    if x < 0 || y < 0 || x as usize + w > res || y as usize + h > res || out.len() != w * h * 2 {
+        return false;
+    }
+    critical_section::with(|cs| {
+        let bg_ref = WATCH_BG.borrow(cs).borrow();
+        let Some(bg) = bg_ref.as_ref() else {
+            return false;
+        };
+        shifted_bg_crop(bg, x, y, w, out);
+        true
+    })
+}
+
+// Copy the on-screen rect at (x, y), `w` pixels wide and `out.len() / (w * 2)` rows tall,
+// out of a full-screen background drawn at the pixel shift offset. Pixels the shifted
+// background doesn't cover come out black.
+fn shifted_bg_crop(bg: &[u8], x: i32, y: i32, w: usize, out: &mut [u8]) {
+    let res = RESOLUTION as i32;
+    let (ox, oy) = shifted_origin(RESOLUTION, RESOLUTION);
+    let sx = x - ox;
+    // Columns of the rect that have background under them
+    let lo = (-sx).clamp(0, w as i32) as usize;
+    let hi = (res - sx).clamp(0, w as i32) as usize;
+    for (row, dst) in out.chunks_exact_mut(w * 2).enumerate() {
+        let sy = y + row as i32 - oy;
+        if !(0..res).contains(&sy) || lo >= hi {
+            dst.fill(0);
+            continue;
+        }
+        dst[..lo * 2].fill(0);
+        dst[hi * 2..].fill(0);
+        let off = (sy * res + sx + lo as i32) as usize * 2;
+        dst[lo * 2..hi * 2].copy_from_slice(&bg[off..off + (hi - lo) * 2]);
+    }
+}
+
+// Format current clock as HH:MM into the provided 5-byte buffer and return it as &str.
+fn format_clock_hm(buf: &mut [u8; 5]) -> &str {
+    let total_secs = clock_now_seconds();
+    let total_mins = total_secs / 60;
+    let h = (total_mins / 60) % 24;
+    let m = total_mins % 60;
+
+    buf[0] = b'0' + (h / 10) as u8;
+    buf[1] = b'0' + (h % 10) as u8;
+    buf[2] = b':';
+    buf[3] = b'0' + (m / 10) as u8;
+    buf[4] = b'0' + (m % 10) as u8;
+
+    core::str::from_utf8(buf).unwrap_or("??:??")
+}
+
+// Format current clock as HH:MM:SS into the provided buffer and return it as &str
+fn format_clock_hms(buf: &mut [u8; 8]) -> &str {
+    let total_secs = clock_now_seconds();
+    let h = (total_secs / 3600) % 24;
+    let m = (total_secs / 60) % 60;
+    let s = total_secs % 60;
+
+    buf[0] = b'0' + (h / 10) as u8;
+    buf[1] = b'0' + (h % 10) as u8;
+    buf[2] = b':';
+    buf[3] = b'0' + (m / 10) as u8;
+    buf[4] = b'0' + (m % 10) as u8;
+    buf[5] = b':';
+    buf[6] = b'0' + (s / 10) as u8;
+    buf[7] = b'0' + (s % 10) as u8;
+
+    core::str::from_utf8(buf).unwrap_or("??:??:??")
+}
+
+// 24-hour hour to 12-hour (1-12) and whether it is PM: 00 -> 12 AM, 12 -> 12 PM
+fn hour_12(h: u32) -> (u32, bool) {
+    let h = h % 24;
+    (if h % 12 == 0 { 12 } else { h % 12 }, h >= 12)
+}
+
+// Format current clock as "hh:MM AM" / "hh:MM PM" into the provided buffer and return it
+fn format_clock_12h(buf: &mut [u8; 8]) -> &str {
+    let total_mins = clock_now_seconds() / 60;
+    let (h, pm) = hour_12((total_mins / 60) as u32);
+    let m = total_mins % 60;
+
+    buf[0] = b'0' + (h / 10) as u8;
+    buf[1] = b'0' + (h % 10) as u8;
+    buf[2] = b':';
+    buf[3] = b'0' + (m / 10) as u8;
+    buf[4] = b'0' + (m % 10) as u8;
+    buf[5] = b' ';
+    buf[6] = if pm { b'P' } else { b'A' };
+    buf[7] = b'M';
+
+    core::str::from_utf8(buf).unwrap_or("??:?? ??")
+}
+
+// Digital clock layout: "HH:MM" in FONT_10X20, centered like draw_text at (CENTER, CENTER),
+// drawn outlined straight over the watch background
+const CLOCK_FG: Rgb565 = Rgb565::CYAN;
+const CLOCK_OUTLINE: Rgb565 = Rgb565::BLACK;
+const DIGIT_W: usize = 10;
+const DIGIT_H: usize = 20;
+const DIGIT_ROLL_MS: u64 = 320;
+// x offset (in cells) of each digit and of the colon within "HH:MM"
+const CLOCK_DIGIT_COLS: [i32; 4] = [0, 1, 3, 4];
+const CLOCK_COLON_COL: i32 = 2;
+// x offset (in cells) of the seconds digits within "HH:MM:SS"
+const CLOCK_SECONDS_COLS: [i32; 2] = [6, 7];
+// 12-hour AM/PM marker size in FONT_6X10
+const CLOCK_SUFFIX_W: usize = 12;
+const CLOCK_SUFFIX_H: usize = 10;
+
+// Which clock digits are on screen and which are mid-roll
+#[derive(Copy, Clone)]
+struct DigitRoll {
+    shown: Option<[u8; 4]>,
+    // Seconds digits on screen, when the face shows them
+    secs: Option<[u8; 2]>,
+    from: [u8; 4],
+    start_ms: Option<u64>,
+}
+
+impl DigitRoll {
+    const fn new() -> Self {
+        Self {
+            shown: None,
+            secs: None,
+            from: [0; 4],
+            start_ms: None,
+        }
+    }
+}
+
+// Drop any in-flight roll (call after the face was repainted underneath it). The date line
+// and the "Set clock" badge were painted over too, so they are redrawn with the next frame.
+fn digit_roll_reset() {
+    critical_section::with(|cs| {
+        *DIGIT_ROLL.borrow(cs).borrow_mut() = DigitRoll::new();
+        *DATE_LINE_SHOWN.borrow(cs).borrow_mut() = None;
+        *CLOCK_BADGE_SHOWN.borrow(cs).borrow_mut() = false;
+    });
+}
+
+fn uptime_ms() -> u64 {
+    let t = SystemTimer::unit_value(Unit::Unit0);
+    t.saturating_mul(1000) / SystemTimer::ticks_per_second()
+}
+
+// Shortest gap between animation frames (~60 fps cap)
+const ANIM_FRAME_MIN_TICKS_DIV: u64 = 60;
+// Longest delta handed out, so a stall (or the first frame after a pause) doesn't skip ahead
+const ANIM_MAX_DT_S: f32 = 0.1;
+
+// Per-animation frame pacer: hands out the time since the previous frame, at most 60 times
+// a second.
+#[derive(Copy, Clone, Debug)]
+struct AnimTimer {
+    last_ticks: Option<u64>,
+}
+
+impl AnimTimer {
+    const fn new() -> Self {
+        Self { last_ticks: None }
+    }
+
+    // Delta time in seconds if a new frame is due (0.0 for the first frame), else None
+    fn tick(&mut self) -> Option<f32> {
+        let now = SystemTimer::unit_value(Unit::Unit0);
+        let tps = SystemTimer::ticks_per_second();
+        let Some(last) = self.last_ticks else {
+            self.last_ticks = Some(now);
+            return Some(0.0);
+        };
+        let elapsed = now.saturating_sub(last);
+        if elapsed < tps / ANIM_FRAME_MIN_TICKS_DIV {
+            return None;
+        }
+        self.last_ticks = Some(now);
+        Some((elapsed as f32 / tps as f32).min(ANIM_MAX_DT_S))
+    }
+}
+
+// Small off-screen RGB565 BE buffer for rendering one glyph cell
+struct CellCanvas {
+    buf: [u8; DIGIT_W * DIGIT_H * 2],
+}
+
+impl OriginDimensions for CellCanvas {
+    fn size(&self) -> Size {
+        Size::new(DIGIT_W as u32, DIGIT_H as u32)
+    }
+}
+
+impl DrawTarget for CellCanvas {
+    type Color = Rgb565;
+    type Error = core::convert::Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = embedded_graphics::Pixel<Rgb565>>,
+    {
+        for embedded_graphics::Pixel(p, c) in pixels {
+            if p.x < 0 || p.y < 0 || p.x as usize >= DIGIT_W || p.y as usize >= DIGIT_H {
+                continue;
+            }
+            let i = (p.y as usize * DIGIT_W + p.x as usize) * 2;
+            self.buf[i..i + 2].copy_from_slice(&c.into_storage().to_be_bytes());
+        }
+        Ok(())
+    }
+}
+
+// Draw one digit cell (top-left at x, y) scrolling from `old` to `new`.
+// progress 0.0 shows `old`, 1.0 shows `new`; in between `old` slides up and out
+// while `new` comes in from below, clipped to the cell.
+pub fn draw_rolling_digit(
+    disp: &mut impl PanelRgb565,
+    x: i32,
+    y: i32,
+    old: u8,
+    new: u8,
+    progress: f32,
+) {
+    let off = (progress.clamp(0.0, 1.0) * DIGIT_H as f32) as i32;
+    draw_clock_cell(
+        disp,
+        x,
+        y,
+        &[
+            (b'0' + old.min(9), -off),
+            (b'0' + new.min(9), DIGIT_H as i32 - off),
+        ],
+    );
+}
+
+// Render one clock cell (top-left at x, y) over the face background: each (ascii, dy)
+// glyph is drawn outlined, shifted down by dy and clipped to the cell. An empty list
+// just restores the background.
+fn draw_clock_cell(disp: &mut impl PanelRgb565, x: i32, y: i32, glyphs: &[(u8, i32)]) {
+    let mut cell = CellCanvas {
+        buf: [0; DIGIT_W * DIGIT_H * 2],
+    };
+    // Start from the face background so the cell blends in; plain fill if it isn't loaded
+    if !watch_bg_crop(x, y, DIGIT_W, DIGIT_H, &mut cell.buf) {
+        let _ = cell.clear(CLOCK_OUTLINE);
+    }
+
+    let style = MonoTextStyleBuilder::new()
+        .font(&FONT_10X20)
+        .text_color(CLOCK_FG)
+        .build();
+    let outline_style = MonoTextStyleBuilder::new()
+        .font(&FONT_10X20)
+        .text_color(CLOCK_OUTLINE)
+        .build();
+    for &(ch, dy) in glyphs {
+        if dy <= -(DIGIT_H as i32) || dy >= DIGIT_H as i32 {
+            continue;
+        }
+        let g = [ch];
+        let text = core::str::from_utf8(&g).unwrap_or("?");
+        for (ox, oy) in OUTLINE_OFFSETS {
+            let p = Point::new(ox, dy + oy);
+            let _ = Text::with_baseline(text, p, outline_style, Baseline::Top).draw(&mut cell);
+        }
+        let _ = Text::with_baseline(text, Point::new(0, dy), style, Baseline::Top).draw(&mut cell);
+    }
+
+    // FB write + one flush of just this cell on the CO5300
+    if let Some(co) = disp.fast_panel() {
+        if x >= 0 && y >= 0 {
+            let (xu, yu) = (x as u16, y as u16);
+            if co
+                .write_rect_fb(xu, yu, DIGIT_W as u16, DIGIT_H as u16, &cell.buf)
+                .is_ok()
+            {
+                let _ =
+                    co.flush_rect_even(xu, yu, xu + DIGIT_W as u16 - 1, yu + DIGIT_H as u16 - 1);
+                return;
+            }
+        }
+    }
+    let raw = ImageRawBE::<Rgb565>::new(&cell.buf, DIGIT_W as u32);
+    let _ = Image::new(&raw, Point::new(x, y)).draw(disp);
+}
+
+// "Set clock" badge baseline below the center, and its length in FONT_10X20 cells
+const CLOCK_BADGE_DY: i32 = 70;
+const CLOCK_BADGE_CHARS: i32 = 9;
+
+// Screen rect (inclusive) of the "Set clock" badge
+fn clock_badge_rect() -> (i32, i32, i32, i32) {
+    let half_w = CLOCK_BADGE_CHARS * DIGIT_W as i32 / 2;
+    let top = CENTER + CLOCK_BADGE_DY - FONT_10X20.baseline as i32;
+    (
+        CENTER - half_w,
+        top,
+        CENTER + half_w - 1,
+        top + DIGIT_H as i32 - 1,
+    )
+}
+
+// "Set clock" prompt under the watch face while the time is not trusted. Painted only when
+// it isn't already up, like the date line.
+fn draw_clock_unset_badge(disp: &mut impl PanelRgb565) {
+    let shown = critical_section::with(|cs| {
+        core::mem::replace(&mut *CLOCK_BADGE_SHOWN.borrow(cs).borrow_mut(), true)
+    });
+    if shown {
+        return;
+    }
+    draw_text(
+        disp,
+        "Set clock",
+        Rgb565::YELLOW,
+        Some(Rgb565::BLACK),
+        CENTER,
+        CENTER + CLOCK_BADGE_DY,
+        false,
+        true,
+        None,
+    );
+}
+
+// Digital clock face: plain text, except digits that just changed roll in over a few frames.
+// With seconds on, a frame where only the seconds moved repaints just those cells.
+fn draw_digital_clock(disp: &mut impl PanelRgb565) {
+    let show_secs = clock_seconds_enabled();
+    let mut buf = [b'0'; 8];
+    let suffix = if show_secs {
+        format_clock_hms(&mut buf);
+        clock_12h().then(|| {
+            let (h, pm) = hour_12(((buf[0] - b'0') * 10 + buf[1] - b'0') as u32);
+            buf[0] = b'0' + (h / 10) as u8;
+            buf[1] = b'0' + (h % 10) as u8;
+            if pm {
+                "PM"
+            } else {
+                "AM"
+            }
+        })
+    } else if clock_12h() {
+        format_clock_12h(&mut buf);
+        Some(if buf[6] == b'P' { "PM" } else { "AM" })
+    } else {
+        let mut hm = [b'0'; 5];
+        format_clock_hm(&mut hm);
+        buf[..5].copy_from_slice(&hm);
+        None
+    };
+    let cells = if show_secs { 8 } else { 5 };
+    let digits = [buf[0] - b'0', buf[1] - b'0', buf[3] - b'0', buf[4] - b'0'];
+    let secs_digits = [buf[6] - b'0', buf[7] - b'0'];
+    let now = uptime_ms();
+    let animate = animations_enabled();
+
+    // Blinking colon: hidden for the second half of every second
+    let secs = clock_now_seconds_f32();
+    let colon_on = !colon_blink_enabled() || secs - floorf(secs) < 0.5;
+    if !colon_on {
+        buf[CLOCK_COLON_COL as usize] = b' ';
+    }
+    let msg = core::str::from_utf8(&buf[..cells]).unwrap_or("??:??");
+
+    // Decide what to draw this frame: Some((from, progress)) while rolling. Also whether
+    // HH:MM is already on screen unchanged, and which seconds are.
+    let (roll, steady, shown_secs) = critical_section::with(|cs| {
+        let mut r = DIGIT_ROLL.borrow(cs).borrow_mut();
+        let shown_secs = r.secs;
+        r.secs = show_secs.then_some(secs_digits);
+        let roll = match (r.shown, r.start_ms) {
+            // A roll is running toward `shown`
+            (Some(target), Some(t0)) if target == digits && animate => {
+                let p = (now.saturating_sub(t0)) as f32 / DIGIT_ROLL_MS as f32;
+                if p >= 1.0 {
+                    r.start_ms = None;
+                }
+                Some((r.from, p.min(1.0)))
+            }
+            // Digits changed since the last frame: start rolling
+            (Some(prev), None) if prev != digits && animate => {
+                r.from = prev;
+                r.shown = Some(digits);
+                r.start_ms = Some(now);
+                Some((prev, 0.0))
+            }
+            // First frame, animations off, or the time jumped mid-roll: snap
+            _ => {
+                let steady = r.shown == Some(digits) && r.start_ms.is_none();
+                *r = DigitRoll {
+                    shown: Some(digits),
+                    secs: r.secs,
+                    ..DigitRoll::new()
+                };
+                return (None, steady, shown_secs);
+            }
+        };
+        (roll, false, shown_secs)
+    });
+
+    let left = CENTER - (cells as i32 * DIGIT_W as i32) / 2;
+    let top = CENTER - FONT_10X20.baseline as i32;
+    match roll {
+        Some((from, progress)) => {
+            for i in 0..4 {
+                if from[i] != digits[i] {
+                    let x = left + CLOCK_DIGIT_COLS[i] * DIGIT_W as i32;
+                    draw_rolling_digit(disp, x, top, from[i], digits[i], progress);
+                }
+            }
+            // Only changed digits are redrawn while rolling, so keep the colon blinking here
+            if colon_blink_enabled() {
+                let x = left + CLOCK_COLON_COL * DIGIT_W as i32;
+                let colon: &[(u8, i32)] = if colon_on { &[(b':', 0)] } else { &[] };
+                draw_clock_cell(disp, x, top, colon);
+            }
+            if show_secs {
+                draw_clock_seconds(disp, left, top, shown_secs, secs_digits);
+            }
+        }
+        // Seconds ticked but HH:MM didn't: just the seconds (and a blinking colon)
+        None if show_secs && steady && shown_secs.is_some() => {
+            draw_clock_seconds(disp, left, top, shown_secs, secs_digits);
+            if colon_blink_enabled() {
+                let x = left + CLOCK_COLON_COL * DIGIT_W as i32;
+                let colon: &[(u8, i32)] = if colon_on { &[(b':', 0)] } else { &[] };
+                draw_clock_cell(disp, x, top, colon);
+            }
+        }
+        None => {
+            // Put the background back under the old text first; the outline has no box to
+            // cover stale glyphs.
+            if let Some(co) = disp.fast_panel() {
+                let (w, h) = (cells * DIGIT_W + 2, DIGIT_H + 2);
+                let x = left - 1;
+                let y = top - 1;
+                let mut bg = alloc::vec![0u8; w * h * 2];
+                if watch_bg_crop(x, y, w, h, &mut bg) {
+                    let _ = co.write_rect_fb(x as u16, y as u16, w as u16, h as u16, &bg);
+                } else {
+                    co.fill_rect_fb(x, y, x + w as i32 - 1, y + h as i32 - 1, CLOCK_OUTLINE);
+                }
+            }
+            draw_text_outlined(disp, msg, CLOCK_FG, CLOCK_OUTLINE, CENTER, CENTER, None);
+            if let Some(suffix) = suffix {
+                draw_clock_suffix(disp, suffix, cells);
+            }
+        }
+    }
+}
+
+// Repaint the seconds cells of "HH:MM:SS" that differ from `shown` (all of them if unknown)
+fn draw_clock_seconds(
+    disp: &mut impl PanelRgb565,
+    left: i32,
+    top: i32,
+    shown: Option<[u8; 2]>,
+    secs: [u8; 2],
+) {
+    for i in 0..2 {
+        if shown.is_some_and(|s| s[i] == secs[i]) {
+            continue;
+        }
+        let x = left + CLOCK_SECONDS_COLS[i] * DIGIT_W as i32;
+        draw_clock_cell(disp, x, top, &[(b'0' + secs[i], 0)]);
+    }
+}
+
+// AM/PM marker of the 12-hour digital clock (`cells` wide), over restored background like
+// the digits
+fn draw_clock_suffix(disp: &mut impl PanelRgb565, suffix: &str, cells: usize) {
+    // Just right of the clock text, sharing its baseline (CENTER)
+    let suffix_x = CENTER + (cells * DIGIT_W) as i32 / 2 + 4;
+    let top = CENTER - FONT_6X10.baseline as i32;
+    if let Some(co) = disp.fast_panel() {
+        let (w, h) = (CLOCK_SUFFIX_W + 2, CLOCK_SUFFIX_H + 2);
+        let (x, y) = (layout_x(suffix_x - 1, w as i32), top - 1);
+        let mut bg = alloc::vec![0u8; w * h * 2];
+        if watch_bg_crop(x, y, w, h, &mut bg) {
+            let _ = co.write_rect_fb(x as u16, y as u16, w as u16, h as u16, &bg);
+        } else {
+            co.fill_rect_fb(x, y, x + w as i32 - 1, y + h as i32 - 1, CLOCK_OUTLINE);
+        }
+    }
+    draw_text_outlined(
+        disp,
+        suffix,
+        CLOCK_FG,
+        CLOCK_OUTLINE,
+        suffix_x + CLOCK_SUFFIX_W as i32 / 2,
+        CENTER,
+        Some(&FONT_6X10),
+    );
+}
+
+// Date line under the digital clock (baseline offset from the clock's)
+#[cfg(feature = "esp32s3-disp143Oled")]
+const DATE_LINE_DY: i32 = 36;
+
+// Weekday + date under the digital clock, repainted only when the day or style changes
+#[cfg(feature = "esp32s3-disp143Oled")]
+fn draw_date_line(disp: &mut impl PanelRgb565) {
+    let secs = clock_now_seconds_u32();
+    let key = (secs / 86_400, date_style());
+    let changed = critical_section::with(|cs| {
+        let mut shown = DATE_LINE_SHOWN.borrow(cs).borrow_mut();
+        let changed = *shown != Some(key);
+        *shown = Some(key);
+        changed
+    });
+    if !changed {
+        return;
+    }
+    let mut buf = [b' '; 14];
+    let text = format_date(&unix_to_datetime(secs), key.1, &mut buf);
+
+    // Styles differ in width, so always restore the widest box before drawing
+    let y = CENTER + DATE_LINE_DY;
+    if let Some(co) = disp.fast_panel() {
+        let (w, h) = (14 * DIGIT_W + 2, DIGIT_H + 2);
+        let x = CENTER - (14 * DIGIT_W as i32) / 2 - 1;
+        let top = y - FONT_10X20.baseline as i32 - 1;
+        let mut bg = alloc::vec![0u8; w * h * 2];
+        if watch_bg_crop(x, top, w, h, &mut bg) {
+            let _ = co.write_rect_fb(x as u16, top as u16, w as u16, h as u16, &bg);
+        } else {
+            co.fill_rect_fb(x, top, x + w as i32 - 1, top + h as i32 - 1, CLOCK_OUTLINE);
+        }
+    }
+    draw_text_outlined(disp, text, CLOCK_FG, CLOCK_OUTLINE, CENTER, y, None);
+}
+
+fn rgb565_from_888(r: u8, g: u8, b: u8) -> Rgb565 {
+    Rgb565::new((r >> 3) as u8, (g >> 2) as u8, (b >> 3) as u8)
+}
+
+fn hand_end(cx: i32, cy: i32, angle_deg: f32, length: i32) -> Point {
+    let ang = angle_deg.to_radians();
+    let dx = (cosf(ang) * length as f32) as i32;
+    let dy = (sinf(ang) * length as f32) as i32;
+    Point::new(cx + dx, cy + dy)
+}
+
+fn draw_hand_line(
+    disp: &mut impl PanelRgb565,
+    cx: i32,
+    cy: i32,
+    end: Point,
+    color: Rgb565,
+    stroke: u8,
+) {
+    let style = PrimitiveStyle::with_stroke(color, stroke.into());
+    let _ = Line::new(Point::new(cx, cy), end)
+        .into_styled(style)
+        .draw(disp);
+}
+
+fn draw_analog_clock(disp: &mut impl PanelRgb565) {
+    let (sx, sy) = pixel_shift();
+    let center = (RESOLUTION as i32 / 2 + sx, RESOLUTION as i32 / 2 + sy);
+    let cx = center.0;
+    let cy = center.1;
+
+    // Current time in fractional hours, minutes, seconds
+    let (h, m, s) = clock_now_hms_f32();
+
+    // Angles: 0 deg at 12 o'clock, increasing clockwise
+    let sec_ang = (s / 60.0) * 360.0 - 90.0;
+    let min_ang = (m / 60.0) * 360.0 - 90.0;
+    let hour_ang = (h / 12.0) * 360.0 - 90.0;
+
+    // Hand lengths
+    let radius = RESOLUTION as i32 / 2 - 10;
+    let sec_len = radius - 10;
+    let min_len = radius - 25;
+    let hour_len = radius - 50;
+
+    // Compute new endpoints
+    let sec_end = hand_end(cx, cy, sec_ang, sec_len);
+    let min_end = hand_end(cx, cy, min_ang, min_len);
+    let hour_end = hand_end(cx, cy, hour_ang, hour_len);
+
+    // Fast path: draw into FB only and flush the dirty region once.
+    if let Some(co) = disp.fast_panel() {
+        critical_section::with(|cs| {
+            let mut cache = HAND_CACHE.borrow(cs).borrow_mut();
+            // Hands land on the same pixels as last frame: nothing to redraw
+            if cache.sec == Some(sec_end)
+                && cache.min == Some(min_end)
+                && cache.hour == Some(hour_end)
+            {
+                return;
+            }
+            let bg_ref = WATCH_BG.borrow(cs).borrow();
+            let bgdata = bg_ref.as_ref();
+
+            // Bounding box of old + new hands with padding
+            let mut minx = cx;
+            let mut miny = cy;
+            let mut maxx = cx;
+            let mut maxy = cy;
+            let mut add_pt = |p: Point, pad: i32| {
+                minx = minx.min(p.x - pad);
+                miny = miny.min(p.y - pad);
+                maxx = maxx.max(p.x + pad);
+                maxy = maxy.max(p.y + pad);
+            };
+
+            // Add previous hand endpoints
+            let sec_stroke = 4;
+            let min_stroke = 4;
+            let hour_stroke = 4;
+            let sec_pad = (sec_stroke * 2).max(6);
+            let min_pad = (min_stroke * 2).max(8);
+            let hour_pad = (hour_stroke * 2).max(10);
+
+            // Previous points
+            if let Some(p) = cache.sec {
+                add_pt(p, sec_pad);
+            }
+            if let Some(p) = cache.min {
+                add_pt(p, min_pad);
+            }
+            if let Some(p) = cache.hour {
+                add_pt(p, hour_pad);
+            }
+
+            // New points
+            add_pt(sec_end, sec_pad);
+            add_pt(min_end, min_pad);
+            add_pt(hour_end, hour_pad);
+
+            // Center dot padding
+            let dot_pad = 22; // covers enlarged center gradient
+            add_pt(Point::new(cx, cy), dot_pad);
+
+            // Clear region to background if available, else black
+            if let Some(bgdata) = bgdata {
+                let bx0 = minx.clamp(0, (RESOLUTION - 1) as i32) as usize;
+                let by0 = miny.clamp(0, (RESOLUTION - 1) as i32) as usize;
+                let bx1 = maxx.clamp(0, (RESOLUTION - 1) as i32) as usize;
+                let by1 = maxy.clamp(0, (RESOLUTION - 1) as i32) as usize;
+                let w = bx1 - bx0 + 1;
+                let h = by1 - by0 + 1;
+                let mut buf = alloc::vec![0u8; w * h * 2];
+                shifted_bg_crop(bgdata, bx0 as i32, by0 as i32, w, &mut buf);
+                let _ = co.write_rect_fb(bx0 as u16, by0 as u16, w as u16, h as u16, &buf);
+            } else {
+                co.fill_rect_fb(minx, miny, maxx, maxy, Rgb565::BLACK);
+            }
+
+            // Date window: repaint it when the clear above cut into it or the day changed;
+            // the hands go over it
+            #[cfg(feature = "esp32s3-disp143Oled")]
+            {
+                let day = clock_now_seconds_u32() / 86_400;
+                let (x0, y0, x1, y1) = analog_date_rect(cx, cy);
+                let cleared = minx <= x1 && maxx >= x0 && miny <= y1 && maxy >= y0;
+                if cleared || cache.date_day != Some(day) {
+                    draw_analog_date(co, cx, cy, day);
+                    cache.date_day = Some(day);
+                }
+            }
+            // Same for the "Set clock" badge, which goes back over the hands after this
+            let (x0, y0, x1, y1) = clock_badge_rect();
+            if minx <= x1 && maxx >= x0 && miny <= y1 && maxy >= y0 {
+                *CLOCK_BADGE_SHOWN.borrow(cs).borrow_mut() = false;
+            }
+
+            // Draw all hands
+            // Hour hand
+            co.draw_line_fb_aa(
+                cx,
+                cy,
+                hour_end.x,
+                hour_end.y,
+                Rgb565::WHITE,
+                hour_stroke as u8,
+            );
+            // Minute hand
+            co.draw_line_fb_aa(
+                cx,
+                cy,
+                min_end.x,
+                min_end.y,
+                Rgb565::YELLOW,
+                min_stroke as u8,
+            );
+            // Second hand
+            co.draw_line_fb_aa(cx, cy, sec_end.x, sec_end.y, Rgb565::CYAN, sec_stroke as u8);
+            // Center dot as solid circle
+            let r_outer: i32 = 8;
+            let c_solid = rgb565_from_888(0x52, 0xC6, 0x6B); // #52C66B
+            co.draw_circle_fb(cx, cy, r_outer, c_solid, true);
+
+            // Update cache
+            cache.sec = Some(sec_end);
+            cache.min = Some(min_end);
+            cache.hour = Some(hour_end);
+        });
+
+        // Flush what the clear and the hands touched (no-op when nothing changed)
+        let _ = co.flush_dirty();
+        return;
+    }
+
+    // Fallback: use embedded-graphics path (may flicker more).
+    draw_hand_line(disp, cx, cy, sec_end, Rgb565::RED, 2);
+    draw_hand_line(disp, cx, cy, min_end, Rgb565::GREEN, 3);
+    draw_hand_line(disp, cx, cy, hour_end, Rgb565::BLUE, 4);
+}
+
+// Analog date window ("Sat 01") baseline below the center
+#[cfg(feature = "esp32s3-disp143Oled")]
+const ANALOG_DATE_DY: i32 = 110;
+#[cfg(feature = "esp32s3-disp143Oled")]
+const ANALOG_DATE_CHARS: i32 = 6;
+
+// Screen rect (inclusive) of the analog date window, outline included
+#[cfg(feature = "esp32s3-disp143Oled")]
+fn analog_date_rect(cx: i32, cy: i32) -> (i32, i32, i32, i32) {
+    let half_w = ANALOG_DATE_CHARS * DIGIT_W as i32 / 2;
+    let top = cy + ANALOG_DATE_DY - FONT_10X20.baseline as i32;
+    (cx - half_w - 1, top - 1, cx + half_w, top + DIGIT_H as i32)
+}
+
+// Weekday + day of month for `day` (days since 1970) into the FB over the face background.
+// Marks the window dirty; the caller's flush sends it.
+#[cfg(feature = "esp32s3-disp143Oled")]
+fn draw_analog_date(co: &mut dyn FastPanel, cx: i32, cy: i32, day: u32) {
+    let dt = unix_to_datetime(day * 86_400);
+    let wd = WEEKDAY_ABBR[dt.weekday() as usize % 7].as_bytes();
+    let buf = [
+        wd[0],
+        wd[1],
+        wd[2],
+        b' ',
+        b'0' + dt.day / 10,
+        b'0' + dt.day % 10,
+    ];
+    let text = core::str::from_utf8(&buf).unwrap_or("??? ??");
+
+    let (x0, y0, x1, y1) = analog_date_rect(cx, cy);
+    let max = (RESOLUTION - 1) as i32;
+    if x0 < 0 || y0 < 0 || x1 > max || y1 > max {
+        return;
+    }
+    let (w, h) = ((x1 - x0 + 1) as usize, (y1 - y0 + 1) as usize);
+    let mut bg = alloc::vec![0u8; w * h * 2];
+    if watch_bg_crop(x0, y0, w, h, &mut bg) {
+        let _ = co.write_rect_fb(x0 as u16, y0 as u16, w as u16, h as u16, &bg);
+    } else {
+        co.fill_rect_fb(x0, y0, x1, y1, CLOCK_OUTLINE);
+    }
+
+    let mut canvas = FbRectCanvas {
+        data: Vec::new(),
+        origin: Point::new(x0, y0),
+        size: Size::new(w as u32, h as u32),
+    };
+    co.save_fb_rect(x0 as u16, y0 as u16, x1 as u16, y1 as u16, &mut canvas.data);
+    let at = Point::new(cx, cy + ANALOG_DATE_DY);
+    let outline_style = MonoTextStyle::new(&FONT_10X20, CLOCK_OUTLINE);
+    for (dx, dy) in OUTLINE_OFFSETS {
+        let p = at + Point::new(dx, dy);
+        let _ = Text::with_alignment(text, p, outline_style, Alignment::Center).draw(&mut canvas);
+    }
+    let style = MonoTextStyle::new(&FONT_10X20, Rgb565::WHITE);
+    let _ = Text::with_alignment(text, at, style, Alignment::Center).draw(&mut canvas);
+    let _ = co.restore_fb_rect(x0 as u16, y0 as u16, x1 as u16, y1 as u16, &canvas.data);
+}
+
+// Draw an annular arc directly to the panel (no framebuffer update, faster, even-aligned writes).
+fn fill_ring_arc_no_fb(
+    drv: &mut dyn FastPanel,
+    cx: i32,
+    cy: i32,
+    r_outer: i32,
+    r_inner: i32,
+    ang0_deg: f32,
+    ang1_deg: f32,
+    color: Rgb565,
+) -> Option<(i32, i32, i32, i32)> {
+    // Normalize angles so ang1 >= ang0 in [0, 360+)
+    let mut ang0 = ang0_deg;
+    let mut ang1 = ang1_deg;
+    while ang0 < 0.0 {
+        ang0 += 360.0;
+        ang1 += 360.0;
+    }
+    while ang1 < ang0 {
+        ang1 += 360.0;
+    }
+    if ang1 <= ang0 {
+        ang1 = ang0 + 360.0;
+    }
+
+    // For small arcs, compute a tighter bounding box based on the arc endpoints
+    // This dramatically speeds up incremental updates
+    let arc_span = ang1 - ang0;
+    let (minx, miny, maxx, maxy) = if arc_span < 350.0 {
+        // Compute bbox from arc endpoints for BOTH inner and outer radii
+        let a0_rad = ang0.to_radians();
+        let a1_rad = ang1.to_radians();
+
+        let cos_a0 = cosf(a0_rad);
+        let sin_a0 = sinf(a0_rad);
+        let cos_a1 = cosf(a1_rad);
+        let sin_a1 = sinf(a1_rad);
+
+        // Start with all 4 arc endpoints (inner/outer at start/end angles)
+        let outer_x0 = cos_a0 * r_outer as f32;
+        let outer_y0 = sin_a0 * r_outer as f32;
+        let outer_x1 = cos_a1 * r_outer as f32;
+        let outer_y1 = sin_a1 * r_outer as f32;
+        let inner_x0 = cos_a0 * r_inner as f32;
+        let inner_y0 = sin_a0 * r_inner as f32;
+        let inner_x1 = cos_a1 * r_inner as f32;
+        let inner_y1 = sin_a1 * r_inner as f32;
+
+        let mut x_min = outer_x0.min(outer_x1).min(inner_x0).min(inner_x1);
+        let mut x_max = outer_x0.max(outer_x1).max(inner_x0).max(inner_x1);
+        let mut y_min = outer_y0.min(outer_y1).min(inner_y0).min(inner_y1);
+        let mut y_max = outer_y0.max(outer_y1).max(inner_y0).max(inner_y1);
+
+        // Check if arc crosses cardinal directions (0°, 90°, 180°, 270°)
+        // and extend bbox accordingly using OUTER radius
+        let check_angle = |target: f32, ang0: f32, ang1: f32| -> bool {
+            let t = if target < ang0 {
+                target + 360.0
+            } else {
+                target
+            };
+            t >= ang0 && t <= ang1
+        };
+
+        if check_angle(0.0, ang0, ang1) {
+            x_max = r_outer as f32;
+        } // right
+        if check_angle(90.0, ang0, ang1) {
+            y_max = r_outer as f32;
+        } // bottom
+        if check_angle(180.0, ang0, ang1) {
+            x_min = -(r_outer as f32);
+        } // left
+        if check_angle(270.0, ang0, ang1) {
+            y_min = -(r_outer as f32);
+        } // top
+
+        // Convert to screen coords with small padding for rounding errors
+        let pad = 2;
+        let minx = ((cx + x_min as i32 - pad).max(0)) & !1;
+        let maxx = ((cx + x_max as i32 + pad).min((RESOLUTION - 1) as i32)) | 1;
+        let miny = ((cy + y_min as i32 - pad).max(0)) & !1;
+        let maxy = ((cy + y_max as i32 + pad).min((RESOLUTION - 1) as i32)) | 1;
+        (minx, miny, maxx, maxy)
+    } else {
+        // Full ring - use full bbox
+        let minx = ((cx - r_outer).max(0)) & !1;
+        let maxx = ((cx + r_outer).min((RESOLUTION - 1) as i32)) | 1;
+        let miny = ((cy - r_outer).max(0)) & !1;
+        let maxy = ((cy + r_outer).min((RESOLUTION - 1) as i32)) | 1;
+        (minx, miny, maxx, maxy)
+    };
+
+    let r2_outer = r_outer * r_outer;
+    let r2_inner = r_inner * r_inner;
+
+    let mut bb: Option<(i32, i32, i32, i32)> = None;
+
+    // Scan rows in 2-pixel bands to satisfy even-write requirement
+    for y0 in (miny..=maxy).step_by(2) {
+        let y_center = y0 + 1;
+        let dy = y_center - cy;
+        // Quick reject if outside outer radius
+        if dy * dy > r2_outer {
+            continue;
+        }
+        let mut run_start: Option<i32> = None;
+        let mut run_end: i32 = 0;
+        for x0 in (minx..=maxx).step_by(2) {
+            let x_center = x0 + 1;
+            let dx = x_center - cx;
+            let d2 = dx * dx + dy * dy;
+            let inside_radial = d2 <= r2_outer && d2 >= r2_inner;
+            let inside_ang = if inside_radial {
+                let mut ang = atan2f(dy as f32, dx as f32).to_degrees();
+                if ang < 0.0 {
+                    ang += 360.0;
+                }
+                if ang < ang0 {
+                    ang += 360.0;
+                }
+                ang >= ang0 && ang <= ang1
+            } else {
+                false
+            };
+
+            if inside_ang {
+                if run_start.is_none() {
+                    run_start = Some(x0);
+                }
+                run_end = x0;
+            } else if let Some(rs) = run_start {
+                let width = (run_end - rs + 2) as u16;
+                let _ = drv.fill_rect_solid_no_fb(rs as u16, y0 as u16, width, 2, color);
+                bb = Some(match bb {
+                    None => (rs, y0, rs + width as i32 - 1, y0 + 1),
+                    Some((bx0, by0, bx1, by1)) => (
+                        bx0.min(rs),
+                        by0.min(y0),
+                        bx1.max(rs + width as i32 - 1),
+                        by1.max(y0 + 1),
+                    ),
+                });
+                run_start = None;
+            }
+        }
+        if let Some(rs) = run_start {
+            let width = (run_end - rs + 2) as u16;
+            let _ = drv.fill_rect_solid_no_fb(rs as u16, y0 as u16, width, 2, color);
+            bb = Some(match bb {
+                None => (rs, y0, rs + width as i32 - 1, y0 + 1),
+                Some((bx0, by0, bx1, by1)) => (
+                    bx0.min(rs),
+                    by0.min(y0),
+                    bx1.max(rs + width as i32 - 1),
+                    by1.max(y0 + 1),
+                ),
+            });
+        }
+    }
+    bb
+}
+
+fn draw_ring_segment(
+    disp: &mut impl PanelRgb565,
+    cx: i32,
+    cy: i32,
+    radius: i32,
+    thickness: i32,
+    start_deg: f32,
+    end_deg: f32,
+    color: Rgb565,
+) {
+    // Draw radial lines at intervals to form ring segment
+    let step = 3.0_f32;
+    let r_inner = radius.saturating_sub(thickness.max(1) - 1);
+
+    // Fast path: draw into FB only and flush once.
+    if let Some(co) = disp.fast_panel() {
+        let mut minx = i32::MAX;
+        let mut miny = i32::MAX;
+        let mut maxx = i32::MIN;
+        let mut maxy = i32::MIN;
+
+        // Draw line and update bbox
+        let mut draw_line = |x0: i32, y0: i32, x1: i32, y1: i32| {
+            if let Some((ax0, ay0, ax1, ay1)) =
+                co.draw_line_fb(x0, y0, x1, y1, color, thickness as u8)
+            {
+                minx = minx.min(ax0 as i32);
+                miny = miny.min(ay0 as i32);
+                maxx = maxx.max(ax1 as i32);
+                maxy = maxy.max(ay1 as i32);
+            }
+        };
+
+        // Draw all radial lines
+        let mut a = start_deg;
+        while a <= end_deg + 0.1 {
+            let ar = a.to_radians();
+            let ox = cx + (cosf(ar) * radius as f32) as i32;
+            let oy = cy + (sinf(ar) * radius as f32) as i32;
+            let ix = cx + (cosf(ar) * r_inner as f32) as i32;
+            let iy = cy + (sinf(ar) * r_inner as f32) as i32;
+            draw_line(ox, oy, ix, iy);
+            a += step;
+        }
+
+        // Flush affected region
+        if minx != i32::MAX {
+            let _ = co.flush_rect_even(
+                minx.clamp(0, (RESOLUTION - 1) as i32) as u16,
+                miny.clamp(0, (RESOLUTION - 1) as i32) as u16,
+                maxx.clamp(0, (RESOLUTION - 1) as i32) as u16,
+                maxy.clamp(0, (RESOLUTION - 1) as i32) as u16,
+            );
+        }
+    } else {
+        // Fallback: use embedded-graphics path (may flicker more).
+        let mut a = start_deg;
+        while a <= end_deg + 0.1 {
+            let ar = a.to_radians();
+            let ox = cx + (cosf(ar) * radius as f32) as i32;
+            let oy = cy + (sinf(ar) * radius as f32) as i32;
+            let ix = cx + (cosf(ar) * r_inner as f32) as i32;
+            let iy = cy + (sinf(ar) * r_inner as f32) as i32;
+            let _ = Line::new(Point::new(ox, oy), Point::new(ix, iy))
+                .into_styled(PrimitiveStyle::with_stroke(color, thickness.max(1) as u32))
+                .draw(disp);
+            a += step;
+        }
+    }
+}
+
+// Progress widgets. Arcs start at 12 o'clock and fill clockwise; the arc ends get a
+// small overlap so 100% closes the ring without a seam.
+const PROGRESS_START_DEG: f32 = -90.0;
+const PROGRESS_CAP_DEG: f32 = 5.0;
+
+// Ring of outer radius `r` and width `thickness` centered on (cx, cy): `bg` all the way
+// round, `fg` over the first `pct` percent. Full redraw; returns the angle the fg arc ends
+// at (the start angle when pct is 0) so callers can grow/shrink it incrementally later.
+pub fn draw_progress_arc(
+    disp: &mut impl PanelRgb565,
+    cx: i32,
+    cy: i32,
+    r: i32,
+    thickness: i32,
+    pct: u8,
+    fg: Rgb565,
+    bg: Rgb565,
+) -> f32 {
+    let pct = pct.min(100);
+    let start = PROGRESS_START_DEG;
+    let end_full = start + 360.0;
+    let fg_start = start - PROGRESS_CAP_DEG;
+    let fg_end = if pct == 100 {
+        end_full + PROGRESS_CAP_DEG
+    } else {
+        start + (pct as f32) * 3.6
+    };
+    let r_inner = (r - thickness).max(0);
+
+    if let Some(co) = disp.fast_panel() {
+        // bg ring a little wider than the fg so stale fg edge blocks get covered
+        let _ = fill_ring_arc_no_fb(
+            co,
+            cx,
+            cy,
+            r + 2,
+            (r_inner - 2).max(0),
+            fg_start,
+            end_full + PROGRESS_CAP_DEG,
+            bg,
+        );
+        if pct == 0 {
+            return fg_start;
+        }
+        let _ = fill_ring_arc_no_fb(co, cx, cy, r, r_inner, fg_start, fg_end, fg);
+    } else {
+        draw_ring_segment(disp, cx, cy, r, thickness + 4, start, end_full, bg);
+        if pct == 0 {
+            return fg_start;
+        }
+        draw_ring_segment(disp, cx, cy, r, thickness, start, fg_end.min(end_full), fg);
+    }
+    fg_end
+}
+
+// Horizontal bar at (x, y) of size w x h: `fg` fills the left `pct` percent, `bg` the rest.
+pub fn draw_progress_bar(
+    disp: &mut impl PanelRgb565,
+    x: i32,
+    y: i32,
+    w: u32,
+    h: u32,
+    pct: u8,
+    fg: Rgb565,
+    bg: Rgb565,
+) {
+    if w == 0 || h == 0 {
+        return;
+    }
+    let fill_w = (w * pct.min(100) as u32 / 100) as i32;
+    let (x1, y1) = (x + w as i32 - 1, y + h as i32 - 1);
+
+    if let Some(co) = disp.fast_panel() {
+        co.fill_rect_fb(x, y, x1, y1, bg);
+        if fill_w > 0 {
+            co.fill_rect_fb(x, y, x + fill_w - 1, y1, fg);
+        }
+        let max = (RESOLUTION - 1) as i32;
+        let _ = co.flush_rect_even(
+            x.clamp(0, max) as u16,
+            y.clamp(0, max) as u16,
+            x1.clamp(0, max) as u16,
+            y1.clamp(0, max) as u16,
+        );
+    } else {
+        let _ = Rectangle::new(Point::new(x, y), Size::new(w, h))
+            .into_styled(PrimitiveStyle::with_fill(bg))
+            .draw(disp);
+        if fill_w > 0 {
+            let _ = Rectangle::new(Point::new(x, y), Size::new(fill_w as u32, h))
+                .into_styled(PrimitiveStyle::with_fill(fg))
+                .draw(disp);
+        }
+    }
+}
+
+fn draw_brightness_ui(disp: &mut impl PanelRgb565) {
+    let pct = brightness_pct();
+    let radius = (RESOLUTION as i32 / 2) + 10;
+    let thickness_fg = 20;
+    let thickness_bg = thickness_fg + 12;
+    let radius_fg_outer = radius;
+    let radius_fg_inner = radius - thickness_fg;
+    let radius_bg_outer = radius + 2;
+    let start = PROGRESS_START_DEG;
+    let end_full = start + 360.0;
+    let end_pct = start + (pct as f32) * 3.6;
+    let bg_ring = Rgb565::BLACK;
+    let fg_ring = rgb565_from_888(0x9F, 0xFF, 0x4A);
+
+    let pad = radius_bg_outer + 4;
+    let x0 = (CENTER - pad).clamp(0, (RESOLUTION - 1) as i32);
+    let x1 = (CENTER + pad).clamp(0, (RESOLUTION - 1) as i32);
+    let y0 = (CENTER - pad).clamp(0, (RESOLUTION - 1) as i32);
+    let y1 = (CENTER + pad).clamp(0, (RESOLUTION - 1) as i32);
+    // Tight text box so we don't wipe nearby graphics.
+    let text_box = (CENTER - 70, CENTER - 20, CENTER + 70, CENTER + 20);
+
+    if let Some(co) = disp.fast_panel() {
+        let last = critical_section::with(|cs| *BRIGHTNESS_LAST.borrow(cs).borrow());
+        let fg_start = start - PROGRESS_CAP_DEG;
+        // Where the fg arc should end for this pct (100% closes the ring with overlap),
+        // matching what draw_progress_arc paints
+        let fg_target = if pct == 100 {
+            end_full + PROGRESS_CAP_DEG
+        } else {
+            start + (pct as f32) * 3.6
+        };
+
+        let painted_end = match last {
+            // Full redraw: background then foreground
+            None => draw_progress_arc(
+                co,
+                CENTER,
+                CENTER,
+                radius_fg_outer,
+                thickness_fg,
+                pct,
+                fg_ring,
+                bg_ring,
+            ),
+            Some((prev_pct, prev_end)) if prev_pct != pct => {
+                // Incremental update. Clear and paint use the fg radii and the same
+                // block test, so clearing (fg_target, prev_end] removes exactly the
+                // blocks an earlier paint could have set and nothing needs repainting.
+                if fg_target > prev_end {
+                    // GROWING: extend the arc from where it actually ends
+                    let _ = fill_ring_arc_no_fb(
+                        co,
+                        CENTER,
+                        CENTER,
+                        radius_fg_outer,
+                        radius_fg_inner,
+                        prev_end.max(fg_start),
+                        fg_target,
+                        fg_ring,
+                    );
+                    fg_target
+                } else if fg_target < prev_end {
+                    // SHRINKING: clear everything past the new end, up to the last painted angle.
+                    // Angles past end_full - 5 wrap onto the arc's start cap, which stays fg
+                    // while pct > 0, so don't clear into it.
+                    let (clear_start, clear_end) = if pct == 0 {
+                        (fg_start, prev_end)
+                    } else {
+                        (fg_target + 0.01, prev_end.min(end_full - 5.0))
+                    };
+                    if clear_end > clear_start {
+                        let _ = fill_ring_arc_no_fb(
+                            co,
+                            CENTER,
+                            CENTER,
+                            radius_fg_outer,
+                            radius_fg_inner,
+                            clear_start,
+                            clear_end,
+                            bg_ring,
+                        );
+                    }
+                    if pct == 0 {
+                        fg_start
+                    } else {
+                        fg_target
+                    }
+                } else {
+                    prev_end
+                }
+            }
+            Some((_, prev_end)) => prev_end,
+        };
+
+        // Update text
+        let (tx0, ty0, tx1, ty1) = text_box;
+        co.fill_rect_fb(tx0, ty0, tx1, ty1, Rgb565::BLACK);
+        let pct_buf = alloc::format!("{}%", pct);
+        // Text goes through embedded-graphics on `disp` (into the FB), then the fast path
+        // flushes the box
+        draw_text(
+            disp,
+            &pct_buf,
+            fg_ring,
+            None,
+            CENTER,
+            CENTER,
+            false,
+            true,
+            Some(&FONT_10X20),
+        );
+
+        critical_section::with(|cs| {
+            *BRIGHTNESS_LAST.borrow(cs).borrow_mut() = Some((pct, painted_end));
+        });
+
+        // Flush only text box
+        let fx0 = (tx0.clamp(0, (RESOLUTION - 1) as i32)) & !1;
+        let fy0 = (ty0.clamp(0, (RESOLUTION - 1) as i32)) & !1;
+        let fx1 = (tx1.clamp(0, (RESOLUTION - 1) as i32) | 1).min((RESOLUTION - 1) as i32);
+        let fy1 = (ty1.clamp(0, (RESOLUTION - 1) as i32) | 1).min((RESOLUTION - 1) as i32);
+        if let Some(co) = disp.fast_panel() {
+            let _ = co.flush_rect_even(fx0 as u16, fy0 as u16, fx1 as u16, fy1 as u16);
+        }
+    } else {
+        // Fallback: small clear and redraw (non-panel path).
+        let _ = Rectangle::new(
+            Point::new(x0, y0),
+            Size::new((x1 - x0 + 1) as u32, (y1 - y0 + 1) as u32),
+        )
+        .into_styled(PrimitiveStyle::with_fill(Rgb565::BLACK))
+        .draw(disp);
+        draw_ring_segment(
+            disp,
+            CENTER,
+            CENTER,
+            radius,
+            thickness_bg,
+            start,
+            end_full,
+            bg_ring,
+        );
+        draw_ring_segment(
+            disp,
+            CENTER,
+            CENTER,
+            radius,
+            thickness_bg,
+            start,
+            end_pct,
+            fg_ring,
+        );
+        draw_ring_segment(
+            disp,
+            CENTER,
+            CENTER,
+            radius,
+            thickness_fg,
+            start,
+            end_pct,
+            fg_ring,
+        );
+        // Text: redraw center text in fallback mode
+        let pct_buf = alloc::format!("{}%", pct);
+        draw_text(
+            disp,
+            &pct_buf,
+            fg_ring,
+            None,
+            CENTER,
+            CENTER - 8,
+            false,
+            true,
+            Some(&FONT_10X20),
+        );
+    }
+}
+
+// Helix opacity over the alien (255 = opaque)
+const TRANSFORM_ALPHA: u8 = 190;
+
+// Inclusive bounding box of the helix, shared by the overlay and the background snapshot.
+fn transform_overlay_bounds() -> (i32, i32, i32, i32) {
+    let amp_max = (RESOLUTION as f32) * 0.26;
+    let pad = (amp_max as i32 + 20).min(CENTER);
+    let max = (RESOLUTION - 1) as i32;
+    (
+        (CENTER - pad).clamp(0, max),
+        (12 - 8).clamp(0, max),
+        (CENTER + pad).clamp(0, max),
+        (RESOLUTION as i32 - 12 + 8).clamp(0, max),
+    )
+}
+
+// Rebuild the FB as black + the current alien and snapshot the helix region, so the
+// overlay can be blended over it each frame.
+fn transform_capture_background(co: &mut dyn FastPanel, alien: AssetId) {
+    co.fill_rect_fb(
+        0,
+        0,
+        (RESOLUTION - 1) as i32,
+        (RESOLUTION - 1) as i32,
+        Rgb565::BLACK,
+    );
+    match load_asset(alien) {
+        Ok((bytes, w, h)) => {
+            let x = (RESOLUTION.saturating_sub(w) / 2) as u16;
+            let y = (RESOLUTION.saturating_sub(h) / 2) as u16;
+            let _ = co.write_rect_fb(x, y, w as u16, h as u16, bytes);
+        }
+        Err(e) => esp_println::println!("transform bg {:?} failed: {:?}", alien, e),
+    }
+    let _ = co.flush_rect_even(0, 0, (RESOLUTION - 1) as u16, (RESOLUTION - 1) as u16);
+
+    let (x0, y0, x1, y1) = transform_overlay_bounds();
+    let mut bg = alloc::vec::Vec::new();
+    co.save_fb_rect(x0 as u16, y0 as u16, x1 as u16, y1 as u16, &mut bg);
+    critical_section::with(|cs| *TRANSFORM_BG.borrow(cs).borrow_mut() = Some(bg));
+}
+
+// Helix rotation speed in rad/s (slower rotation for better 3D illusion)
+const TRANSFORM_SPIN: f32 = 1.6;
+
+fn draw_transform_overlay(disp: &mut impl PanelRgb565, dt: f32) {
+    // DNA-like helix animation with depth sorting for proper 3D illusion.
+    // Phase advances by frame delta (monotonic), kept in [0, 2pi) so it never loses precision.
+    let t = critical_section::with(|cs| {
+        let mut phase = TRANSFORM_PHASE.borrow(cs).borrow_mut();
+        *phase = (*phase + dt * TRANSFORM_SPIN) % (2.0 * core::f32::consts::PI);
+        *phase
+    });
+    let amp_max = (RESOLUTION as f32) * 0.26;
+    let step = 16; // slightly tighter spacing for smoother curve
+    let cx = CENTER;
+    let y_start = 12;
+    let y_end = RESOLUTION as i32 - 12;
+
+    // Front/back color pairs with more contrast for depth
+    let strand_a_front = rgb565_from_888(0xC0, 0xFF, 0x70); // brighter front
+    let strand_a_back = rgb565_from_888(0x40, 0x90, 0x10); // darker back
+    let strand_b_front = rgb565_from_888(0xA8, 0xFF, 0x50);
+    let strand_b_back = rgb565_from_888(0x38, 0x80, 0x08);
+    let rung_front = rgb565_from_888(0xB0, 0xFF, 0x60);
+    let rung_back = rgb565_from_888(0x50, 0x90, 0x18);
+
+    // Base thickness values - will be modulated by depth
+    let strand_thick_base = 6u8;
+    let rung_thick = 3u8;
+
+    // Bounding box for the helix drawing (reuse for clear/flush).
+    let (x0, y0, x1, y1) = transform_overlay_bounds();
+
+    if let Some(co) = disp.fast_panel() {
+        // The previous frame may still be going out by DMA; finish it before touching the FB
+        // (double buffered, it streams from the front buffer and this frame can start).
+        if !co.double_buffered() {
+            co.flush_wait();
+        }
+
+        // Put the preserved background back under the helix region (or black if there is none).
+        let restored = critical_section::with(|cs| {
+            TRANSFORM_BG
+                .borrow(cs)
+                .borrow()
+                .as_ref()
+                .map(|bg| {
+                    co.restore_fb_rect(x0 as u16, y0 as u16, x1 as u16, y1 as u16, bg)
+                        .is_ok()
+                })
+                .unwrap_or(false)
+        });
+        if !restored {
+            co.fill_rect_fb(x0, y0, x1, y1, Rgb565::BLACK);
+        }
+
+        // Collect strand segments for depth-sorted drawing
+        // (y_pos, depth, is_strand_a, prev_point, curr_point)
+        let mut segments: heapless::Vec<(i32, f32, bool, Point, Point), 64> = heapless::Vec::new();
+
+        // Collect rungs with depth info for proper front/back coloring
+        // (y_pos, depth, point_a, point_b, is_front)
+        let mut rungs: heapless::Vec<(i32, f32, Point, Point, bool), 32> = heapless::Vec::new();
+
+        let mut prev_a: Option<Point> = None;
+        let mut prev_b: Option<Point> = None;
+
+        // Generate strand points
+        for (i, y) in (y_start..=y_end).step_by(step).enumerate() {
+            let phase = t + (i as f32) * 0.32;
+            let amp = amp_max * 0.75;
+
+            let off_a = (sinf(phase) * amp) as i32;
+            let off_b = -off_a;
+
+            let xa = cx + off_a;
+            let xb = cx + off_b;
+            let pa = Point::new(xa, y);
+            let pb = Point::new(xb, y);
+
+            // Depth value: cosf gives z-depth (-1 = back, +1 = front)
+            let depth_a = cosf(phase);
+            // let depth_b = -depth_a;
+
+            if let (Some(pa_prev), Some(pb_prev)) = (prev_a, prev_b) {
+                let prev_phase = t + ((i - 1) as f32) * 0.32;
+                let avg_depth_a = (depth_a + cosf(prev_phase)) / 2.0;
+                let avg_depth_b = -avg_depth_a;
+
+                let _ = segments.push((y, avg_depth_a, true, pa_prev, pa));
+                let _ = segments.push((y, avg_depth_b, false, pb_prev, pb));
+            }
+
+            // Draw rungs at fixed Y intervals
+            if i % 3 == 1 {
+                // Rung visibility based on rotation: when strands are at edges (|sinf| high),
+                // the rung is facing us or away. When |sinf| is low, rung is on the side.
+                // Use cosf to determine if rung faces front or back
+                let rung_facing_front = cosf(phase).abs() < 0.7; // rung visible when strands near edges
+                let rung_depth = if rung_facing_front { 0.1 } else { -0.5 };
+                let _ = rungs.push((y, rung_depth, pa, pb, rung_facing_front));
+            }
+
+            prev_a = Some(pa);
+            prev_b = Some(pb);
+        }
+
+        // Sort strands by depth (back-to-front)
+        for i in 0..segments.len() {
+            for j in 0..segments.len().saturating_sub(1 + i) {
+                if segments[j].1 > segments[j + 1].1 {
+                    segments.swap(j, j + 1);
+                }
+            }
+        }
+
+        // Sort rungs by depth too
+        for i in 0..rungs.len() {
+            for j in 0..rungs.len().saturating_sub(1 + i) {
+                if rungs[j].1 > rungs[j + 1].1 {
+                    rungs.swap(j, j + 1);
+                }
+            }
+        }
+
+        // Interleave drawing: back rungs, back strands, front rungs, front strands
+        // Draw back rungs first
+        for &(_y, depth, pa, pb, is_front) in rungs.iter() {
+            if depth < 0.0 {
+                let col = if is_front { rung_front } else { rung_back };
+                let _ = co.blend_line_fb(pa.x, pa.y, pb.x, pb.y, col, rung_thick, TRANSFORM_ALPHA);
+            }
+        }
+
+        // Draw sorted strand segments (back ones first due to sorting)
+        for &(_y, depth, is_a, p_prev, p_curr) in segments.iter() {
+            let depth_factor = (depth + 1.0) / 2.0;
+            let strand_thick = ((strand_thick_base as f32) * (0.5 + 0.7 * depth_factor)) as u8;
+            let strand_thick = strand_thick.max(3).min(9);
+
+            let front_side = depth >= 0.0;
+
+            let (col_main, col_shadow) = if is_a {
+                if front_side {
+                    (strand_a_front, rgb565_from_888(0x70, 0xB0, 0x30))
+                } else {
+                    (strand_a_back, rgb565_from_888(0x28, 0x60, 0x08))
+                }
+            } else {
+                if front_side {
+                    (strand_b_front, rgb565_from_888(0x60, 0xA0, 0x28))
+                } else {
+                    (strand_b_back, rgb565_from_888(0x20, 0x50, 0x04))
+                }
+            };
+
+            let _ = co.blend_line_fb(
+                p_prev.x,
+                p_prev.y,
+                p_curr.x,
+                p_curr.y,
+                col_shadow,
+                strand_thick + 2,
+                TRANSFORM_ALPHA,
+            );
+            let _ = co.blend_line_fb(
+                p_prev.x,
+                p_prev.y,
+                p_curr.x,
+                p_curr.y,
+                col_main,
+                strand_thick,
+                TRANSFORM_ALPHA,
+            );
+        }
+
+        // Draw front rungs last (on top of strands)
+        for &(_y, depth, pa, pb, is_front) in rungs.iter() {
+            if depth >= 0.0 {
+                let col = if is_front { rung_front } else { rung_back };
+                let _ = co.blend_line_fb(pa.x, pa.y, pb.x, pb.y, col, rung_thick, TRANSFORM_ALPHA);
+            }
+        }
+
+        // Flush only the helix region to avoid needless panel churn. DMA-backed so the main
+        // loop keeps running while it goes out; main drives it with `flush_poll`.
+        if co.double_buffered() {
+            let _ = co.swap_buffers();
+        } else {
+            let _ = co.flush_rect_even_dma(x0 as u16, y0 as u16, x1 as u16, y1 as u16);
+        }
+    } else {
+        // Fallback path using embedded-graphics primitives.
+        let strand_thick = strand_thick_base; // use base thickness for fallback
+        let _ = Rectangle::new(
+            Point::new(x0, y0),
+            Size::new((x1 - x0 + 1) as u32, (y1 - y0 + 1) as u32),
+        )
+        .into_styled(PrimitiveStyle::with_fill(Rgb565::BLACK))
+        .draw(disp);
+        let mut prev_a: Option<Point> = None;
+        let mut prev_b: Option<Point> = None;
+
+        // Draw helix strands
+        for (i, y) in (y_start..=y_end).step_by(step).enumerate() {
+            let phase = t + (i as f32) * 0.35;
+            let amp = amp_max * 0.75;
+            let off = (sinf(phase) * amp) as i32;
+            let xa = cx + off;
+            let xb = cx - off;
+            let pa = Point::new(xa, y);
+            let pb = Point::new(xb, y);
+            let front_side = sinf(phase) >= 0.0;
+
+            // Choose colors based on front/back
+            let col_a = if front_side {
+                strand_a_front
+            } else {
+                strand_a_back
+            };
+            let col_b = if front_side {
+                strand_b_back
+            } else {
+                strand_b_front
+            };
+            let col_a_sh = rgb565_from_888(
+                (col_a.r().saturating_mul(3) / 4) as u8,
+                (col_a.g().saturating_mul(3) / 4) as u8,
+                (col_a.b().saturating_mul(3) / 4) as u8,
+            );
+            let col_b_sh = rgb565_from_888(
+                (col_b.r().saturating_mul(3) / 4) as u8,
+                (col_b.g().saturating_mul(3) / 4) as u8,
+                (col_b.b().saturating_mul(3) / 4) as u8,
+            );
+
+            // Connect strands smoothly
+            if let Some(p) = prev_a {
+                let _ = Line::new(p, pa)
+                    .into_styled(PrimitiveStyle::with_stroke(col_a_sh, strand_thick.into()))
+                    .draw(disp);
+                let _ = Line::new(p, pa)
+                    .into_styled(PrimitiveStyle::with_stroke(
+                        col_a,
+                        strand_thick.saturating_sub(2).into(),
+                    ))
+                    .draw(disp);
+            }
+
+            // Connect strands smoothly
+            if let Some(p) = prev_b {
+                let _ = Line::new(p, pb)
+                    .into_styled(PrimitiveStyle::with_stroke(col_b_sh, strand_thick.into()))
+                    .draw(disp);
+                let _ = Line::new(p, pb)
+                    .into_styled(PrimitiveStyle::with_stroke(
+                        col_b,
+                        strand_thick.saturating_sub(2).into(),
+                    ))
+                    .draw(disp);
+            }
+
+            // Curved rung: bend slightly using a midpoint offset for a faux spin effect.
+            let mid_phase = phase + core::f32::consts::FRAC_PI_2;
+            let mid_bend = (sinf(mid_phase) * amp * 0.18) as i32;
+            let mid_x = cx + mid_bend;
+            let mid_y = y + step as i32 / 2;
+            let pm = Point::new(mid_x, mid_y);
+            let col_rung = if front_side { rung_front } else { rung_back };
+
+            // Draw two segments to form a bent rung
+            let _ = Line::new(pa, pm)
+                .into_styled(PrimitiveStyle::with_stroke(col_rung, rung_thick.into()))
+                .draw(disp);
+            let _ = Line::new(pm, pb)
+                .into_styled(PrimitiveStyle::with_stroke(col_rung, rung_thick.into()))
+                .draw(disp);
+
+            prev_a = Some(pa);
+            prev_b = Some(pb);
+        }
+    }
+}
+
+// Clock editor date line, below the time
+const CLOCK_EDIT_DATE_DY: i32 = 36;
+
+fn draw_clock_edit(disp: &mut impl PanelRgb565, ed: ClockEditState) {
+    // Build HH:MM (or hh:MM AM) string from digits
+    let mut buf = [b' '; 8];
+    buf[0] = b'0' + ed.digits[0];
+    buf[1] = b'0' + ed.digits[1];
+    buf[2] = b':';
+    buf[3] = b'0' + ed.digits[2];
+    buf[4] = b'0' + ed.digits[3];
+    let chars_total = match ed.pm {
+        Some(pm) => {
+            buf[6] = if pm { b'P' } else { b'A' };
+            buf[7] = b'M';
+            8
+        }
+        None => 5,
+    };
+    let msg = core::str::from_utf8(&buf[..chars_total]).unwrap_or("00:00");
+
+    let font = &FONT_10X20; // largest built-in mono ASCII font available
+
+    // Draw the time (use larger 10x20 font)
+    draw_text(
+        disp,
+        msg,
+        Rgb565::CYAN,
+        Some(Rgb565::BLACK),
+        CENTER,
+        CENTER,
+        false,
+        true,
+        Some(font),
+    );
+
+    // Date line (YYYY-MM-DD) below the time
+    let mut date = *b"0000-00-00";
+    let mut y = ed.year;
+    for c in date[..4].iter_mut().rev() {
+        *c = b'0' + (y % 10) as u8;
+        y /= 10;
+    }
+    date[5] = b'0' + ed.month / 10;
+    date[6] = b'0' + ed.month % 10;
+    date[8] = b'0' + ed.day / 10;
+    date[9] = b'0' + ed.day % 10;
+    let date_msg = core::str::from_utf8(&date).unwrap_or("0000-00-00");
+    draw_text(
+        disp,
+        date_msg,
+        Rgb565::CYAN,
+        Some(Rgb565::BLACK),
+        CENTER,
+        CENTER + CLOCK_EDIT_DATE_DY,
+        false,
+        true,
+        Some(font),
+    );
+
+    // Underline the active field only (skip the separators); AM/PM and the date fields
+    // span several cells. Lines: 0 = time, 1 = date.
+    let char_w = font.character_size.width as i32;
+    let char_h = font.character_size.height as i32;
+    let lines = [
+        (chars_total as i32, CENTER + char_h / 2 + 2),
+        (
+            date.len() as i32,
+            CENTER + CLOCK_EDIT_DATE_DY + char_h / 2 + 2,
+        ),
+    ];
+    let (line, visual_idx, cells) = match ed.idx.min(7) as i32 {
+        4 => (0, 6, 2),
+        5 => (1, 0, 4),
+        6 => (1, 5, 2),
+        7 => (1, 8, 2),
+        i if i >= 2 => (0, i + 1, 1), // skip colon slot
+        i => (0, i, 1),
+    };
+
+    // Erase the previous underline (it may sit on the other line), then draw the new one
+    for (chars, base_y) in lines {
+        let start_x = CENTER - char_w * chars / 2;
+        Rectangle::new(
+            Point::new(start_x, base_y),
+            Size::new((char_w * chars) as u32, 2),
+        )
+        .into_styled(PrimitiveStyle::with_fill(Rgb565::BLACK))
+        .draw(disp)
+        .ok();
+    }
+    let (chars, base_y) = lines[line];
+    let underline_x = CENTER - char_w * chars / 2 + visual_idx * char_w;
+    let rect = Rectangle::new(
+        Point::new(underline_x, base_y),
+        Size::new((char_w * cells) as u32, 2),
+    );
+    rect.into_styled(PrimitiveStyle::with_fill(Rgb565::CYAN))
+        .draw(disp)
+        .ok();
+}
+
+// Menu backdrop colors: a subtle dark slate fading to black at the bottom
+const BACKDROP_TOP: Rgb565 = Rgb565::new(2, 5, 5);
+const BACKDROP_BOTTOM: Rgb565 = Rgb565::BLACK;
+
+// Full-screen gradient behind menu screens (plain black clear on other panels). Text drawn
+// over it should pass `bg: None` so the gradient shows through.
+fn draw_menu_backdrop(disp: &mut impl PanelRgb565) {
+    if let Some(co) = disp.fast_panel() {
+        let max = (RESOLUTION - 1) as i32;
+        if let Some((x0, y0, x1, y1)) =
+            co.fill_gradient_fb(0, 0, max, max, BACKDROP_TOP, BACKDROP_BOTTOM)
+        {
+            let _ = co.flush_rect_even(x0, y0, x1, y1);
+        }
+    } else {
+        let _ = disp.clear(Rgb565::BLACK);
+    }
+}
+
+fn draw_smash_cal_page(disp: &mut impl PanelRgb565) {
+    draw_text(
+        disp,
+        "Smash Calibration",
+        Rgb565::WHITE,
+        Some(Rgb565::BLACK),
+        CENTER,
+        CENTER - 60,
+        true,
+        true,
+        None,
+    );
+    let progress = critical_section::with(|cs| *SMASH_CAL_PROGRESS.borrow(cs).borrow());
+    let Some(pct) = progress else {
+        draw_text(
+            disp,
+            "Waiting for motion sensor",
+            Rgb565::CYAN,
+            Some(Rgb565::BLACK),
+            CENTER,
+            CENTER,
+            false,
+            true,
+            None,
+        );
+        return;
+    };
+    draw_progress_bar(
+        disp,
+        CENTER - 120,
+        CENTER - 8,
+        240,
+        16,
+        pct,
+        Rgb565::CYAN,
+        Rgb565::new(4, 8, 4),
+    );
+    let (msg, color) = if pct >= 100 {
+        ("Calibrated", Rgb565::GREEN)
+    } else {
+        ("Hold still", Rgb565::WHITE)
+    };
+    draw_text(
+        disp,
+        msg,
+        color,
+        Some(Rgb565::BLACK),
+        CENTER,
+        CENTER + 44,
+        false,
+        true,
+        None,
+    );
+}
+
+// Launcher rows shown at once, the selected one in the middle
+const LAUNCHER_ROWS: i32 = 5;
+const LAUNCHER_ROW_H: i32 = 36;
+
+// Scrolling list of `Page::ALL` titles with the selected entry highlighted
+fn draw_launcher_page(disp: &mut impl PanelRgb565, selected: u8) {
+    draw_text(
+        disp,
+        "Launcher",
+        Rgb565::WHITE,
+        Some(Rgb565::BLACK),
+        CENTER,
+        CENTER - 130,
+        true,
+        true,
+        None,
+    );
+    let n = Page::ALL.len() as i32;
+    let sel = selected as i32 % n;
+    for row in 0..LAUNCHER_ROWS.min(n) {
+        let offset = row - LAUNCHER_ROWS.min(n) / 2;
+        let idx = (sel + offset).rem_euclid(n);
+        let (color, font) = if offset == 0 {
+            (Rgb565::CYAN, Some(&FONT_10X20_LATIN1))
+        } else {
+            (Rgb565::new(12, 24, 12), Some(&FONT_6X10))
+        };
+        draw_text(
+            disp,
+            Page::ALL[idx as usize].title(),
+            color,
+            Some(Rgb565::BLACK),
+            CENTER,
+            CENTER + offset * LAUNCHER_ROW_H,
+            false,
+            true,
+            font,
+        );
+    }
+}
+
+// Diagnostics: one line per subsystem, redrawn once a second by the main loop
+fn draw_service_page(disp: &mut impl PanelRgb565) {
+    draw_text(
+        disp,
+        "Service",
+        Rgb565::WHITE,
+        Some(Rgb565::BLACK),
+        CENTER,
+        CENTER - 90,
+        true,
+        true,
+        None,
+    );
+
+    let secs = uptime_ms() / 1000;
+    let battery = match battery_pct() {
+        Some(pct) => alloc::format!("Battery {}%", pct),
+        None => alloc::string::String::from("Battery --"),
+    };
+    let temperature = match temperature_stats() {
+        Some((now_c, _, _)) => {
+            let mut buf = [0u8; 10];
+            alloc::format!(
+                "IMU temp {}",
+                format_temperature(now_c, TempUnit::Celsius, &mut buf)
+            )
+        }
+        None => alloc::string::String::from("IMU temp --"),
+    };
+    let (shift_x, shift_y) = pixel_shift();
+    let lines = [
+        alloc::format!(
+            "Uptime {}:{:02}:{:02}",
+            secs / 3600,
+            secs / 60 % 60,
+            secs % 60
+        ),
+        battery,
+        temperature,
+        alloc::format!("Clock {}", if clock_reliable() { "set" } else { "not set" }),
+        alloc::format!("Sensor {}", sensor_health().label()),
+        alloc::format!("Brightness {}%", brightness_pct()),
+        alloc::format!("Wake {}", wake_sources().label()),
+        alloc::format!("Pixel shift {},{}", shift_x, shift_y),
+    ];
+    for (i, line) in lines.iter().enumerate() {
+        draw_text(
+            disp,
+            line,
+            Rgb565::CYAN,
+            Some(Rgb565::BLACK),
+            CENTER,
+            CENTER - 50 + i as i32 * 24,
+            false,
+            true,
+            None,
+        );
+    }
+}
+
+fn draw_temperature_page(disp: &mut impl PanelRgb565) {
+    let unit = temp_unit();
+    draw_text(
+        disp,
+        "Temperature",
+        Rgb565::WHITE,
+        Some(Rgb565::BLACK),
+        CENTER,
+        CENTER - 60,
+        true,
+        true,
+        None,
+    );
+
+    let Some((now_c, min_c, max_c)) = temperature_stats() else {
+        draw_text(
+            disp,
+            "--",
+            Rgb565::CYAN,
+            Some(Rgb565::BLACK),
+            CENTER,
+            CENTER,
+            false,
+            true,
+            None,
+        );
+        return;
+    };
+
+    // Current value
+    let mut buf = [0u8; 10];
+    let msg = format_temperature(now_c, unit, &mut buf);
+    draw_text(
+        disp,
+        msg,
+        Rgb565::CYAN,
+        Some(Rgb565::BLACK),
+        CENTER,
+        CENTER,
+        false,
+        true,
+        Some(&FONT_10X20_LATIN1),
+    );
+
+    // Min / max since boot or last reset
+    let mut min_buf = [0u8; 10];
+    let mut max_buf = [0u8; 10];
+    let line = alloc::format!(
+        "{} / {}",
+        format_temperature(min_c, unit, &mut min_buf),
+        format_temperature(max_c, unit, &mut max_buf)
+    );
+    draw_text(
+        disp,
+        &line,
+        Rgb565::WHITE,
+        Some(Rgb565::BLACK),
+        CENTER,
+        CENTER + 40,
+        false,
+        true,
+        Some(&FONT_10X20_LATIN1),
+    );
+    draw_text(
+        disp,
+        "min / max - hold select to reset",
+        Rgb565::WHITE,
+        Some(Rgb565::BLACK),
+        CENTER,
+        CENTER + 80,
+        false,
+        true,
+        Some(&FONT_6X10),
+    );
+}
+
+fn ensure_watch_background_loaded() -> bool {
+    // Decompress watch background into PSRAM if not already done
+    critical_section::with(|cs| {
+        if WATCH_BG.borrow(cs).borrow().is_some() {
+            return true;
+        }
+
+        // Decompress now
+        match decode_asset_expect(WATCH_BG_IMAGE, RESOLUTION, RESOLUTION) {
+            Ok(decompressed) => {
+                *WATCH_BG.borrow(cs).borrow_mut() = Some(decompressed);
+                true
+            }
+            Err(e) => {
+                esp_println::println!("watch background decode failed: {:?}", e);
+                false
+            }
+        }
+    })
+}
+
+// Draw from already-decompressed bytes (used by cache on OLED).
+// A size mismatch is logged; use `try_draw_image_bytes` to handle it yourself.
+pub fn draw_image_bytes(
+    disp: &mut impl PanelRgb565,
+    bytes: &[u8],
+    w: u32,
+    h: u32,
+    clear: bool,
+    update_fb: bool,
+) {
+    if let Err(e) = try_draw_image_bytes(disp, bytes, w, h, clear, update_fb) {
+        esp_println::println!("draw_image_bytes {}x{}: {:?}", w, h, e);
+    }
+}
+
+// Same as `draw_image_bytes`, but reports a w*h*2 vs. byte length mismatch instead of drawing nothing silently
+pub fn try_draw_image_bytes(
+    disp: &mut impl PanelRgb565,
+    bytes: &[u8],
+    w: u32,
+    h: u32,
+    clear: bool,
+    update_fb: bool,
+) -> Result<(), AssetError> {
+    let (x, y) = shifted_origin(w, h);
+    critical_section::with(|cs| *SHIFTED_ITEM.borrow(cs).borrow_mut() = Some((x, y, w, h)));
+    try_draw_image_bytes_at(disp, bytes, x, y, w, h, clear, update_fb)
+}
+
+// Draw with the top-left corner at (x, y) instead of centered. Positions are absolute (no
+// pixel shift or wrist mirroring) and may be partly off-screen; the image is clipped.
+pub fn draw_image_bytes_at(
+    disp: &mut impl PanelRgb565,
+    bytes: &[u8],
+    x: i32,
+    y: i32,
+    w: u32,
+    h: u32,
+    clear: bool,
+    update_fb: bool,
+) {
+    if let Err(e) = try_draw_image_bytes_at(disp, bytes, x, y, w, h, clear, update_fb) {
+        esp_println::println!("draw_image_bytes_at {}x{} @({}, {}): {:?}", w, h, x, y, e);
+    }
+}
+
+// `draw_image_bytes_at` that returns the length mismatch instead of logging it
+pub fn try_draw_image_bytes_at(
+    disp: &mut impl PanelRgb565,
+    bytes: &[u8],
+    x: i32,
+    y: i32,
+    w: u32,
+    h: u32,
+    clear: bool,
+    update_fb: bool,
+) -> Result<(), AssetError> {
+    // Validate size first, so a bad blob leaves the screen as it was
+    let expected = rgb565_len(w, h)?;
+    if bytes.len() != expected {
+        return Err(AssetError::LengthMismatch {
+            expected,
+            actual: bytes.len(),
+        });
+    }
+    // Clear background if requested
+    if clear {
+        if !update_fb {
+            if let Some(co) = disp.fast_panel() {
+                let _ = co.fill_rect_solid_no_fb(
+                    0,
+                    0,
+                    RESOLUTION as u16,
+                    RESOLUTION as u16,
+                    Rgb565::BLACK,
+                );
+            } else {
+                let _ = disp.clear(Rgb565::BLACK);
+            }
+        } else {
+            let _ = disp.clear(Rgb565::BLACK);
+        }
+    }
+
+    // Try the fast raw blit if the backend has one (see `FastPanel`).
+    if let Some(co) = disp.fast_panel() {
+        // The raw blit needs an on-screen window, so cut the visible part out first
+        let lim = RESOLUTION as i32;
+        let (cx0, cy0) = (x.max(0), y.max(0));
+        let (cx1, cy1) = ((x + w as i32).min(lim), (y + h as i32).min(lim));
+        if cx0 >= cx1 || cy0 >= cy1 {
+            return Ok(()); // entirely off-screen
+        }
+        let (cw, ch) = ((cx1 - cx0) as usize, (cy1 - cy0) as usize);
+        let clipped_rows;
+        let data = if cw == w as usize && ch == h as usize {
+            bytes
+        } else {
+            let mut out = Vec::with_capacity(cw * ch * 2);
+            for row in 0..ch {
+                let off = (((cy0 - y) as usize + row) * w as usize + (cx0 - x) as usize) * 2;
+                out.extend_from_slice(&bytes[off..off + cw * 2]);
+            }
+            clipped_rows = out;
+            &clipped_rows[..]
+        };
+        let res = if update_fb {
+            co.blit_rect_be_fast(cx0 as u16, cy0 as u16, cw as u16, ch as u16, data)
+        } else {
+            co.blit_rect_be_fast_no_fb(cx0 as u16, cy0 as u16, cw as u16, ch as u16, data)
+        };
+        if let Err(e) = res {
+            esp_println::println!("fast blit failed: {:?}; fallback", e);
+            let raw = ImageRawBE::<Rgb565>::new(bytes, w);
+            let _ = Image::new(&raw, Point::new(x, y)).draw(disp);
+        }
+    } else {
+        // embedded-graphics clips against the target itself
+        let raw = ImageRawBE::<Rgb565>::new(bytes, w);
+        let _ = Image::new(&raw, Point::new(x, y)).draw(disp);
+    }
+    Ok(())
+}
+
+// Optional asset header, prepended to the zlib stream by `pack_assets.py --header`:
+//   magic "RWIM" | version u8 | reserved u8 | width u16 LE | height u16 LE | crc32 u32 LE
+// The crc32 (IEEE) covers the decompressed RGB565 bytes. Blobs without the magic are
+// treated as legacy headerless zlib streams and only get a length check.
+const ASSET_MAGIC: [u8; 4] = *b"RWIM";
+const ASSET_VERSION: u8 = 1;
+const ASSET_HEADER_LEN: usize = 14;
+
+// Asset decode errors
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AssetError {
+    NoHeader,
+    Truncated,
+    UnsupportedVersion(u8),
+    Decompress,
+    DimensionMismatch {
+        expected: (u32, u32),
+        actual: (u32, u32),
+    },
+    LengthMismatch {
+        expected: usize,
+        actual: usize,
+    },
+    CrcMismatch {
+        expected: u32,
+        actual: u32,
+    },
+    SpriteIndex {
+        index: u16,
+        count: u16,
+    },
+    // The decode buffer could not be allocated (PSRAM exhausted)
+    OutOfMemory {
+        bytes: usize,
+    },
+    // w * h * 2 overflows usize (a corrupt header or bogus dimensions)
+    Oversize {
+        width: u32,
+        height: u32,
+    },
+}
+
+// Byte length of a w x h RGB565 image
+fn rgb565_len(w: u32, h: u32) -> Result<usize, AssetError> {
+    (w as usize)
+        .checked_mul(h as usize)
+        .and_then(|px| px.checked_mul(2))
+        .ok_or(AssetError::Oversize {
+            width: w,
+            height: h,
+        })
+}
+
+// CRC-32 (IEEE 802.3, reflected), table built at compile time
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut c = i as u32;
+        let mut k = 0;
+        while k < 8 {
+            c = if c & 1 != 0 {
+                0xEDB8_8320 ^ (c >> 1)
+            } else {
+                c >> 1
+            };
+            k += 1;
+        }
+        table[i] = c;
+        i += 1;
+    }
+    table
+};
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &b in data {
+        crc = CRC32_TABLE[((crc ^ b as u32) & 0xFF) as usize] ^ (crc >> 8);
+    }
+    !crc
+}
+
+// Inflate a zlib stream of at most `need` bytes. The output buffer is reserved up front
+// with try_reserve, so running out of PSRAM comes back as OutOfMemory instead of aborting
+// inside the allocator. The result is exactly sized, so leaking it never reallocates.
+fn inflate_zlib_exact(src: &[u8], need: usize) -> Result<Vec<u8>, AssetError> {
+    let mut out: Vec<u8> = Vec::new();
+    out.try_reserve_exact(need)
+        .map_err(|_| AssetError::OutOfMemory { bytes: need })?;
+    out.resize(need, 0);
+    let mut state = alloc::boxed::Box::<DecompressorOxide>::default();
+    let flags = inflate_flags::TINFL_FLAG_PARSE_ZLIB_HEADER
+        | inflate_flags::TINFL_FLAG_USING_NON_WRAPPING_OUTPUT_BUF;
+    let (status, _, written) = decompress(&mut state, src, &mut out, 0, flags);
+    if status != TINFLStatus::Done {
+        return Err(AssetError::Decompress);
+    }
+    out.truncate(written);
+    Ok(out)
+}
+
+// Decode a headered asset blob, validating dimensions, length and crc32.
+// Returns the decompressed RGB565 BE bytes and the width/height from the header.
+pub fn decode_asset(blob: &[u8]) -> Result<(Vec<u8>, u32, u32), AssetError> {
+    if blob.len() < ASSET_MAGIC.len() || blob[..ASSET_MAGIC.len()] != ASSET_MAGIC {
+        return Err(AssetError::NoHeader);
+    }
+    if blob.len() < ASSET_HEADER_LEN {
+        return Err(AssetError::Truncated);
+    }
+    let version = blob[4];
+    if version != ASSET_VERSION {
+        return Err(AssetError::UnsupportedVersion(version));
+    }
+    let w = u16::from_le_bytes([blob[6], blob[7]]) as u32;
+    let h = u16::from_le_bytes([blob[8], blob[9]]) as u32;
+    let crc = u32::from_le_bytes([blob[10], blob[11], blob[12], blob[13]]);
+
+    let need = rgb565_len(w, h)?;
+    let data = inflate_zlib_exact(&blob[ASSET_HEADER_LEN..], need)?;
+    if data.len() != need {
+        return Err(AssetError::LengthMismatch {
+            expected: need,
+            actual: data.len(),
+        });
+    }
+    let actual = crc32(&data);
+    if actual != crc {
+        return Err(AssetError::CrcMismatch {
+            expected: crc,
+            actual,
+        });
+    }
+    Ok((data, w, h))
+}
+
+// Decode a blob that is expected to be w x h, accepting both headered and legacy blobs.
+fn decode_asset_expect(blob: &[u8], w: u32, h: u32) -> Result<Vec<u8>, AssetError> {
+    match decode_asset(blob) {
+        Ok((data, bw, bh)) => {
+            if (bw, bh) != (w, h) {
+                return Err(AssetError::DimensionMismatch {
+                    expected: (w, h),
+                    actual: (bw, bh),
+                });
+            }
+            Ok(data)
+        }
+        Err(AssetError::NoHeader) => {
+            // Legacy headerless zlib: the length check is all we can do.
+            let need = rgb565_len(w, h)?;
+            let data = inflate_zlib_exact(blob, need)?;
+            if data.len() != need {
+                return Err(AssetError::LengthMismatch {
+                    expected: need,
+                    actual: data.len(),
+                });
+            }
+            Ok(data)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+// Map asset id to cache slot index, dimensions, and compressed blob
+fn asset_meta(id: AssetId) -> (usize, u32, u32, &'static [u8]) {
+    match id {
+        AssetId::Alien1 => (0, 308, 374, ALIEN1_IMAGE),
+        AssetId::Alien2 => (1, 308, 374, ALIEN2_IMAGE),
+        AssetId::Alien3 => (2, 308, 374, ALIEN3_IMAGE),
+        AssetId::Alien4 => (3, 308, 374, ALIEN4_IMAGE),
+        AssetId::Alien5 => (4, 308, 374, ALIEN5_IMAGE),
+        AssetId::Alien6 => (5, 308, 374, ALIEN6_IMAGE),
+        AssetId::Alien7 => (6, 308, 374, ALIEN7_IMAGE),
+        AssetId::Alien8 => (7, 308, 374, ALIEN8_IMAGE),
+        AssetId::Alien9 => (8, 308, 374, ALIEN9_IMAGE),
+        AssetId::Alien10 => (9, 308, 374, ALIEN10_IMAGE),
+        AssetId::Logo => (10, 466, 466, ALIEN_LOGO),
+        AssetId::InfoPage => (11, 466, 466, INFO_PAGE_IMAGE),
+        AssetId::SettingsImage => (12, 400, 344, SETTINGS_IMAGE),
+        AssetId::WatchIcon => (13, 316, 316, WATCH_ICON_IMAGE),
+        AssetId::StatusIcons => (14, 96, 48, STATUS_ICONS_IMAGE),
+    }
+}
+
+fn asset_id_for_state(s: OmnitrixState) -> AssetId {
+    match s {
+        OmnitrixState::Alien1 => AssetId::Alien1,
+        OmnitrixState::Alien2 => AssetId::Alien2,
+        OmnitrixState::Alien3 => AssetId::Alien3,
+        OmnitrixState::Alien4 => AssetId::Alien4,
+        OmnitrixState::Alien5 => AssetId::Alien5,
+        OmnitrixState::Alien6 => AssetId::Alien6,
+        OmnitrixState::Alien7 => AssetId::Alien7,
+        OmnitrixState::Alien8 => AssetId::Alien8,
+        OmnitrixState::Alien9 => AssetId::Alien9,
+        OmnitrixState::Alien10 => AssetId::Alien10,
+    }
+}
+
+// Get a cached asset, decompressing it into PSRAM first if needed
+pub fn load_asset(id: AssetId) -> Result<(&'static [u8], u32, u32), AssetError> {
+    let (idx, w, h, blob) = asset_meta(id);
+    critical_section::with(|cs| {
+        let slot = ASSETS.borrow(cs).borrow()[idx];
+        if let Some(d) = slot.data {
+            return Ok((d, slot.w, slot.h));
+        }
+        let tmp = decode_asset_expect(blob, w, h)?;
+        let leaked: &'static [u8] = alloc::boxed::Box::leak(tmp.into_boxed_slice());
+        ASSETS.borrow(cs).borrow_mut()[idx] = AssetSlot {
+            data: Some(leaked),
+            w,
+            h,
+        };
+        Ok((leaked, w, h))
+    })
+}
+
+// Pre-cache a compressed asset into PSRAM. Errors are logged and returned, so callers can
+// tell an OutOfMemory apart from a bad blob.
+pub fn precache_asset(id: AssetId) -> Result<(), AssetError> {
+    load_asset(id).map(|_| ()).map_err(|e| {
+        esp_println::println!("asset {:?} decode failed: {:?}", id, e);
+        e
+    })
+}
+
+// Draw a cached asset centered (no FB mirror); on failure log it and show a fallback label
+fn draw_cached_asset(disp: &mut impl PanelRgb565, id: AssetId) {
+    let res = load_asset(id)
+        .and_then(|(bytes, w, h)| try_draw_image_bytes(disp, bytes, w, h, false, false));
+    if let Err(e) = res {
+        esp_println::println!("asset {:?} draw failed: {:?}", id, e);
+        draw_text(
+            disp,
+            "Asset error",
+            Rgb565::RED,
+            Some(Rgb565::BLACK),
+            CENTER,
+            CENTER,
+            true,
+            true,
+            None,
+        );
+    }
+}
+
+// Sprite atlases: one cached asset cut into a grid of equal cells, so small glyphs
+// don't each need their own AssetId / cache slot.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AtlasId {
+    StatusIcons,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct SpriteAtlas {
+    pub asset: AssetId,
+    pub cell_w: u32,
+    pub cell_h: u32,
+    pub cols: u32,
+    pub rows: u32,
+}
+
+impl SpriteAtlas {
+    pub const fn count(&self) -> u32 {
+        self.cols * self.rows
+    }
+}
+
+pub fn atlas_meta(id: AtlasId) -> SpriteAtlas {
+    match id {
+        AtlasId::StatusIcons => SpriteAtlas {
+            asset: AssetId::StatusIcons,
+            cell_w: 24,
+            cell_h: 24,
+            cols: 4,
+            rows: 2,
+        },
+    }
+}
+
+// Draw one atlas cell with its top-left at (x, y), no FB mirror.
+// Uses the strided blit on the CO5300; falls back to an embedded-graphics sub-image
+// elsewhere or when the cell is partly off-screen.
+pub fn draw_sprite(
+    disp: &mut impl PanelRgb565,
+    atlas_id: AtlasId,
+    index: u16,
+    x: i32,
+    y: i32,
+) -> Result<(), AssetError> {
+    let atlas = atlas_meta(atlas_id);
+    if index as u32 >= atlas.count() {
+        return Err(AssetError::SpriteIndex {
+            index,
+            count: atlas.count() as u16,
+        });
+    }
+    let (bytes, aw, ah) = load_asset(atlas.asset)?;
+    if aw < atlas.cols * atlas.cell_w || ah < atlas.rows * atlas.cell_h {
+        return Err(AssetError::DimensionMismatch {
+            expected: (atlas.cols * atlas.cell_w, atlas.rows * atlas.cell_h),
+            actual: (aw, ah),
+        });
+    }
+    let sx = (index as u32 % atlas.cols) * atlas.cell_w;
+    let sy = (index as u32 / atlas.cols) * atlas.cell_h;
+
+    if let Some(co) = disp.fast_panel() {
+        if x >= 0 && y >= 0 {
+            let start = ((sy * aw + sx) * 2) as usize;
+            let res = co.blit_rect_be_strided_no_fb(
+                x as u16,
+                y as u16,
+                atlas.cell_w as u16,
+                atlas.cell_h as u16,
+                &bytes[start..],
+                aw as u16,
+            );
+            if res.is_ok() {
+                return Ok(());
+            }
+        }
+    }
+
+    let raw = ImageRawBE::<Rgb565>::new(bytes, aw);
+    let cell = raw.sub_image(&Rectangle::new(
+        Point::new(sx as i32, sy as i32),
+        Size::new(atlas.cell_w, atlas.cell_h),
+    ));
+    let _ = Image::new(&cell, Point::new(x, y)).draw(disp);
+    Ok(())
+}
+
+// Top-of-screen row of active status icons, centered (round panel clips the corners)
+const STATUS_ROW_Y: i32 = 36;
+const STATUS_ICON_GAP: i32 = 6;
+
+fn draw_status_row(disp: &mut impl PanelRgb565) {
+    let atlas = atlas_meta(AtlasId::StatusIcons);
+    let flags = critical_section::with(|cs| *STATUS_FLAGS.borrow(cs).borrow());
+    let n = StatusIcon::ALL
+        .iter()
+        .filter(|i| flags & (1u8 << (**i as u8)) != 0)
+        .count() as i32;
+    if n == 0 {
+        return;
+    }
+    let cw = atlas.cell_w as i32;
+    let total = n * cw + (n - 1) * STATUS_ICON_GAP;
+    let mut x = CENTER - total / 2;
+    for icon in StatusIcon::ALL {
+        if flags & (1u8 << (icon as u8)) == 0 {
+            continue;
+        }
+        let ix = layout_x(x, cw);
+        if let Err(e) = draw_sprite(disp, AtlasId::StatusIcons, icon as u16, ix, STATUS_ROW_Y) {
+            esp_println::println!("status icon {:?}: {:?}", icon, e);
+            return;
+        }
+        x += cw + STATUS_ICON_GAP;
+    }
+}
+
+// Pre-cache all (call once at boot), returns how many were cached. Stops at the first
+// failure and returns it; once PSRAM runs out there is no point trying the rest.
+pub fn precache_all() -> Result<usize, AssetError> {
+    let mut ok = 0;
+    for id in [
+        AssetId::Alien1,
+        AssetId::Alien2,
+        AssetId::Alien3,
+        AssetId::Alien4,
+        AssetId::Alien5,
+        AssetId::Alien6,
+        AssetId::Alien7,
+        AssetId::Alien8,
+        AssetId::Alien9,
+        AssetId::Alien10,
+        AssetId::Logo,
+        AssetId::SettingsImage,
+        AssetId::WatchIcon,
+        AssetId::StatusIcons,
+    ] {
+        precache_asset(id)?;
+        ok += 1;
+    }
+    Ok(ok)
+}
+
+// Get cached bytes and dims
+pub fn get_cached_asset(id: AssetId) -> Option<(&'static [u8], u32, u32)> {
+    let (idx, _, _, _) = asset_meta(id);
+    critical_section::with(|cs| {
+        let slot = ASSETS.borrow(cs).borrow()[idx];
+        slot.data.map(|d| (d, slot.w, slot.h))
+    })
+}
+
+// helper function to update the display based on UI_STATE
+// What one `update_ui` call did: whether anything was drawn, and (on the CO5300) the
+// bounding box of everything that reached the panel, inclusive.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct RenderReport {
+    pub drew: bool,
+    pub dirty: Option<(u16, u16, u16, u16)>,
+}
+
+pub fn update_ui(disp: &mut impl PanelRgb565, state: UiState, redraw: bool) -> RenderReport {
+    // If caller does not want a redraw this cycle, bail out early.
+    if !redraw {
+        return RenderReport {
+            drew: false,
+            dirty: None,
+        };
+    }
+
+    if let Some(co) = disp.fast_panel() {
+        co.take_touched_rect(); // drop anything written outside update_ui
+    }
+
+    draw_ui(disp, state);
+
+    if let Some(co) = disp.fast_panel() {
+        let dirty = co.take_touched_rect();
+        return RenderReport {
+            drew: dirty.is_some(),
+            dirty,
+        };
+    }
+    // Other backends don't report what they touched; assume the page was drawn.
+    RenderReport {
+        drew: true,
+        dirty: None,
+    }
+}
+
+// Draw the current page/dialog (the body of `update_ui`).
+fn draw_ui(disp: &mut impl PanelRgb565, state: UiState) {
+    // Clear when:
+    // - entering Omnitrix from another page, OR
+    // - exiting Transform dialog while staying in Omnitrix
+    let current_kind = match state.page {
+        Page::Main(_) => PageKind::Main,
+        Page::Settings(_) => PageKind::Settings,
+        Page::Omnitrix(_) => PageKind::Omnitrix,
+        Page::EasterEgg => PageKind::EasterEgg,
+        Page::Watch(_) => PageKind::Watch,
+        Page::Temperature => PageKind::Temperature,
+        Page::Flashlight => PageKind::Flashlight,
+        Page::SmashCalibration => PageKind::SmashCalibration,
+        Page::Service => PageKind::Service,
+        Page::Launcher(_) => PageKind::Launcher,
+    };
+    let current_transform_active = matches!(state.page, Page::Omnitrix(_))
+        && matches!(state.dialog, Some(Dialog::TransformPage));
+
+    // Full repaint requested (or the drop warning just closed): wipe the panel. Pixel shift
+    // moved: clear only the uncovered strips. Either way, forget page trackers so
+    // everything repaints at the current offset.
+    let (full_repaint, shift_moved) = critical_section::with(|cs| {
+        let drop_closed = state.dialog.is_none()
+            && core::mem::replace(&mut *LAST_DROP_ACTIVE.borrow(cs).borrow_mut(), false);
+        let full = core::mem::replace(&mut *PIXEL_SHIFT_DIRTY.borrow(cs).borrow_mut(), false)
+            || drop_closed;
+        let moved = core::mem::replace(&mut *PIXEL_SHIFT_MOVED.borrow(cs).borrow_mut(), false);
+        if full || moved {
+            *LAST_PAGE_KIND.borrow(cs).borrow_mut() = None;
+            *LAST_WATCH_STATE.borrow(cs).borrow_mut() = None;
+            *LAST_TRANSFORM_ACTIVE.borrow(cs).borrow_mut() = false;
+            *BRIGHTNESS_LAST.borrow(cs).borrow_mut() = None;
+        }
+        (full, moved)
+    });
+    if shift_moved && !full_repaint {
+        clear_shift_strips(disp);
+    }
+    if full_repaint {
+        if let Some(co) = disp.fast_panel() {
+            co.fill_rect_fb(
+                0,
+                0,
+                (RESOLUTION - 1) as i32,
+                (RESOLUTION - 1) as i32,
+                Rgb565::BLACK,
+            );
+            let _ = co.flush_rect_even(0, 0, (RESOLUTION - 1) as u16, (RESOLUTION - 1) as u16);
+        } else {
+            let _ = disp.clear(Rgb565::BLACK);
+        }
+    }
+
+    let should_clear_no_fb = critical_section::with(|cs| {
+        let mut last_kind = LAST_PAGE_KIND.borrow(cs).borrow_mut();
+        let mut last_tx = LAST_OMNI_TRANSFORM_ACTIVE.borrow(cs).borrow_mut();
+
+        let entering_omni =
+            current_kind == PageKind::Omnitrix && *last_kind != Some(PageKind::Omnitrix);
+        let exiting_transform =
+            (*last_tx) && current_kind == PageKind::Omnitrix && !current_transform_active;
+
+        // update trackers for next frame
+        *last_kind = Some(current_kind);
+        *last_tx = current_transform_active;
+
+        entering_omni || exiting_transform
+    });
+
+    if should_clear_no_fb {
+        let _ = if let Some(co) = disp.fast_panel() {
+            co.fill_rect_solid_no_fb(0, 0, RESOLUTION as u16, RESOLUTION as u16, Rgb565::BLACK)
+                .ok();
+        } else {
+            disp.clear(Rgb565::BLACK).ok();
+        };
+    }
+
+    if let Some(dialog) = state.dialog {
+        match dialog {
+            Dialog::TransformPage => {
+                // On first entry into Transform dialog, hard clear the whole screen.
+                let entering = critical_section::with(|cs| {
+                    let mut last = LAST_TRANSFORM_ACTIVE.borrow(cs).borrow_mut();
+                    let was = *last;
+                    *last = true;
+                    !was
                 });
                 if entering {
-                    if let Some(co) = (disp as &mut dyn Any)
-                        .downcast_mut::<crate::display::DisplayType<'static>>()
-                    {
-                        let _ = co.fill_rect_solid_no_fb(
-                            0,
-                            0,
-                            RESOLUTION as u16,
-                            RESOLUTION as u16,
-                            Rgb565::BLACK,
-                        );
-                        co.fill_rect_fb(
-                            0,
-                            0,
-                            (RESOLUTION - 1) as i32,
-                            (RESOLUTION - 1) as i32,
-                            Rgb565::BLACK,
-                        );
+                    if let Some(co) = disp.fast_panel() {
+                        // Keep the alien visible under a semi-transparent helix.
+                        match state.page {
+                            Page::Omnitrix(o) => {
+                                transform_capture_background(co, asset_id_for_state(o))
+                            }
+                            _ => {
+                                co.fill_rect_fb(
+                                    0,
+                                    0,
+                                    (RESOLUTION - 1) as i32,
+                                    (RESOLUTION - 1) as i32,
+                                    Rgb565::BLACK,
+                                );
+                                let _ = co.flush_rect_even(
+                                    0,
+                                    0,
+                                    (RESOLUTION - 1) as u16,
+                                    (RESOLUTION - 1) as u16,
+                                );
+                            }
+                        }
+                    } else {
+                        let _ = disp.clear(Rgb565::BLACK);
+                    }
+                }
+
+                // Capped at ~60 fps; skipped frames leave the last helix on screen
+                let dt = critical_section::with(|cs| {
+                    let mut anim = TRANSFORM_ANIM.borrow(cs).borrow_mut();
+                    if entering {
+                        *anim = AnimTimer::new();
+                    }
+                    anim.tick()
+                });
+                if let Some(dt) = dt {
+                    draw_transform_overlay(disp, dt);
+                }
+            }
+            Dialog::DropWarning => {
+                // Static screen, drawn once on entry
+                let entering = critical_section::with(|cs| {
+                    !core::mem::replace(&mut *LAST_DROP_ACTIVE.borrow(cs).borrow_mut(), true)
+                });
+                if entering {
+                    draw_drop_warning(disp);
+                }
+            }
+        }
+        return;
+    }
+
+    // Reset watch-state tracker if we’re not on the Watch page.
+    if !matches!(state.page, Page::Watch(_)) {
+        critical_section::with(|cs| {
+            *LAST_WATCH_STATE.borrow(cs).borrow_mut() = None;
+            *WATCH_BG.borrow(cs).borrow_mut() = None; // free background when leaving watch page
+            *LAST_WATCH_EDIT_ACTIVE.borrow(cs).borrow_mut() = false;
+        });
+    }
+    let entering_brightness = critical_section::with(|cs| {
+        let mut last = LAST_SETTINGS_STATE.borrow(cs).borrow_mut();
+        let was = *last;
+        let now = if let Page::Settings(s) = state.page {
+            Some(s)
+        } else {
+            None
+        };
+        *last = now;
+        was != now && matches!(now, Some(SettingsMenuState::BrightnessAdjust))
+    });
+    if !matches!(state.page, Page::Settings(_)) {
+        brightness_edit_set(false);
+        critical_section::with(|cs| *BRIGHTNESS_LAST.borrow(cs).borrow_mut() = None);
+    } else {
+        // Within settings: clear brightness edit when not on brightness adjust page, and reset cache when entering adjust.
+        if !matches!(
+            state.page,
+            Page::Settings(SettingsMenuState::BrightnessAdjust)
+        ) {
+            brightness_edit_set(false);
+        }
+        if entering_brightness {
+            critical_section::with(|cs| *BRIGHTNESS_LAST.borrow(cs).borrow_mut() = None);
+        }
+    }
+    // Reset transform tracker (and free its background) when dialog is not active.
+    critical_section::with(|cs| {
+        *LAST_TRANSFORM_ACTIVE.borrow(cs).borrow_mut() = false;
+        *TRANSFORM_BG.borrow(cs).borrow_mut() = None;
+    });
+
+    match state.page {
+        Page::Main(menu_state) => {
+            match menu_state {
+                MainMenuState::Home => {
+                    // Draw the cached Omnitrix logo asset (no FB mirror)
+                    draw_cached_asset(disp, AssetId::Logo);
+                    draw_status_row(disp);
+                }
+                MainMenuState::WatchApp => {
+                    let _ = disp.clear(Rgb565::BLACK);
+                    draw_cached_asset(disp, AssetId::WatchIcon);
+                }
+                MainMenuState::TempApp => {
+                    draw_text(
+                        disp,
+                        "Temperature",
+                        Rgb565::WHITE,
+                        Some(Rgb565::BLACK),
+                        CENTER,
+                        CENTER,
+                        true,
+                        true,
+                        None,
+                    );
+                }
+                MainMenuState::FlashlightApp => {
+                    draw_text(
+                        disp,
+                        "Flashlight",
+                        Rgb565::WHITE,
+                        Some(Rgb565::BLACK),
+                        CENTER,
+                        CENTER,
+                        true,
+                        true,
+                        None,
+                    );
+                }
+                MainMenuState::LauncherApp => {
+                    draw_text(
+                        disp,
+                        "Launcher",
+                        Rgb565::WHITE,
+                        Some(Rgb565::BLACK),
+                        CENTER,
+                        CENTER,
+                        true,
+                        true,
+                        None,
+                    );
+                }
+                MainMenuState::SettingsApp => {
+                    let _ = disp.clear(Rgb565::BLACK);
+                    draw_cached_asset(disp, AssetId::SettingsImage);
+                }
+            }
+        }
+
+        Page::Settings(settings_state) => {
+            // The adjust view manages its own background (incremental arc updates)
+            if settings_state != SettingsMenuState::BrightnessAdjust {
+                draw_menu_backdrop(disp);
+            }
+            match settings_state {
+                SettingsMenuState::BrightnessPrompt => {
+                    // Backdrop is down, draw a simple white sun icon with label inside.
+                    let cx = CENTER;
+                    let cy = CENTER;
+                    let outer_r = 90;
+                    let ray_len = 42;
+                    let ray_thick = 6u8;
+                    let col = Rgb565::WHITE;
+                    // Circle + rays using embedded-graphics primitives.
+                    let _ = embedded_graphics::primitives::Circle::new(
+                        Point::new(cx - outer_r, cy - outer_r),
+                        (outer_r * 2) as u32,
+                    )
+                    .into_styled(PrimitiveStyle::with_stroke(col, 4))
+                    .draw(disp);
+                    for i in 0..8 {
+                        let ang = i as f32 * core::f32::consts::FRAC_PI_4;
+                        let dx = (cosf(ang) * (outer_r + 4) as f32) as i32;
+                        let dy = (sinf(ang) * (outer_r + 4) as f32) as i32;
+                        let tx = cx + dx;
+                        let ty = cy + dy;
+                        let rx = (cosf(ang) * (outer_r + ray_len) as f32) as i32 + cx;
+                        let ry = (sinf(ang) * (outer_r + ray_len) as f32) as i32 + cy;
+                        let _ = Line::new(Point::new(tx, ty), Point::new(rx, ry))
+                            .into_styled(PrimitiveStyle::with_stroke(col, ray_thick as u32))
+                            .draw(disp);
+                    }
+
+                    // two layers of text to fit the sun icon
+                    draw_text(
+                        disp,
+                        "Adjust",
+                        col,
+                        None,
+                        CENTER,
+                        CENTER - 8,
+                        false,
+                        false,
+                        None,
+                    );
+                    // second layer for better readability
+                    draw_text(
+                        disp,
+                        "Brightness",
+                        col,
+                        None,
+                        CENTER,
+                        CENTER + 8,
+                        false,
+                        false,
+                        None,
+                    );
+                }
+                SettingsMenuState::BrightnessAdjust => {
+                    draw_brightness_ui(disp);
+                }
+                SettingsMenuState::TempUnit => {
+                    let label = match temp_unit() {
+                        TempUnit::Celsius => "Celsius",
+                        TempUnit::Fahrenheit => "Fahrenheit",
+                    };
+                    draw_text(
+                        disp,
+                        "Temperature Unit",
+                        Rgb565::WHITE,
+                        None,
+                        CENTER,
+                        CENTER - 12,
+                        false,
+                        true,
+                        None,
+                    );
+                    draw_text(
+                        disp,
+                        label,
+                        Rgb565::CYAN,
+                        None,
+                        CENTER,
+                        CENTER + 12,
+                        false,
+                        true,
+                        None,
+                    );
+                }
+                SettingsMenuState::Carousel => {
+                    let secs = carousel_secs();
+                    let label = if secs == 0 {
+                        alloc::string::String::from("Off")
+                    } else {
+                        alloc::format!("Every {} s", secs)
+                    };
+                    draw_text(
+                        disp,
+                        "Omnitrix Carousel",
+                        Rgb565::WHITE,
+                        None,
+                        CENTER,
+                        CENTER - 12,
+                        false,
+                        true,
+                        None,
+                    );
+                    draw_text(
+                        disp,
+                        &label,
+                        Rgb565::CYAN,
+                        None,
+                        CENTER,
+                        CENTER + 12,
+                        false,
+                        true,
+                        None,
+                    );
+                }
+                SettingsMenuState::Animations => {
+                    let label = if animations_enabled() { "On" } else { "Off" };
+                    draw_text(
+                        disp,
+                        "Animations",
+                        Rgb565::WHITE,
+                        None,
+                        CENTER,
+                        CENTER - 12,
+                        false,
+                        true,
+                        None,
+                    );
+                    draw_text(
+                        disp,
+                        label,
+                        Rgb565::CYAN,
+                        None,
+                        CENTER,
+                        CENTER + 12,
+                        false,
+                        true,
+                        None,
+                    );
+                }
+                SettingsMenuState::ColonBlink => {
+                    let label = if colon_blink_enabled() { "On" } else { "Off" };
+                    draw_text(
+                        disp,
+                        "Blink Colon",
+                        Rgb565::WHITE,
+                        None,
+                        CENTER,
+                        CENTER - 12,
+                        false,
+                        true,
+                        None,
+                    );
+                    draw_text(
+                        disp,
+                        label,
+                        Rgb565::CYAN,
+                        None,
+                        CENTER,
+                        CENTER + 12,
+                        false,
+                        true,
+                        None,
+                    );
+                }
+                SettingsMenuState::ClockFormat => {
+                    let label = if clock_12h() { "12-hour" } else { "24-hour" };
+                    draw_text(
+                        disp,
+                        "Clock Format",
+                        Rgb565::WHITE,
+                        None,
+                        CENTER,
+                        CENTER - 12,
+                        false,
+                        true,
+                        None,
+                    );
+                    draw_text(
+                        disp,
+                        label,
+                        Rgb565::CYAN,
+                        None,
+                        CENTER,
+                        CENTER + 12,
+                        false,
+                        true,
+                        None,
+                    );
+                }
+                SettingsMenuState::ClockSeconds => {
+                    let label = if clock_seconds_enabled() {
+                        "HH:MM:SS"
+                    } else {
+                        "HH:MM"
+                    };
+                    draw_text(
+                        disp,
+                        "Show Seconds",
+                        Rgb565::WHITE,
+                        None,
+                        CENTER,
+                        CENTER - 12,
+                        false,
+                        true,
+                        None,
+                    );
+                    draw_text(
+                        disp,
+                        label,
+                        Rgb565::CYAN,
+                        None,
+                        CENTER,
+                        CENTER + 12,
+                        false,
+                        true,
+                        None,
+                    );
+                }
+                SettingsMenuState::DateFormat => {
+                    let label = match date_style() {
+                        DateStyle::Iso => "ISO  2024-05-14",
+                        DateStyle::Us => "US  05/14/2024",
+                        DateStyle::Eu => "EU  14/05/2024",
+                    };
+                    draw_text(
+                        disp,
+                        "Date Format",
+                        Rgb565::WHITE,
+                        None,
+                        CENTER,
+                        CENTER - 12,
+                        false,
+                        true,
+                        None,
+                    );
+                    draw_text(
+                        disp,
+                        label,
+                        Rgb565::CYAN,
+                        None,
+                        CENTER,
+                        CENTER + 12,
+                        false,
+                        true,
+                        None,
+                    );
+                }
+                SettingsMenuState::EncoderDir => {
+                    let label = match encoder_direction() {
+                        EncoderDirection::Normal => "Normal",
+                        EncoderDirection::Reversed => "Reversed",
+                    };
+                    draw_text(
+                        disp,
+                        "Encoder Direction",
+                        Rgb565::WHITE,
+                        None,
+                        CENTER,
+                        CENTER - 12,
+                        false,
+                        true,
+                        None,
+                    );
+                    draw_text(
+                        disp,
+                        label,
+                        Rgb565::CYAN,
+                        None,
+                        CENTER,
+                        CENTER + 12,
+                        false,
+                        true,
+                        None,
+                    );
+                }
+                SettingsMenuState::Wrist => {
+                    let label = match wrist() {
+                        Wrist::Left => "Left",
+                        Wrist::Right => "Right",
+                    };
+                    draw_text(
+                        disp,
+                        "Wrist",
+                        Rgb565::WHITE,
+                        None,
+                        CENTER,
+                        CENTER - 12,
+                        false,
+                        true,
+                        None,
+                    );
+                    draw_text(
+                        disp,
+                        label,
+                        Rgb565::CYAN,
+                        None,
+                        CENTER,
+                        CENTER + 12,
+                        false,
+                        true,
+                        None,
+                    );
+                }
+                SettingsMenuState::AutoRotate => {
+                    let label = if auto_rotate_enabled() { "On" } else { "Off" };
+                    draw_text(
+                        disp,
+                        "Auto Rotate",
+                        Rgb565::WHITE,
+                        None,
+                        CENTER,
+                        CENTER - 12,
+                        false,
+                        true,
+                        None,
+                    );
+                    draw_text(
+                        disp,
+                        label,
+                        Rgb565::CYAN,
+                        None,
+                        CENTER,
+                        CENTER + 12,
+                        false,
+                        true,
+                        None,
+                    );
+                }
+                SettingsMenuState::AlwaysOn => {
+                    let label = if always_on_enabled() { "On" } else { "Off" };
+                    draw_text(
+                        disp,
+                        "Always-On Dim",
+                        Rgb565::WHITE,
+                        None,
+                        CENTER,
+                        CENTER - 12,
+                        false,
+                        true,
+                        None,
+                    );
+                    draw_text(
+                        disp,
+                        label,
+                        Rgb565::CYAN,
+                        None,
+                        CENTER,
+                        CENTER + 12,
+                        false,
+                        true,
+                        None,
+                    );
+                }
+                SettingsMenuState::ScreenTimeout => {
+                    let secs = screen_timeout_secs();
+                    let label = if secs == 0 {
+                        alloc::string::String::from("Never")
                     } else {
-                        let _ = disp.clear(Rgb565::BLACK);
-                    }
+                        alloc::format!("After {} s", secs)
+                    };
+                    draw_text(
+                        disp,
+                        "Screen Timeout",
+                        Rgb565::WHITE,
+                        None,
+                        CENTER,
+                        CENTER - 12,
+                        false,
+                        true,
+                        None,
+                    );
+                    draw_text(
+                        disp,
+                        &label,
+                        Rgb565::CYAN,
+                        None,
+                        CENTER,
+                        CENTER + 12,
+                        false,
+                        true,
+                        None,
+                    );
                 }
-
-                draw_transform_overlay(disp);
-            }
-        }
-        return;
-    }
-
-    // Reset watch-state tracker if we’re not on the Watch page.
-    if !matches!(state.page, Page::Watch(_)) {
-        critical_section::with(|cs| {
-            *LAST_WATCH_STATE.borrow(cs).borrow_mut() = None;
-            *WATCH_BG.borrow(cs).borrow_mut() = None; // free background when leaving watch page
-            *LAST_WATCH_EDIT_ACTIVE.borrow(cs).borrow_mut() = false;
-        });
-    }
-    let entering_brightness = critical_section::with(|cs| {
-        let mut last = LAST_SETTINGS_STATE.borrow(cs).borrow_mut();
-        let was = *last;
-        let now = if let Page::Settings(s) = state.page {
-            Some(s)
-        } else {
-            None
-        };
-        *last = now;
-        was != now && matches!(now, Some(SettingsMenuState::BrightnessAdjust))
-    });
-    if !matches!(state.page, Page::Settings(_)) {
-        brightness_edit_set(false);
-        critical_section::with(|cs| *BRIGHTNESS_LAST.borrow(cs).borrow_mut() = None);
-    } else {
-        // Within settings: clear brightness edit when not on brightness adjust page, and reset cache when entering adjust.
-        if !matches!(
-            state.page,
-            Page::Settings(SettingsMenuState::BrightnessAdjust)
-        ) {
-            brightness_edit_set(false);
-        }
-        if entering_brightness {
-            critical_section::with(|cs| *BRIGHTNESS_LAST.borrow(cs).borrow_mut() = None);
-        }
-    }
-    // Reset transform tracker when dialog is not active.
-    critical_section::with(|cs| {
-        *LAST_TRANSFORM_ACTIVE.borrow(cs).borrow_mut() = false;
-    });
-
-    match state.page {
-        Page::Main(menu_state) => {
-            match menu_state {
-                MainMenuState::Home => {
-                    // Draw the cached Omnitrix logo asset (no FB mirror)
-                    if let Some((buf, w, h)) = get_cached_asset(AssetId::Logo) {
-                        draw_image_bytes(disp, buf, w, h, false, false);
-                    } else if precache_asset(AssetId::Logo) {
-                        if let Some((buf, w, h)) = get_cached_asset(AssetId::Logo) {
-                            draw_image_bytes(disp, buf, w, h, false, false);
-                        }
-                    }
+                SettingsMenuState::BrightnessLimits => {
+                    let (min, max) = brightness_limits();
+                    let label = alloc::format!("{}% - {}%", min, max);
+                    draw_text(
+                        disp,
+                        "Brightness Range",
+                        Rgb565::WHITE,
+                        None,
+                        CENTER,
+                        CENTER - 12,
+                        false,
+                        true,
+                        None,
+                    );
+                    draw_text(
+                        disp,
+                        &label,
+                        Rgb565::CYAN,
+                        None,
+                        CENTER,
+                        CENTER + 12,
+                        false,
+                        true,
+                        None,
+                    );
                 }
-                MainMenuState::WatchApp => {
-                    let _ = disp.clear(Rgb565::BLACK);
-                    if let Some((bytes, w, h)) = get_cached_asset(AssetId::WatchIcon) {
-                        draw_image_bytes(disp, bytes, w, h, false, false);
-                    } else if precache_asset(AssetId::WatchIcon) {
-                        if let Some((bytes, w, h)) = get_cached_asset(AssetId::WatchIcon) {
-                            draw_image_bytes(disp, bytes, w, h, false, false);
-                        }
-                    }
+                SettingsMenuState::LowBattery => {
+                    let (warn, critical) = battery_thresholds();
+                    let label = alloc::format!("Dim {}% / Sleep {}%", warn, critical);
+                    draw_text(
+                        disp,
+                        "Low Battery",
+                        Rgb565::WHITE,
+                        None,
+                        CENTER,
+                        CENTER - 12,
+                        false,
+                        true,
+                        None,
+                    );
+                    draw_text(
+                        disp,
+                        &label,
+                        Rgb565::CYAN,
+                        None,
+                        CENTER,
+                        CENTER + 12,
+                        false,
+                        true,
+                        None,
+                    );
                 }
-                MainMenuState::SettingsApp => {
-                    let _ = disp.clear(Rgb565::BLACK);
-                    if let Some((bytes, w, h)) = get_cached_asset(AssetId::SettingsImage) {
-                        draw_image_bytes(disp, bytes, w, h, false, false);
-                    } else if precache_asset(AssetId::SettingsImage) {
-                        if let Some((bytes, w, h)) = get_cached_asset(AssetId::SettingsImage) {
-                            draw_image_bytes(disp, bytes, w, h, false, false);
-                        }
-                    }
+                SettingsMenuState::WakeSource => {
+                    draw_text(
+                        disp,
+                        "Wake Source",
+                        Rgb565::WHITE,
+                        None,
+                        CENTER,
+                        CENTER - 12,
+                        false,
+                        true,
+                        None,
+                    );
+                    draw_text(
+                        disp,
+                        wake_sources().label(),
+                        Rgb565::CYAN,
+                        None,
+                        CENTER,
+                        CENTER + 12,
+                        false,
+                        true,
+                        None,
+                    );
                 }
-            }
-        }
-
-        Page::Settings(settings_state) => match settings_state {
-            SettingsMenuState::BrightnessPrompt => {
-                // Clear the screen, then draw a simple white sun icon with label inside.
-                let _ = disp.clear(Rgb565::BLACK);
-                let cx = CENTER;
-                let cy = CENTER;
-                let outer_r = 90;
-                let ray_len = 42;
-                let ray_thick = 6u8;
-                let col = Rgb565::WHITE;
-                // Circle + rays using embedded-graphics primitives.
-                let _ = embedded_graphics::primitives::Circle::new(
-                    Point::new(cx - outer_r, cy - outer_r),
-                    (outer_r * 2) as u32,
-                )
-                .into_styled(PrimitiveStyle::with_stroke(col, 4))
-                .draw(disp);
-                for i in 0..8 {
-                    let ang = i as f32 * core::f32::consts::FRAC_PI_4;
-                    let dx = (cosf(ang) * (outer_r + 4) as f32) as i32;
-                    let dy = (sinf(ang) * (outer_r + 4) as f32) as i32;
-                    let tx = cx + dx;
-                    let ty = cy + dy;
-                    let rx = (cosf(ang) * (outer_r + ray_len) as f32) as i32 + cx;
-                    let ry = (sinf(ang) * (outer_r + ray_len) as f32) as i32 + cy;
-                    let _ = Line::new(Point::new(tx, ty), Point::new(rx, ry))
-                        .into_styled(PrimitiveStyle::with_stroke(col, ray_thick as u32))
-                        .draw(disp);
+                SettingsMenuState::WakeAlarm => {
+                    let label = match wake_alarm_at() {
+                        Some(at) => alloc::format!("{:02}:{:02}", at / 3600, at / 60 % 60),
+                        None => alloc::string::String::from("Off"),
+                    };
+                    draw_text(
+                        disp,
+                        "Wake Alarm",
+                        Rgb565::WHITE,
+                        None,
+                        CENTER,
+                        CENTER - 12,
+                        false,
+                        true,
+                        None,
+                    );
+                    draw_text(
+                        disp,
+                        &label,
+                        Rgb565::CYAN,
+                        None,
+                        CENTER,
+                        CENTER + 12,
+                        false,
+                        true,
+                        None,
+                    );
+                }
+                SettingsMenuState::TapNav => {
+                    draw_text(
+                        disp,
+                        "Tap Navigation",
+                        Rgb565::WHITE,
+                        None,
+                        CENTER,
+                        CENTER - 12,
+                        false,
+                        true,
+                        None,
+                    );
+                    draw_text(
+                        disp,
+                        tap_cadence().label(),
+                        Rgb565::CYAN,
+                        None,
+                        CENTER,
+                        CENTER + 12,
+                        false,
+                        true,
+                        None,
+                    );
+                }
+                SettingsMenuState::SmashCal => {
+                    draw_text(
+                        disp,
+                        "Calibrate Smash",
+                        Rgb565::WHITE,
+                        None,
+                        CENTER,
+                        CENTER - 12,
+                        false,
+                        true,
+                        None,
+                    );
+                    draw_text(
+                        disp,
+                        "Select, then hold still",
+                        Rgb565::CYAN,
+                        None,
+                        CENTER,
+                        CENTER + 12,
+                        false,
+                        true,
+                        Some(&FONT_6X10),
+                    );
+                }
+                SettingsMenuState::EasterEgg => {
+                    draw_text(
+                        disp,
+                        "Easter Egg",
+                        Rgb565::WHITE,
+                        None,
+                        CENTER,
+                        CENTER,
+                        false,
+                        true,
+                        None,
+                    );
                 }
-
-                // two layers of text to fit the sun icon
-                draw_text(
-                    disp,
-                    "Adjust",
-                    col,
-                    Some(Rgb565::BLACK),
-                    CENTER,
-                    CENTER - 8,
-                    false,
-                    false,
-                    None,
-                );
-                // second layer for better readability
-                draw_text(
-                    disp,
-                    "Brightness",
-                    col,
-                    Some(Rgb565::BLACK),
-                    CENTER,
-                    CENTER + 8,
-                    false,
-                    false,
-                    None,
-                );
-            }
-            SettingsMenuState::BrightnessAdjust => {
-                draw_brightness_ui(disp);
-            }
-            SettingsMenuState::EasterEgg => {
-                draw_text(
-                    disp,
-                    "Easter Egg",
-                    Rgb565::WHITE,
-                    Some(Rgb565::BLACK),
-                    CENTER,
-                    CENTER,
-                    true,
-                    true,
-                    None,
-                );
             }
-        },
+        }
 
         Page::Watch(watch_state) => {
             // If watch mode changed, repaint face and reset cache.
@@ -2097,6 +5899,7 @@ pub fn update_ui(disp: &mut impl PanelRgb565, state: UiState, redraw: bool) {
                 critical_section::with(|cs| {
                     *HAND_CACHE.borrow(cs).borrow_mut() = HandCache::new();
                 });
+                digit_roll_reset();
             }
 
             // If time was changed, repaint face and reset cache.
@@ -2121,11 +5924,15 @@ pub fn update_ui(disp: &mut impl PanelRgb565, state: UiState, redraw: bool) {
                 critical_section::with(|cs| {
                     *HAND_CACHE.borrow(cs).borrow_mut() = HandCache::new();
                 });
+                digit_roll_reset();
             }
 
             match watch_state {
                 WatchAppState::Analog => {
                     draw_analog_clock(disp);
+                    if !clock_reliable() {
+                        draw_clock_unset_badge(disp);
+                    }
                 }
                 WatchAppState::Digital => {
                     // Draw either time or edit state
@@ -2151,21 +5958,15 @@ pub fn update_ui(disp: &mut impl PanelRgb565, state: UiState, redraw: bool) {
 
                     // Draw either edit UI or current time
                     if let Some(ed) = edit {
+                        digit_roll_reset();
                         draw_clock_edit(disp, ed);
                     } else {
-                        let mut buf = [b'0'; 5];
-                        let msg = format_clock_hm(&mut buf);
-                        draw_text(
-                            disp,
-                            msg,
-                            Rgb565::CYAN,
-                            Some(Rgb565::BLACK),
-                            CENTER,
-                            CENTER,
-                            false,
-                            true,
-                            None,
-                        );
+                        draw_digital_clock(disp);
+                        #[cfg(feature = "esp32s3-disp143Oled")]
+                        draw_date_line(disp);
+                        if !clock_reliable() {
+                            draw_clock_unset_badge(disp);
+                        }
                     }
                 }
             }
@@ -2176,23 +5977,49 @@ pub fn update_ui(disp: &mut impl PanelRgb565, state: UiState, redraw: bool) {
             // Note that we do not clear here, but before entering a clear happens, it is handled above for efficiency
             // Clear is necessary as the alien images don't cover the full screen
             let aid = asset_id_for_state(omnitrix_state);
-            if let Some((bytes, w, h)) = get_cached_asset(aid) {
-                draw_image_bytes(disp, bytes, w, h, false, false);
-                // esp_println::println!("Omnitrix: drew cached image");
-            } else if precache_asset(aid) {
-                if let Some((bytes, w, h)) = get_cached_asset(aid) {
-                    draw_image_bytes(disp, bytes, w, h, false, false);
-                }
+            draw_cached_asset(disp, aid);
+        }
+
+        Page::Temperature => {
+            draw_temperature_page(disp);
+        }
+
+        Page::SmashCalibration => {
+            draw_smash_cal_page(disp);
+        }
+
+        Page::Service => {
+            draw_service_page(disp);
+        }
+
+        Page::Launcher(selected) => {
+            draw_launcher_page(disp, selected);
+        }
+
+        // Plain white torch; brightness is forced to max by the main loop while here.
+        // Only redrawn on entry, nothing else changes on this page.
+        Page::Flashlight => {
+            if let Some(co) = disp.fast_panel() {
+                let _ = co.fill_rect_solid_no_fb(
+                    0,
+                    0,
+                    RESOLUTION as u16,
+                    RESOLUTION as u16,
+                    Rgb565::WHITE,
+                );
+            } else {
+                let _ = disp.clear(Rgb565::WHITE);
             }
         }
 
         Page::EasterEgg => {
             // Draw info page image by decompressing on demand (no cache).
-            let need = (466 * 466 * 2) as usize;
-            if let Ok(buf) = decompress_to_vec_zlib_with_limit(INFO_PAGE_IMAGE, need) {
-                if buf.len() == need {
+            match decode_asset_expect(INFO_PAGE_IMAGE, 466, 466) {
+                Ok(buf) => {
                     draw_image_bytes(disp, &buf, 466, 466, false, false);
-                } else {
+                }
+                Err(e) => {
+                    esp_println::println!("info page decode failed: {:?}", e);
                     disp.clear(Rgb565::WHITE).ok();
                     draw_text(
                         disp,
@@ -2206,20 +6033,34 @@ pub fn update_ui(disp: &mut impl PanelRgb565, state: UiState, redraw: bool) {
                         None,
                     );
                 }
-            } else {
-                disp.clear(Rgb565::WHITE).ok();
-                draw_text(
-                    disp,
-                    "Info Screen",
-                    Rgb565::CYAN,
-                    None,
-                    CENTER,
-                    CENTER,
-                    false,
-                    true,
-                    None,
-                );
             }
+            // IMU health, the same line as on the Service page
+            draw_text(
+                disp,
+                &alloc::format!("Sensor {}", sensor_health().label()),
+                Rgb565::CYAN,
+                Some(Rgb565::BLACK),
+                CENTER,
+                CENTER + 180,
+                false,
+                true,
+                None,
+            );
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::hour_12;
+
+    #[test]
+    fn hour_12_midnight_and_noon() {
+        assert_eq!(hour_12(0), (12, false)); // 00:xx -> 12 AM
+        assert_eq!(hour_12(1), (1, false));
+        assert_eq!(hour_12(11), (11, false));
+        assert_eq!(hour_12(12), (12, true)); // 12:xx -> 12 PM
+        assert_eq!(hour_12(13), (1, true));
+        assert_eq!(hour_12(23), (11, true));
+    }
+}
@@ -16,7 +16,9 @@ use esp_hal::{
 use esp_hal::peripherals::{GPIO10, GPIO11};
 
 #[cfg(feature = "esp32s3-disp143Oled")]
-use esp_hal::peripherals::{DMA_CH0, GPIO10, GPIO11, GPIO12, GPIO13, GPIO14, GPIO47, GPIO48, LPWR};
+use esp_hal::peripherals::{
+    ADC1, DMA_CH0, GPIO10, GPIO11, GPIO12, GPIO13, GPIO14, GPIO4, GPIO47, GPIO48, LPWR, USB_DEVICE,
+};
 
 pub struct BoardPins<'a> {
     // Leds
@@ -35,7 +37,9 @@ pub struct BoardPins<'a> {
     // IMU interrupt (active-low on GPIO8 per Waveshare schematic)
     #[cfg(feature = "esp32s3-disp143Oled")]
     pub imu_int: Input<'a>,
-    // pub enc_sw:  Input<'a>,  // not used in this example
+    // Rotary encoder push switch (active-low)
+    #[cfg(feature = "esp32s3-disp143Oled")]
+    pub enc_sw: Input<'a>,
 
     // display-related pins are feature gated
     #[cfg(any(feature = "devkit-esp32s3-disp128"))]
@@ -49,6 +53,16 @@ pub struct BoardPins<'a> {
     // RTC peripheral for deep sleep
     #[cfg(feature = "esp32s3-disp143Oled")]
     pub lpwr: LPWR<'a>,
+
+    // Battery voltage sense (BAT_ADC, behind the board's divider) and the ADC reading it
+    #[cfg(feature = "esp32s3-disp143Oled")]
+    pub bat_adc: GPIO4<'a>,
+    #[cfg(feature = "esp32s3-disp143Oled")]
+    pub adc1: ADC1<'a>,
+
+    // USB-Serial-JTAG, used for the serial command console
+    #[cfg(feature = "esp32s3-disp143Oled")]
+    pub usb_device: USB_DEVICE<'a>,
 }
 
 // nested, feature-only struct for LCD/SPI pins
@@ -79,6 +93,9 @@ pub struct DisplayPins<'a> {
     pub rst: Output<'a>,      // GPIO21
     pub en: Output<'a>,       // GPIO42
     pub dma_ch0: DMA_CH0<'a>, // <- DMA channel for SPI2
+    // Panel TE (tearing effect) output. The Waveshare 1.43" board doesn't route the TE pad
+    // of the panel FPC to any GPIO, so this is None there; set it if the line is wired.
+    pub te: Option<Input<'a>>,
 }
 
 #[cfg(feature = "esp32s3-disp143Oled")]
@@ -175,6 +192,9 @@ pub fn init_board_pins<'a>(p: Peripherals) -> (Io<'a>, BoardPins<'a>, I2C0<'a>)
     let mut enc_dt = Input::new(p.GPIO17, InputConfig::default().with_pull(Pull::None)); //was 3
     enc_clk.listen(Event::AnyEdge);
     enc_dt.listen(Event::AnyEdge);
+    // encoder push switch to ground, on a pin the board leaves free
+    let mut enc_sw = Input::new(p.GPIO18, InputConfig::default().with_pull(Pull::Up));
+    enc_sw.listen(Event::AnyEdge);
 
     // OLED control pins
     let cs = Output::new(p.GPIO9, Level::High, OutputConfig::default());
@@ -221,6 +241,7 @@ pub fn init_board_pins<'a>(p: Peripherals) -> (Io<'a>, BoardPins<'a>, I2C0<'a>)
             enc_clk,
             enc_dt,
             imu_int,
+            enc_sw,
             display_pins: DisplayPins {
                 spi2,
                 cs,
@@ -232,12 +253,16 @@ pub fn init_board_pins<'a>(p: Peripherals) -> (Io<'a>, BoardPins<'a>, I2C0<'a>)
                 rst,
                 en,
                 dma_ch0,
+                te: None,
             },
             imu_i2c: ImuI2cPins {
                 sda: imu_sda,
                 scl: imu_scl,
             },
             lpwr: p.LPWR,
+            bat_adc: p.GPIO4,
+            adc1: p.ADC1,
+            usb_device: p.USB_DEVICE,
         },
         i2c0,
     )